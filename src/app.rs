@@ -5,6 +5,8 @@ use crate::components::footer::Footer;
 use crate::components::sidebar::Sidebar;
 use crate::components::guide::Guide;
 use crate::components::file_upload::FileUpload;
+use crate::components::paste_area::PasteArea;
+use crate::components::filter_builder::FilterBuilder;
 use crate::components::info_section::InfoSection;
 use crate::state::AppData;
 use crate::pages::{
@@ -24,7 +26,7 @@ use crate::pages::{
 pub fn App() -> impl IntoView {
     // Provide global state
     let app_data = AppData::new();
-    provide_context(app_data.clone());
+    provide_context(app_data);
 
     view! {
         <Router base="/easy_stat_r">
@@ -45,6 +47,8 @@ pub fn App() -> impl IntoView {
                         // Let's keep it persistent for now as data state is global.
                         <div class="fade-in">
                             <FileUpload/>
+                            <PasteArea/>
+                            <FilterBuilder/>
                         </div>
                         
                         <Routes>
@@ -76,7 +80,7 @@ fn Home() -> impl IntoView {
         <div>
             // Display Data Info, DataFrame Preview, and Summary Statistics
             {move || {
-                if let Some(df) = app_data.df.get() {
+                if let Some(df) = app_data.df() {
                     let shape = df.shape();
                     let column_names: Vec<String> = df.get_column_names()
                         .iter()
@@ -0,0 +1,264 @@
+//! Command-line front end for the native binary.
+//!
+//! Loads a CSV or spreadsheet file through the same path as the upload component and
+//! prints the analysis result as text (the same tables as the pages) or JSON.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use crate::stats::{anova, factor, pca, regression, ttest};
+use crate::utils::csv_import::{CsvOptions, TextEncoding};
+use crate::utils::excel_to_polars::SheetOptions;
+use crate::utils::file_loader::{read_dataframe_with_report, ImportOptions};
+
+pub const USAGE: &str = "\
+Usage: easy_stat_r-server <command> --file <path> [options]
+
+Commands:
+  ttest        --value <col> --group <col>          Welch's t-test
+  paired       --pre <col> --post <col>             Paired t-test
+  anova        --value <col> --factor <col> [--factor2 <col>]
+                                                    One-way / two-way (Type II) ANOVA
+  regression   --target <col> --explanatory <col,...>
+                                                    OLS regression
+  pca          [--columns <col,...>]                Principal component analysis
+  factor       [--columns <col,...>] [--factors <n>] [--no-rotation]
+                                                    Factor analysis (principal component method)
+  columns                                           List column names and types
+
+Options:
+  --file <path>       CSV/TSV, Excel (.xlsx/.xls/.xlsb), OpenDocument (.ods), JSON/NDJSON or Parquet file
+  --sheet <name>      Worksheet to read (default: the first sheet)
+  --header-row <n>    1-based row number of the header row
+  --range <A1:F20>    Only read this cell range
+  --delimiter <c>     CSV delimiter, e.g. ';' or tab (default: detected)
+  --encoding <name>   utf-8, shift_jis, euc-jp, utf-16le or utf-16be (default: detected)
+  --quote <c>         CSV quote character, or none (default: \")
+  --decimal-comma     Read 1,5 as 1.5 in CSV files
+  --na <token,...>    CSV values read as missing (default: NA)
+  --format <fmt>      text (default) or json
+  -h, --help          Show this help
+
+--columns defaults to every numeric column.";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Parsed command line: a subcommand, `--key value` options and bare `--flag`s.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub command: String,
+    options: HashMap<String, Vec<String>>,
+    flags: Vec<String>,
+}
+
+const FLAGS: &[&str] = &["no-rotation", "decimal-comma", "help"];
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut iter = args.into_iter();
+        while let Some(arg) = iter.next() {
+            if arg == "-h" {
+                parsed.flags.push("help".to_string());
+            } else if let Some(key) = arg.strip_prefix("--") {
+                if FLAGS.contains(&key) {
+                    parsed.flags.push(key.to_string());
+                } else {
+                    let value = iter.next().ok_or_else(|| anyhow!("Missing value for --{}", key))?;
+                    parsed.options.entry(key.to_string()).or_default().push(value);
+                }
+            } else if parsed.command.is_empty() {
+                parsed.command = arg;
+            } else {
+                return Err(anyhow!("Unexpected argument: {}", arg));
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).and_then(|v| v.last()).map(|s| s.as_str())
+    }
+
+    pub fn required(&self, name: &str) -> Result<&str> {
+        self.get(name).ok_or_else(|| anyhow!("Missing required option --{}", name))
+    }
+
+    /// Comma-separated and/or repeated list option.
+    pub fn list(&self, name: &str) -> Vec<String> {
+        self.options
+            .get(name)
+            .into_iter()
+            .flatten()
+            .flat_map(|v| v.split(','))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Workbook selection from `--sheet`, `--header-row` and `--range`.
+    pub fn sheet_options(&self) -> Result<SheetOptions> {
+        let header_row = match self.get("header-row") {
+            Some(v) => Some(v.parse().map_err(|_| anyhow!("--header-row must be a number"))?),
+            None => None,
+        };
+        Ok(SheetOptions {
+            sheet: self.get("sheet").map(str::to_string),
+            header_row,
+            range: self.get("range").map(str::to_string),
+        })
+    }
+
+    /// CSV settings from `--delimiter`, `--encoding`, `--quote`, `--decimal-comma` and `--na`.
+    pub fn csv_options(&self) -> Result<CsvOptions> {
+        let single_char = |name: &str, value: &str| -> Result<char> {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(anyhow!("--{} must be a single character", name)),
+            }
+        };
+        let delimiter = match self.get("delimiter") {
+            Some("tab") | Some("\\t") => Some('\t'),
+            Some(v) => Some(single_char("delimiter", v)?),
+            None => None,
+        };
+        let encoding = match self.get("encoding") {
+            Some(v) => Some(TextEncoding::from_label(v).ok_or_else(|| anyhow!("Unknown encoding: {}", v))?),
+            None => None,
+        };
+        let quote = match self.get("quote") {
+            Some("none") => None,
+            Some(v) => Some(single_char("quote", v)?),
+            None => Some('"'),
+        };
+        let defaults = CsvOptions::default();
+        Ok(CsvOptions {
+            delimiter,
+            encoding,
+            quote,
+            decimal_comma: self.flag("decimal-comma"),
+            na_values: if self.options.contains_key("na") { self.list("na") } else { defaults.na_values },
+            ..defaults
+        })
+    }
+
+    pub fn import_options(&self) -> Result<ImportOptions> {
+        Ok(ImportOptions { sheet: self.sheet_options()?, csv: self.csv_options()? })
+    }
+
+    pub fn format(&self) -> Result<OutputFormat> {
+        match self.get("format").unwrap_or("text") {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(anyhow!("Unknown format: {} (expected text or json)", other)),
+        }
+    }
+}
+
+/// Loads the file; cells set to null by type inference are reported on stderr.
+pub fn load_file(path: &str, options: &ImportOptions) -> Result<DataFrame> {
+    let bytes = std::fs::read(path).map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+    let (df, report) = read_dataframe_with_report(path, &bytes, options)?;
+    for line in report.summary_lines(5) {
+        eprintln!("Warning: {}", line);
+    }
+    Ok(df)
+}
+
+fn numeric_column_names(df: &DataFrame) -> Vec<String> {
+    df.get_columns()
+        .iter()
+        .filter(|s| s.dtype().is_numeric())
+        .map(|s| s.name().to_string())
+        .collect()
+}
+
+fn render<T: serde::Serialize>(result: &T, text: String, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Text => Ok(text),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(result)?),
+    }
+}
+
+/// Runs one command and returns the text to print on stdout.
+pub fn run(args: &Args) -> Result<String> {
+    if args.flag("help") || args.command.is_empty() {
+        return Ok(USAGE.to_string());
+    }
+    let format = args.format()?;
+    let df = load_file(args.required("file")?, &args.import_options()?)?;
+
+    match args.command.as_str() {
+        "ttest" => {
+            let res = ttest::welch_from_df(&df, args.required("value")?, args.required("group")?)?;
+            render(&res, res.report().to_text(), format)
+        }
+        "paired" => {
+            let res = ttest::paired_from_df(&df, args.required("pre")?, args.required("post")?)?;
+            render(&res, res.report().to_text(), format)
+        }
+        "anova" => {
+            let value = args.required("value")?;
+            let f1 = args.required("factor")?;
+            match args.get("factor2") {
+                Some(f2) => {
+                    let res = anova::two_way_from_df(&df, value, f1, f2)?;
+                    render(&res, res.report().to_text(), format)
+                }
+                None => {
+                    let res = anova::one_way_from_df(&df, value, f1)?;
+                    render(&res, res.report().to_text(), format)
+                }
+            }
+        }
+        "regression" => {
+            let expl = args.list("explanatory");
+            let expl_refs: Vec<&str> = expl.iter().map(|s| s.as_str()).collect();
+            let res = regression::ols_from_df(&df, args.required("target")?, &expl_refs)?;
+            render(&res, res.report().to_text(), format)
+        }
+        "pca" | "factor" => {
+            let mut cols = args.list("columns");
+            if cols.is_empty() {
+                cols = numeric_column_names(&df);
+            }
+            let col_refs: Vec<&str> = cols.iter().map(|s| s.as_str()).collect();
+            if args.command == "pca" {
+                let res = pca::pca_from_df(&df, &col_refs)?;
+                render(&res, res.report().to_text(), format)
+            } else {
+                let n_factors = match args.get("factors") {
+                    Some(v) => v.parse().map_err(|_| anyhow!("--factors must be a number"))?,
+                    None => 2,
+                };
+                let rotation = if args.flag("no-rotation") {
+                    factor::Rotation::None
+                } else {
+                    factor::Rotation::Varimax
+                };
+                let res = factor::factor_analysis_from_df(&df, &col_refs, n_factors, rotation)?;
+                render(&res, res.report().to_text(), format)
+            }
+        }
+        "columns" => {
+            let cols: Vec<(String, String)> = df
+                .get_columns()
+                .iter()
+                .map(|s| (s.name().to_string(), s.dtype().to_string()))
+                .collect();
+            let lines: Vec<String> = cols.iter().map(|(n, t)| format!("{}\t{}", n, t)).collect();
+            render(&cols, lines.join("\n"), format)
+        }
+        other => Err(anyhow!("Unknown command: {}\n\n{}", other, USAGE)),
+    }
+}
@@ -0,0 +1,154 @@
+use leptos::*;
+use crate::state::AppData;
+use crate::utils::binning::{bin_column, BinMethod, BinSpec};
+use crate::utils::recipe::Step;
+
+const METHODS: [&str; 4] = ["width", "quantile", "breaks", "median"];
+
+fn method(key: &str, bins: usize, breaks: &str) -> BinMethod {
+    match key {
+        "quantile" => BinMethod::Quantiles(bins),
+        // Unparsable entries are skipped; too few breaks is reported by the binning itself
+        "breaks" => BinMethod::Breaks(breaks.split([',', '、', ' ']).filter_map(|b| b.trim().parse().ok()).collect()),
+        "median" => BinMethod::MedianSplit,
+        _ => BinMethod::EqualWidth(bins),
+    }
+}
+
+/// Cuts a numeric column of the page's dataset into groups and adds them as
+/// an ordered categorical column (e.g. a factor for ANOVA or chi-square).
+#[component]
+pub fn BinningPanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let numeric = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| {
+            df.get_columns().iter().filter(|s| s.dtype().is_numeric()).map(|s| s.name().to_string()).collect::<Vec<_>>()
+        }).unwrap_or_default())
+    });
+    // Empty: the first numeric column
+    let column_choice = create_rw_signal(String::new());
+    let column = create_memo(move |_| {
+        let choice = column_choice.get();
+        numeric.with(|c| if c.contains(&choice) { choice } else { c.first().cloned().unwrap_or_default() })
+    });
+    let method_key = create_rw_signal("quantile".to_string());
+    let bins = create_rw_signal(4usize);
+    let breaks = create_rw_signal(String::new());
+    let into = create_rw_signal(String::new());
+    let labels = create_rw_signal(String::new());
+    let descending = create_rw_signal(false);
+    let (outcome, set_outcome) = create_signal(Option::<Result<String, String>>::None);
+
+    let spec = create_memo(move |_| {
+        let column = column.get();
+        let name = into.get();
+        BinSpec {
+            into: if name.trim().is_empty() { format!("{}_群", column) } else { name },
+            method: method(&method_key.get(), bins.get(), &breaks.get()),
+            labels: labels.with(|l| l.split([',', '、']).map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect()),
+            descending: descending.get(),
+            column,
+        }
+    });
+    let preview = create_memo(move |_| {
+        let spec = spec.get();
+        df.with(|df| df.as_ref().map(|df| bin_column(df, &spec).map(|(_, report)| report).map_err(|e| e.to_string())))
+    });
+
+    let apply = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let spec = spec.get_untracked();
+        match bin_column(&current.df, &spec) {
+            Ok((df, report)) => {
+                let message = format!("列「{}」を追加しました ({} 群)。", spec.into.trim(), report.counts.len());
+                app_data.apply_step(&current.name, Step::Bin { spec }, df);
+                into.set(String::new());
+                labels.set(String::new());
+                set_outcome.set(Some(Ok(message)));
+            }
+            Err(e) => set_outcome.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"数値の区切り (群分け)"</h3>
+            {move || (!numeric.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"列"</label>
+                        <select on:change=move |ev| column_choice.set(event_target_value(&ev))>
+                            {move || numeric.get().into_iter().map(|c| {
+                                let selected = c == column.get();
+                                view! { <option value=c.clone() selected=selected>{c}</option> }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </div>
+                    <div class="input-group">
+                        <label>"区切り方"</label>
+                        <select on:change=move |ev| method_key.set(event_target_value(&ev))>
+                            {METHODS.into_iter().map(|key| view! {
+                                <option value=key selected=move || method_key.get() == key>{method(key, 0, "").label()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        {move || match method_key.get().as_str() {
+                            "width" | "quantile" => view! {
+                                <label>"群の数"</label>
+                                <input type="number" min="2" style="width: 5rem;" prop:value=move || bins.get().to_string()
+                                    on:input=move |ev| if let Ok(n) = event_target_value(&ev).parse() { bins.set(n) }/>
+                            }.into_view(),
+                            "breaks" => view! {
+                                <input type="text" placeholder="区切り値 (例: 0, 40, 60, 80, 100)" prop:value=move || breaks.get()
+                                    on:input=move |ev| breaks.set(event_target_value(&ev))/>
+                            }.into_view(),
+                            _ => view! { <div/> }.into_view(),
+                        }}
+                    </div>
+                    <div class="input-group">
+                        <label>"群のラベル (低い群から、カンマ区切り。空欄なら区間を表示)"</label>
+                        <input type="text" prop:value=move || labels.get() on:input=move |ev| labels.set(event_target_value(&ev))/>
+                    </div>
+                    <div class="input-group">
+                        <label>"水準の順序"</label>
+                        <select on:change=move |ev| descending.set(event_target_value(&ev) == "desc")>
+                            <option value="asc" selected=move || !descending.get()>"低い群から"</option>
+                            <option value="desc" selected=move || descending.get()>"高い群から"</option>
+                        </select>
+                    </div>
+                    <div class="input-group">
+                        <label>"新しい列の名前"</label>
+                        <input type="text" placeholder=move || format!("{}_群", column.get()) prop:value=move || into.get()
+                            on:input=move |ev| into.set(event_target_value(&ev))/>
+                    </div>
+
+                    {move || match preview.get() {
+                        Some(Ok(report)) => view! {
+                            <div>
+                                <table class="dataframe">
+                                    <thead><tr><th>"群"</th><th>"行数"</th></tr></thead>
+                                    <tbody>
+                                        {report.labels.iter().zip(&report.counts).map(|(label, n)| view! {
+                                            <tr><td>{label.clone()}</td><td>{*n}</td></tr>
+                                        }).collect::<Vec<_>>()}
+                                    </tbody>
+                                </table>
+                                {(report.outside > 0).then(|| view! {
+                                    <p style="color: #b45309;">{format!("⚠ 区切りの範囲外の {} 個の値は欠損値になります。", report.outside)}</p>
+                                })}
+                            </div>
+                        }.into_view(),
+                        Some(Err(e)) => view! { <p style="color: red;">{format!("Error: {}", e)}</p> }.into_view(),
+                        None => view! { <div/> }.into_view(),
+                    }}
+                    <button class="primary-btn" on:click=apply disabled=move || !matches!(preview.get(), Some(Ok(_)))>"群の列を追加"</button>
+                    {move || outcome.get().map(|result| match result {
+                        Ok(message) => view! { <p>{message}</p> }.into_view(),
+                        Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,106 @@
+use leptos::*;
+use crate::components::data_table::DataTable;
+use crate::state::AppData;
+use crate::utils::expression::add_computed_column;
+use crate::utils::recipe::Step;
+
+/// Rows of the new column shown before it is added.
+const PREVIEW_ROWS: usize = 5;
+
+/// A column name as it must be written in an expression.
+fn reference(name: &str) -> String {
+    let plain = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+    if plain { name.to_string() } else { format!("`{}`", name) }
+}
+
+/// Adds a column computed from an expression such as `(q1 + q2 + q3) / 3`.
+#[component]
+pub fn ComputedColumnPanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let name = create_rw_signal(String::new());
+    let source = create_rw_signal(String::new());
+    let (saved, set_saved) = create_signal(Option::<Result<String, String>>::None);
+    let columns = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| df.get_column_names().iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap_or_default())
+    });
+    let result = create_memo(move |_| {
+        let source = source.get();
+        if source.trim().is_empty() {
+            return None;
+        }
+        let name = name.get();
+        let name = if name.trim().is_empty() { "新しい列".to_string() } else { name };
+        df.with(|df| df.as_ref().map(|df| {
+            add_computed_column(df, &name, &source)
+                .and_then(|out| Ok(out.select([name.trim()])?))
+                .map_err(|e| e.to_string())
+        }))
+    });
+
+    let add = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let (column, expression) = (name.get_untracked(), source.get_untracked());
+        match add_computed_column(&current.df, &column, &expression) {
+            Ok(df) => {
+                app_data.apply_step(&current.name, Step::Compute { name: column.trim().to_string(), expression }, df);
+                set_saved.set(Some(Ok(format!("列「{}」を追加しました。", column.trim()))));
+                name.set(String::new());
+                source.set(String::new());
+            }
+            Err(e) => set_saved.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"計算して列を追加"</h3>
+            {move || (!columns.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"新しい列の名前"</label>
+                        <input type="text" placeholder="合計点" prop:value=move || name.get()
+                            on:input=move |ev| name.set(event_target_value(&ev))/>
+                    </div>
+                    <div class="input-group">
+                        <label>"計算式"</label>
+                        <textarea rows="2" style="width: 100%; font-family: monospace;"
+                            placeholder="(q1 + q2 + q3) / 3"
+                            prop:value=move || source.get()
+                            on:input=move |ev| source.set(event_target_value(&ev))></textarea>
+                        <div>
+                            <small>"列名をクリックすると計算式に追加されます: "</small>
+                            {move || columns.get().into_iter().map(|c| {
+                                let text = reference(&c);
+                                view! {
+                                    <button style="margin: 0.2rem;" on:click=move |_| source.update(|s| {
+                                        if !s.is_empty() && !s.ends_with(' ') { s.push(' '); }
+                                        s.push_str(&text);
+                                    })>{c}</button>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </div>
+                        <small style="color: var(--text-secondary);">
+                            "演算子: + - * / % ^ 比較 (== != < <= > >=) and or not ・ 関数: log, log10, exp, sqrt, abs, round(x, 桁), zscore, mean, sum, min, max, if(条件, 真, 偽), is_missing ・ 文字列は \"...\"、空白や記号を含む列名は `...` で囲みます"
+                        </small>
+                    </div>
+                    {move || match result.get() {
+                        Some(Ok(preview)) => view! { <DataTable df=preview max_rows=PREVIEW_ROWS/> }.into_view(),
+                        Some(Err(e)) => view! { <p style="color: red;">{format!("Error: {}", e)}</p> }.into_view(),
+                        None => view! { <div/> }.into_view(),
+                    }}
+                    <button class="primary-btn" on:click=add
+                        disabled=move || name.with(|n| n.trim().is_empty()) || !matches!(result.get(), Some(Ok(_)))>
+                        "列を追加"
+                    </button>
+                    {move || saved.get().map(|result| match result {
+                        Ok(message) => view! { <p>{message}</p> }.into_view(),
+                        Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
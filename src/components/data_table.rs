@@ -0,0 +1,44 @@
+use leptos::*;
+use polars::prelude::*;
+
+/// The first `max_rows` rows of a DataFrame, with each column's dtype under its name.
+#[component]
+pub fn DataTable(df: DataFrame, #[prop(default = 10)] max_rows: usize) -> impl IntoView {
+    let height = df.height().min(max_rows);
+    let columns = df.get_columns().to_vec();
+    let headers = columns.iter().map(|s| view! {
+        <th>{s.name().to_string()}<br/><small style="font-weight: 400; color: var(--text-secondary);">{s.dtype().to_string()}</small></th>
+    }).collect::<Vec<_>>();
+    let rows = (0..height).map(|i| {
+        let cells = columns.iter().map(|s| {
+            let value = s.get(i).map(|v| cell_text(&v)).unwrap_or_default();
+            view! { <td>{value}</td> }
+        }).collect::<Vec<_>>();
+        view! { <tr>{cells}</tr> }
+    }).collect::<Vec<_>>();
+
+    view! {
+        <div class="table-container">
+            <table class="dataframe">
+                <thead>
+                    <tr>{headers}</tr>
+                </thead>
+                <tbody>
+                    {rows}
+                </tbody>
+            </table>
+        </div>
+        {(df.height() > height).then(|| view! {
+            <p style="color: var(--text-secondary); font-size: 0.85rem;">{format!("先頭 {} 行を表示 (全 {} 行)", height, df.height())}</p>
+        })}
+    }
+}
+
+fn cell_text(value: &AnyValue) -> String {
+    match value {
+        AnyValue::Null => String::new(),
+        AnyValue::String(s) => s.to_string(),
+        AnyValue::StringOwned(s) => s.to_string(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,29 @@
+use leptos::*;
+use crate::state::AppData;
+
+/// Lets a page analyze a dataset other than the active one. `choice` is `None`
+/// while the page follows the dataset selected in the sidebar.
+#[component]
+pub fn DatasetPicker(choice: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let names = create_memo(move |_| app_data.datasets.with(|r| r.names()));
+
+    move || (names.with(|n| n.len()) > 1).then(|| view! {
+        <div class="input-group dataset-picker">
+            <label>"分析するデータ"</label>
+            <select on:change=move |ev| {
+                let name = event_target_value(&ev);
+                choice.set(Some(name).filter(|n| !n.is_empty()));
+            }>
+                <option value="" selected=move || choice.get().is_none()>
+                    {move || format!("使用中のデータ ({})", app_data.active_name().unwrap_or_default())}
+                </option>
+                {names.get().into_iter().map(|name| {
+                    let value = name.clone();
+                    let label = name.clone();
+                    view! { <option value=value selected=move || choice.get().as_deref() == Some(name.as_str())>{label}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    })
+}
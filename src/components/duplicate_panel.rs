@@ -0,0 +1,107 @@
+use leptos::*;
+use crate::components::data_table::DataTable;
+use crate::state::AppData;
+use crate::utils::duplicates::{deduplicate, duplicate_groups, KeepRule};
+use crate::utils::recipe::Step;
+
+/// Duplicate rows shown at most.
+const PREVIEW_ROWS: usize = 50;
+
+/// Finds rows repeated on every column or on key columns and keeps one row
+/// of each group in the page's dataset.
+#[component]
+pub fn DuplicatePanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let columns = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| df.get_column_names().iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap_or_default())
+    });
+    // Empty: compare whole rows
+    let keys = create_rw_signal(Vec::<String>::new());
+    let keep = create_rw_signal(KeepRule::First);
+    let (outcome, set_outcome) = create_signal(Option::<Result<String, String>>::None);
+
+    let chosen = move || columns.get().into_iter().filter(|c| keys.with(|k| k.contains(c))).collect::<Vec<_>>();
+    let found = create_memo(move |_| {
+        let keys = chosen();
+        df.with(|df| df.as_ref().map(|df| duplicate_groups(df, &keys).map_err(|e| e.to_string())))
+    });
+
+    let apply = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let (keys, keep) = (chosen(), keep.get_untracked());
+        match deduplicate(&current.df, &keys, keep) {
+            Ok((df, report)) => {
+                let message = format!(
+                    "重複グループ {} 個から {} 行を削除しました ({} 行 → {} 行)",
+                    report.groups, report.dropped, current.df.height(), df.height()
+                );
+                app_data.apply_step(&current.name, Step::Deduplicate { keys, keep }, df);
+                set_outcome.set(Some(Ok(message)));
+            }
+            Err(e) => set_outcome.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"重複行の検出と削除"</h3>
+            {move || (!columns.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"比較する列 (選ばないときは、すべての列が同じ行を重複とみなします)"</label>
+                        {move || columns.get().into_iter().map(|c| {
+                            let (checked, name, label) = (c.clone(), c.clone(), c);
+                            view! {
+                                <label style="margin-right: 1rem;">
+                                    <input type="checkbox" prop:checked=move || keys.with(|l| l.contains(&checked))
+                                        on:change=move |ev| {
+                                            let on = event_target_checked(&ev);
+                                            keys.update(|l| { l.retain(|x| *x != name); if on { l.push(name.clone()); } });
+                                        }/>
+                                    {format!(" {}", label)}
+                                </label>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+
+                    {move || match found.get() {
+                        Some(Ok(rows)) if rows.height() == 0 => view! { <p>"重複している行はありません。"</p> }.into_view(),
+                        Some(Ok(rows)) => {
+                            let groups = rows.column("group").ok().and_then(|g| g.n_unique().ok()).unwrap_or_default();
+                            view! {
+                                <div>
+                                    <p>{format!("重複グループ: {} 個 ({} 行)", groups, rows.height())}</p>
+                                    <DataTable df=rows max_rows=PREVIEW_ROWS/>
+                                </div>
+                            }.into_view()
+                        }
+                        Some(Err(e)) => view! { <p style="color: red;">{format!("Error: {}", e)}</p> }.into_view(),
+                        None => view! { <div/> }.into_view(),
+                    }}
+
+                    <div class="input-group">
+                        <label>"各グループで"</label>
+                        <select on:change=move |ev| {
+                            let label = event_target_value(&ev);
+                            if let Some(k) = KeepRule::ALL.into_iter().find(|k| k.label() == label) { keep.set(k); }
+                        }>
+                            {KeepRule::ALL.into_iter().map(|k| view! {
+                                <option value=k.label() selected=move || keep.get() == k>{k.label()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <button class="primary-btn" on:click=apply
+                            disabled=move || !matches!(found.get(), Some(Ok(ref rows)) if rows.height() > 0)>
+                            "重複行を削除"
+                        </button>
+                    </div>
+                    {move || outcome.get().map(|result| match result {
+                        Ok(message) => view! { <p>{message}</p> }.into_view(),
+                        Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
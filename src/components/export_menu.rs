@@ -0,0 +1,58 @@
+use leptos::*;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+use crate::state::AppData;
+use crate::utils::data_formats::{export_dataframe, ExportFormat};
+
+/// Saves bytes as a file through a temporary object URL.
+pub(crate) fn download(file_name: &str, mime_type: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window().and_then(|w| w.document()).ok_or("document not available")?;
+    let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    Url::revoke_object_url(&url)
+}
+
+/// Download of the active dataset (including any cleaning done in the app).
+#[component]
+pub fn ExportMenu() -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let (format, set_format) = create_signal(ExportFormat::Csv);
+    let (error, set_error) = create_signal(Option::<String>::None);
+
+    let export = move |_| {
+        let Some(dataset) = app_data.dataset_untracked(None) else { return };
+        let format = format.get_untracked();
+        let file_name = format.file_name(&dataset.name);
+        let result = export_dataframe(&dataset.df, format)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| download(&file_name, format.mime_type(), &bytes).map_err(|e| format!("{:?}", e)));
+        set_error.set(result.err().map(|e| format!("Error: {}", e)));
+    };
+
+    view! {
+        <div class="export-menu" style="margin-top: 1rem;">
+            <select on:change=move |ev| {
+                let value = event_target_value(&ev);
+                if let Some(f) = ExportFormat::ALL.into_iter().find(|f| f.extension() == value) {
+                    set_format.set(f);
+                }
+            }>
+                {ExportFormat::ALL.into_iter().map(|f| view! {
+                    <option value=f.extension() selected=move || format.get() == f>{f.label()}</option>
+                }).collect::<Vec<_>>()}
+            </select>
+            <button class="link-button" style="margin-left: 0.5rem;" on:click=export>
+                <i class="fas fa-download"></i>" 現在のデータをダウンロード"
+            </button>
+            {move || error.get().map(|e| view! { <div style="color: red; margin-top: 0.5rem;">{e}</div> })}
+        </div>
+    }
+}
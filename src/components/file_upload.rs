@@ -1,25 +1,72 @@
 use leptos::*;
 use web_sys::{HtmlInputElement, FileReader};
 use wasm_bindgen::JsCast;
+use crate::components::data_table::DataTable;
+use crate::components::export_menu::ExportMenu;
+use crate::components::sample_menu::SampleMenu;
 use crate::state::AppData;
-use crate::utils::excel_to_polars::convert_excel_to_df;
-use polars::prelude::*;
-use std::io::Cursor;
+use crate::utils::csv_import::{preview_csv, CsvOptions, TextEncoding, DELIMITER_CANDIDATES};
+use crate::utils::excel_to_polars::{preview_rows, sheet_names, SheetOptions};
+use crate::utils::file_loader::{is_delimited_text, is_workbook, read_dataframe_with_report, ImportOptions, ACCEPTED_EXTENSIONS};
+use crate::utils::type_inference::ImportReport;
+
+/// Number of raw rows shown when choosing the header row.
+const PREVIEW_ROWS: usize = 8;
+
+fn delimiter_label(c: char) -> String {
+    match c {
+        '\t' => "タブ".to_string(),
+        ',' => "カンマ (,)".to_string(),
+        ';' => "セミコロン (;)".to_string(),
+        '|' => "縦棒 (|)".to_string(),
+        other => other.to_string(),
+    }
+}
 
 #[component]
 pub fn FileUpload() -> impl IntoView {
     let app_data = use_context::<AppData>().expect("AppData context not found");
-    // Clone for the view closure
-    let app_data_for_view = app_data.clone();
+
+    // Uploaded workbook (file name, bytes), kept so it can be re-read with other settings
+    let workbook = create_rw_signal(Option::<(String, Vec<u8>)>::None);
+    let sheets = create_rw_signal(Vec::<String>::new());
+    let sheet_options = create_rw_signal(SheetOptions::default());
+    // Uploaded CSV/TSV, previewed until the options are confirmed
+    let csv_file = create_rw_signal(Option::<(String, Vec<u8>)>::None);
+    let csv_options = create_rw_signal(CsvOptions::default());
+    // Dataset the uploaded workbook was stored as, replaced when it is re-read
+    let workbook_dataset = create_rw_signal(Option::<String>::None);
+    // Cells the type inference set to null in the last import
+    let import_report = create_rw_signal(ImportReport::default());
+
+    // Reads the file into a new dataset, or into `replace` when re-reading it with other
+    // settings. Returns the dataset name, or None if the file could not be read.
+    let load = move |file_name: &str, bytes: &[u8], options: &ImportOptions, replace: Option<&str>| {
+        match read_dataframe_with_report(file_name, bytes, options) {
+            Ok((df, report)) => {
+                import_report.set(report);
+                Some(match replace {
+                    Some(name) => {
+                        app_data.set_dataset(name, df);
+                        name.to_string()
+                    }
+                    None => app_data.add_dataset(file_name, df),
+                })
+            },
+            Err(e) => {
+                app_data.error_msg.set(Some(format!("Error: {}", e)));
+                import_report.set(ImportReport::default());
+                None
+            }
+        }
+    };
 
     let on_file_change = move |ev: ev::Event| {
-        // AppData is moved here
         let input: HtmlInputElement = ev.target().unwrap().unchecked_into();
-        
+
         if let Some(files) = input.files() {
             if let Some(file) = files.get(0) {
                 let file_name = file.name();
-                app_data.file_name.set(file_name.clone());
                 app_data.error_msg.set(None);
 
                 let reader = FileReader::new().unwrap();
@@ -33,24 +80,23 @@ pub fn FileUpload() -> impl IntoView {
                     let uint8_array = js_sys::Uint8Array::new(&array_buffer);
                     let bytes = uint8_array.to_vec();
 
-                    let df_result = if file_name_clone.ends_with(".csv") {
-                        CsvReader::new(Cursor::new(bytes))
-                            .finish()
-                            .map_err(|e| anyhow::anyhow!("CSV Parse Error: {}", e))
-                    } else if file_name_clone.ends_with(".xlsx") || file_name_clone.ends_with(".xls") {
-                        convert_excel_to_df(&bytes)
+                    if is_workbook(&file_name_clone, &bytes) {
+                        // Start with the first sheet and first row; the settings panel can change both
+                        workbook_dataset.set(load(&file_name_clone, &bytes, &ImportOptions::default(), None));
+                        sheets.set(sheet_names(&file_name_clone, &bytes).unwrap_or_default());
+                        sheet_options.set(SheetOptions::default());
+                        workbook.set(Some((file_name_clone.clone(), bytes)));
+                        csv_file.set(None);
+                    } else if is_delimited_text(&file_name_clone) {
+                        // Nothing is loaded until the preview has been confirmed
+                        sheets.set(vec![]);
+                        workbook.set(None);
+                        csv_options.set(CsvOptions::default());
+                        csv_file.set(Some((file_name_clone.clone(), bytes)));
                     } else {
-                        Err(anyhow::anyhow!("Unsupported file format"))
-                    };
-
-                    match df_result {
-                        Ok(df) => {
-                            app_data.df.set(Some(df));
-                        },
-                        Err(e) => {
-                            app_data.error_msg.set(Some(format!("Error: {}", e)));
-                            app_data.df.set(None);
-                        }
+                        load(&file_name_clone, &bytes, &ImportOptions::default(), None);
+                        workbook.set(None);
+                        csv_file.set(None);
                     }
                 };
 
@@ -63,31 +109,256 @@ pub fn FileUpload() -> impl IntoView {
         }
     };
 
+    // Raw rows of the chosen sheet/range, ignoring the header row setting
+    let preview = create_memo(move |_| {
+        let options = SheetOptions { header_row: None, ..sheet_options.get() };
+        workbook.with(|wb| {
+            wb.as_ref().map(|(name, bytes)| preview_rows(name, bytes, &options, PREVIEW_ROWS).map_err(|e| e.to_string()))
+        })
+    });
+
+    let apply = move |_| {
+        workbook.with_untracked(|wb| {
+            if let Some((name, bytes)) = wb {
+                let options = ImportOptions { sheet: sheet_options.get_untracked(), ..Default::default() };
+                if let Some(dataset) = load(name, bytes, &options, workbook_dataset.get_untracked().as_deref()) {
+                    workbook_dataset.set(Some(dataset));
+                }
+            }
+        });
+    };
+
+    // Detected encoding/delimiter and the first rows parsed with the current CSV options
+    let csv_preview = create_memo(move |_| {
+        let options = csv_options.get();
+        csv_file.with(|file| {
+            file.as_ref().map(|(_, bytes)| {
+                let (encoding, delimiter) = options.resolve(bytes);
+                let preview = preview_csv(bytes, &options, PREVIEW_ROWS).map_err(|e| e.to_string());
+                (encoding, delimiter, preview)
+            })
+        })
+    });
+
+    let apply_csv = move |_| {
+        if let Some((name, bytes)) = csv_file.get_untracked() {
+            if load(&name, &bytes, &ImportOptions { csv: csv_options.get_untracked(), ..Default::default() }, None).is_some() {
+                csv_file.set(None);
+            }
+        }
+    };
+
     view! {
         <div class="file-upload-container" style="margin-bottom: 2rem; padding: 2rem; border: 2px dashed var(--border-color); border-radius: 12px; text-align: center; background: var(--surface);">
             <i class="fas fa-cloud-upload-alt" style="font-size: 3rem; color: var(--primary-color); margin-bottom: 1rem;"></i>
             <h3 style="margin-bottom: 1rem; color: var(--text-primary);">"データをアップロード"</h3>
-            <p style="color: var(--text-secondary); margin-bottom: 1.5rem;">"CSV または Excel (.xlsx) ファイルを選択してください"</p>
-            
+            <p style="color: var(--text-secondary); margin-bottom: 1.5rem;">"CSV / TSV / Excel (.xlsx, .xls, .xlsb) / OpenDocument (.ods) / JSON / Parquet ファイルを選択してください"</p>
+
             <label class="link-button" style="cursor: pointer; display: inline-block;">
                 "ファイルを選択"
-                <input 
-                    type="file" 
-                    accept=".csv,.xlsx,.xls" 
+                <input
+                    type="file"
+                    accept=ACCEPTED_EXTENSIONS
                     on:change=on_file_change
                     style="display: none;"
                 />
             </label>
 
+            <SampleMenu on_load=move |_| {
+                // The sample replaces whatever upload was being configured
+                workbook.set(None);
+                csv_file.set(None);
+                import_report.set(ImportReport::default());
+            }/>
+
             {move || {
-                if let Some(err) = app_data_for_view.error_msg.get() {
+                if let Some(err) = app_data.error_msg.get() {
                     view! { <div style="color: red; margin-top: 1rem;">{err}</div> }.into_view()
-                } else if !app_data_for_view.file_name.get().is_empty() {
-                    view! { <div style="color: green; margin-top: 1rem;">"選択されたファイル: " {app_data_for_view.file_name.get()}</div> }.into_view()
+                } else if let Some(name) = app_data.active_name() {
+                    view! { <div style="color: green; margin-top: 1rem;">"使用中のデータ: " {name}</div> }.into_view()
                 } else {
                     view! { <div/> }.into_view()
                 }
             }}
+
+            <ImportReportView report=import_report/>
+
+            {move || app_data.active_name().is_some().then(|| view! { <ExportMenu/> })}
+
+            {move || csv_file.with(|f| f.is_some()).then(|| view! {
+                <div class="import-settings" style="margin-top: 1.5rem; text-align: left;">
+                    <h4>{move || format!("CSV の読み込み設定: {}", csv_file.with(|f| f.as_ref().map(|(n, _)| n.clone()).unwrap_or_default()))}</h4>
+                    <div class="input-group">
+                        <label>"文字コード"</label>
+                        <select on:change=move |ev| {
+                            let encoding = TextEncoding::from_label(&event_target_value(&ev));
+                            csv_options.update(|o| o.encoding = encoding);
+                        }>
+                            <option value="auto" selected=move || csv_options.with(|o| o.encoding.is_none())>
+                                {move || format!("自動 ({})", csv_preview.with(|p| p.as_ref().map(|(e, _, _)| e.label()).unwrap_or("")))}
+                            </option>
+                            {TextEncoding::ALL.into_iter().map(|e| view! {
+                                <option value=e.label() selected=move || csv_options.with(|o| o.encoding == Some(e))>{e.label()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+
+                        <label>"区切り文字"</label>
+                        <select on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            csv_options.update(|o| o.delimiter = if value == "auto" { None } else { value.chars().next() });
+                        }>
+                            <option value="auto" selected=move || csv_options.with(|o| o.delimiter.is_none())>
+                                {move || format!("自動 ({})", csv_preview.with(|p| p.as_ref().map(|(_, d, _)| delimiter_label(*d)).unwrap_or_default()))}
+                            </option>
+                            {DELIMITER_CANDIDATES.into_iter().map(|d| view! {
+                                <option value=d.to_string() selected=move || csv_options.with(|o| o.delimiter == Some(d))>{delimiter_label(d)}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+
+                        <label>"引用符"</label>
+                        <select on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            csv_options.update(|o| o.quote = value.chars().next());
+                        }>
+                            <option value="\"" selected=move || csv_options.with(|o| o.quote == Some('"'))>"ダブルクォート (\")"</option>
+                            <option value="'" selected=move || csv_options.with(|o| o.quote == Some('\''))>"シングルクォート (')"</option>
+                            <option value="" selected=move || csv_options.with(|o| o.quote.is_none())>"なし"</option>
+                        </select>
+
+                        <label>"欠損値として扱う文字列 (カンマ区切り)"</label>
+                        <input type="text"
+                            placeholder="例: NA, -, 未回答"
+                            prop:value=move || csv_options.with(|o| o.na_values.join(", "))
+                            on:change=move |ev| {
+                                let values = event_target_value(&ev)
+                                    .split(',')
+                                    .map(|v| v.trim().to_string())
+                                    .filter(|v| !v.is_empty())
+                                    .collect();
+                                csv_options.update(|o| o.na_values = values);
+                            }
+                        />
+                    </div>
+                    <label style="margin-right: 1.5rem;">
+                        <input type="checkbox"
+                            prop:checked=move || csv_options.with(|o| o.decimal_comma)
+                            on:change=move |ev| csv_options.update(|o| o.decimal_comma = event_target_checked(&ev))
+                        />
+                        " 小数点はカンマ (1,5 = 1.5)"
+                    </label>
+                    <label>
+                        <input type="checkbox"
+                            prop:checked=move || csv_options.with(|o| o.has_header)
+                            on:change=move |ev| csv_options.update(|o| o.has_header = event_target_checked(&ev))
+                        />
+                        " 1行目は見出し"
+                    </label>
+
+                    {move || match csv_preview.get() {
+                        Some((_, _, Ok(df))) => view! { <DataTable df=df max_rows=PREVIEW_ROWS/> }.into_view(),
+                        Some((_, _, Err(e))) => view! { <div style="color: red; margin-top: 1rem;">{e}</div> }.into_view(),
+                        None => view! { <div/> }.into_view(),
+                    }}
+
+                    <button class="primary-btn" style="margin-top: 1rem;" on:click=apply_csv>
+                        "この設定で読み込む"
+                    </button>
+                </div>
+            })}
+
+            {move || workbook.with(|wb| wb.is_some()).then(|| view! {
+                <div class="import-settings" style="margin-top: 1.5rem; text-align: left;">
+                    <h4>"読み込み設定"</h4>
+                    <div class="input-group">
+                        <label>"シート"</label>
+                        <select on:change=move |ev| {
+                            let name = event_target_value(&ev);
+                            sheet_options.update(|o| {
+                                o.sheet = Some(name);
+                                o.header_row = None;
+                            });
+                        }>
+                            {move || sheets.get().into_iter().enumerate().map(|(i, name)| {
+                                let selected = sheet_options.with(|o| match &o.sheet {
+                                    Some(s) => *s == name,
+                                    None => i == 0,
+                                });
+                                view! { <option value=name.clone() selected=selected>{name}</option> }
+                            }).collect::<Vec<_>>()}
+                        </select>
+
+                        <label>"見出し行 (行番号)"</label>
+                        <input type="number" min="1"
+                            placeholder="先頭行"
+                            prop:value=move || sheet_options.with(|o| o.header_row.map(|r| r.to_string()).unwrap_or_default())
+                            on:change=move |ev| {
+                                let row = event_target_value(&ev).trim().parse().ok();
+                                sheet_options.update(|o| o.header_row = row);
+                            }
+                        />
+
+                        <label>"セル範囲 (任意, 例: B3:F120)"</label>
+                        <input type="text"
+                            placeholder="シート全体"
+                            prop:value=move || sheet_options.with(|o| o.range.clone().unwrap_or_default())
+                            on:change=move |ev| {
+                                let range = event_target_value(&ev);
+                                sheet_options.update(|o| o.range = Some(range).filter(|r| !r.trim().is_empty()));
+                            }
+                        />
+                    </div>
+
+                    <p style="color: var(--text-secondary); font-size: 0.85rem;">"行をクリックすると見出し行に設定されます。"</p>
+                    {move || match preview.get() {
+                        Some(Ok(rows)) => view! {
+                            <div class="table-container">
+                                <table class="dataframe">
+                                    <tbody>
+                                        {rows.into_iter().enumerate().map(|(i, (row_no, cells))| {
+                                            let is_header = sheet_options.with(|o| o.header_row.map_or(i == 0, |r| r == row_no));
+                                            view! {
+                                                <tr
+                                                    style:cursor="pointer"
+                                                    style:font-weight=if is_header { "700" } else { "400" }
+                                                    on:click=move |_| sheet_options.update(|o| o.header_row = Some(row_no))
+                                                >
+                                                    <th>{row_no}</th>
+                                                    {cells.into_iter().map(|c| view! { <td>{c}</td> }).collect::<Vec<_>>()}
+                                                </tr>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }.into_view(),
+                        Some(Err(e)) => view! { <div style="color: red;">{e}</div> }.into_view(),
+                        None => view! { <div/> }.into_view(),
+                    }}
+
+                    <button class="primary-btn" style="margin-top: 1rem;" on:click=apply>
+                        "この設定で読み込む"
+                    </button>
+                </div>
+            })}
         </div>
     }
 }
+
+/// Lists the cells that type inference set to null, if there were any.
+#[component]
+pub fn ImportReportView(#[prop(into)] report: Signal<ImportReport>) -> impl IntoView {
+    move || report.with(|r| r.coerced_total() > 0).then(|| view! {
+        <div class="import-report" style="margin-top: 1rem; padding: 1rem; text-align: left; background: #fff8e1; border-left: 4px solid #f0ad4e;">
+            <strong>{move || format!("型が合わない {} 個のセルを欠損値 (空欄) として読み込みました", report.with(|r| r.coerced_total()))}</strong>
+            <ul style="margin: 0.5rem 0 0; padding-left: 1.2rem;">
+                {move || report.get().coerced_columns().map(|c| {
+                    let cells: Vec<String> = c.coerced.iter().take(10).map(|cell| format!("{}行目「{}」", cell.row, cell.value)).collect();
+                    let more = if c.coerced.len() > 10 { " ほか" } else { "" };
+                    view! {
+                        <li>{format!("{} ({}): {}件 — {}{}", c.column, c.dtype, c.coerced.len(), cells.join(", "), more)}</li>
+                    }
+                }).collect::<Vec<_>>()}
+            </ul>
+        </div>
+    })
+}
@@ -0,0 +1,137 @@
+use leptos::*;
+use crate::state::AppData;
+use crate::utils::filter::{Condition, FilterOp, RowFilter};
+use crate::utils::recipe::Step;
+
+/// Edits the row filter shared by the analysis pages. Conditions are drafted
+/// here and only take effect when applied.
+#[component]
+pub fn FilterBuilder() -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let columns = create_memo(move |_| {
+        app_data.datasets.with(|r| r.active().map(|d| d.df.get_column_names().iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap_or_default())
+    });
+    let draft = create_rw_signal(RowFilter::default());
+    let (error, set_error) = create_signal(Option::<String>::None);
+
+    let update = move |index: usize, change: &dyn Fn(&mut Condition)| {
+        draft.update(|f| {
+            if let Some(condition) = f.conditions.get_mut(index) {
+                change(condition);
+            }
+        });
+    };
+    let add = move |_| {
+        let column = columns.get_untracked().into_iter().next().unwrap_or_default();
+        draft.update(|f| f.conditions.push(Condition { column, op: FilterOp::Eq, value: String::new() }));
+    };
+    // Drops the other rows from the active dataset itself, as an undoable cleaning step
+    let keep_rows = move |_| {
+        let filter = draft.get_untracked();
+        let Some(current) = app_data.dataset_untracked(None) else { return };
+        match filter.apply(&current.df) {
+            Ok(df) => {
+                app_data.apply_step(&current.name, Step::Filter { filter }, df);
+                draft.set(RowFilter::default());
+                app_data.filter.set(RowFilter::default());
+                set_error.set(None);
+            }
+            Err(e) => set_error.set(Some(format!("Error: {}", e))),
+        }
+    };
+    let preview = move || {
+        let filter = draft.get();
+        if filter.is_empty() {
+            return None;
+        }
+        let df = app_data.df()?;
+        Some(match filter.apply(&df) {
+            Ok(filtered) => format!("使用中のデータでは {} / {} 行が条件に合います。", filtered.height(), df.height()),
+            Err(e) => format!("Error: {}", e),
+        })
+    };
+
+    view! {
+        <details class="filter-builder" style="margin-bottom: 2rem; padding: 1rem 2rem; border: 1px solid var(--border-color); border-radius: 12px; background: var(--surface);">
+            <summary style="cursor: pointer; font-weight: 600;">
+                "行の絞り込み (フィルタ)"
+                {move || app_data.filter.with(|f| (!f.is_empty()).then(|| format!(" — 適用中: {}", f.describe())))}
+            </summary>
+            <p style="color: var(--text-secondary);">"条件に合う行だけを各分析ページで使います。元のデータは変更されません (「条件に合う行だけをデータに残す」を除く。これはデータクレンジングの履歴から元に戻せます)。"</p>
+            {move || draft.get().conditions.into_iter().enumerate().map(|(i, condition)| {
+                let current_column = condition.column.clone();
+                view! {
+                    <div style="display: flex; gap: 0.5rem; align-items: center; margin-bottom: 0.5rem;">
+                        <select on:change=move |ev| { let v = event_target_value(&ev); update(i, &|c| c.column = v.clone()); }>
+                            {columns.get().into_iter().map(|c| {
+                                let selected = c == current_column;
+                                view! { <option value=c.clone() selected=selected>{c}</option> }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <select on:change=move |ev| {
+                            let label = event_target_value(&ev);
+                            if let Some(op) = FilterOp::ALL.into_iter().find(|op| op.label() == label) { update(i, &|c| c.op = op); }
+                        }>
+                            {FilterOp::ALL.into_iter().map(|op| view! {
+                                <option value=op.label() selected=op == condition.op>{op.label()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        {condition.op.takes_value().then(|| view! {
+                            <input type="text" placeholder="値" prop:value=condition.value.clone()
+                                on:change=move |ev| { let v = event_target_value(&ev); update(i, &|c| c.value = v.clone()); }/>
+                        })}
+                        <button on:click=move |_| draft.update(|f| { f.conditions.remove(i); })>"×"</button>
+                    </div>
+                }
+            }).collect::<Vec<_>>()}
+            <button on:click=add>"条件を追加"</button>
+            {move || (draft.with(|f| f.conditions.len()) > 1).then(|| view! {
+                <div class="input-group">
+                    <label>
+                        <input type="radio" name="filter-combine" prop:checked=move || draft.with(|f| f.all)
+                            on:change=move |_| draft.update(|f| f.all = true)/>
+                        " すべての条件を満たす (AND)"
+                    </label>
+                    <label style="margin-left: 1rem;">
+                        <input type="radio" name="filter-combine" prop:checked=move || draft.with(|f| !f.all)
+                            on:change=move |_| draft.update(|f| f.all = false)/>
+                        " いずれかの条件を満たす (OR)"
+                    </label>
+                </div>
+            })}
+            {move || preview().map(|text| view! { <p>{text}</p> })}
+            <div style="display: flex; gap: 0.5rem; margin-top: 1rem;">
+                <button class="primary-btn" on:click=move |_| app_data.filter.set(draft.get_untracked())>"フィルタを適用"</button>
+                <button on:click=move |_| { draft.set(RowFilter::default()); app_data.filter.set(RowFilter::default()); }>"フィルタを解除"</button>
+                <button on:click=keep_rows disabled=move || draft.with(|f| f.is_empty())>"条件に合う行だけをデータに残す"</button>
+            </div>
+            {move || error.get().map(|e| view! { <p style="color: red;">{e}</p> })}
+        </details>
+    }
+}
+
+/// "Filtered: n of N rows" for the dataset a page analyzes, with a button to remove the filter.
+#[component]
+pub fn FilterBadge(choice: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let source = app_data.source_df(choice);
+
+    move || {
+        let filter = app_data.filter.get();
+        if filter.is_empty() {
+            return None;
+        }
+        let df = source.get()?;
+        let text = match filter.apply(&df) {
+            Ok(filtered) => format!("絞り込み中: {} / {} 行 ({})", filtered.height(), df.height(), filter.describe()),
+            Err(e) => format!("フィルタをこのデータに適用できないため、分析できません: {}", e),
+        };
+        Some(view! {
+            <div class="filter-badge" style="display: inline-flex; gap: 0.75rem; align-items: center; margin: 0.5rem 0 1rem; padding: 0.4rem 0.9rem; border-radius: 999px; background: #fef3c7; color: #92400e;">
+                <i class="fas fa-filter"></i>
+                <span>{text}</span>
+                <button on:click=move |_| app_data.filter.set(RowFilter::default())>"解除"</button>
+            </div>
+        })
+    }
+}
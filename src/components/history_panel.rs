@@ -0,0 +1,110 @@
+use leptos::*;
+use wasm_bindgen::JsCast;
+use web_sys::{FileReader, HtmlInputElement};
+use crate::components::export_menu::download;
+use crate::state::AppData;
+use crate::utils::recipe::{recipe_from_json, recipe_to_json, replay};
+
+/// The cleaning steps applied to the page's dataset, with undo/redo and the
+/// steps saved as (or loaded from) a JSON recipe.
+#[component]
+pub fn HistoryPanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let (outcome, set_outcome) = create_signal(Option::<Result<String, String>>::None);
+
+    // (dataset name, applied steps, can undo, can redo)
+    let history = move || {
+        app_data.datasets.with(|r| {
+            r.resolve(dataset.get().as_deref())
+                .map(|d| (d.name.clone(), d.history.steps(), d.history.can_undo(), d.history.can_redo()))
+        })
+    };
+    let current_name = move || app_data.dataset_untracked(dataset.get_untracked().as_deref()).map(|d| d.name);
+    let undo = move |_| {
+        if let Some(name) = current_name() {
+            app_data.datasets.update(|r| { r.undo(&name); });
+            set_outcome.set(None);
+        }
+    };
+    let redo = move |_| {
+        if let Some(name) = current_name() {
+            app_data.datasets.update(|r| { r.redo(&name); });
+            set_outcome.set(None);
+        }
+    };
+    let export = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let stem = current.name.rsplit_once('.').map_or(current.name.as_str(), |(stem, _)| stem).to_string();
+        let result = recipe_to_json(&current.history.steps())
+            .map_err(|e| e.to_string())
+            .and_then(|json| download(&format!("{}.recipe.json", stem), "application/json", json.as_bytes()).map_err(|e| format!("{:?}", e)));
+        set_outcome.set(result.err().map(|e| Err(format!("Error: {}", e))));
+    };
+
+    // Replays the recipe on the dataset; nothing changes unless every step fits the data
+    let apply_recipe = move |text: String| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let result = recipe_from_json(&text).and_then(|steps| Ok((replay(&current.df, &steps)?, steps)));
+        match result {
+            Ok((frames, steps)) => {
+                let count = steps.len();
+                app_data.datasets.update(|r| {
+                    for (step, df) in steps.into_iter().zip(frames) {
+                        r.apply(&current.name, step, df);
+                    }
+                });
+                set_outcome.set(Some(Ok(format!("レシピの {} 個の手順を「{}」に適用しました。", count, current.name))));
+            }
+            Err(e) => set_outcome.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+    let on_recipe_file = move |ev: ev::Event| {
+        let input: HtmlInputElement = ev.target().unwrap().unchecked_into();
+        let Some(file) = input.files().and_then(|files| files.get(0)) else { return };
+        let reader = FileReader::new().unwrap();
+        let reader_clone = reader.clone();
+        let on_load = move |_: web_sys::Event| {
+            if let Some(text) = reader_clone.result().ok().and_then(|r| r.as_string()) {
+                apply_recipe(text);
+            }
+        };
+        let closure = wasm_bindgen::closure::Closure::wrap(Box::new(on_load) as Box<dyn FnMut(_)>);
+        reader.set_onload(Some(closure.as_ref().unchecked_ref()));
+        closure.forget();
+        reader.read_as_text(&file).unwrap();
+        // Allow loading the same file again
+        input.set_value("");
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"処理の履歴とレシピ"</h3>
+            {move || history().map(|(name, steps, can_undo, can_redo)| view! {
+                <div class="control-panel">
+                    {if steps.is_empty() {
+                        view! { <p>{format!("「{}」にはまだ処理をしていません。", name)}</p> }.into_view()
+                    } else {
+                        view! {
+                            <ol>
+                                {steps.iter().map(|step| view! { <li>{step.describe()}</li> }).collect::<Vec<_>>()}
+                            </ol>
+                        }.into_view()
+                    }}
+                    <div style="display: flex; gap: 0.5rem; flex-wrap: wrap;">
+                        <button on:click=undo disabled=!can_undo><i class="fas fa-undo"></i>" 元に戻す"</button>
+                        <button on:click=redo disabled=!can_redo><i class="fas fa-redo"></i>" やり直す"</button>
+                        <button on:click=export disabled=steps.is_empty()><i class="fas fa-download"></i>" レシピを保存 (JSON)"</button>
+                    </div>
+                    <div class="input-group">
+                        <label>"保存したレシピを読み込んで、このデータに同じ処理を適用"</label>
+                        <input type="file" accept=".json,application/json" on:change=on_recipe_file/>
+                    </div>
+                </div>
+            })}
+            {move || outcome.get().map(|result| match result {
+                Ok(message) => view! { <p>{message}</p> }.into_view(),
+                Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+            })}
+        </div>
+    }
+}
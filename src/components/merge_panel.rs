@@ -0,0 +1,188 @@
+use leptos::*;
+use polars::prelude::DataFrame;
+use crate::components::data_table::DataTable;
+use crate::state::AppData;
+use crate::utils::merge::{check_keys, merge, JoinKind, MergeOptions};
+
+/// Rows shown in the unmatched / duplicate key tables and the result preview.
+const PREVIEW_ROWS: usize = 8;
+
+/// Merges two loaded datasets on one or more key columns and stores the result as a new dataset.
+#[component]
+pub fn MergePanel() -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let names = create_memo(move |_| app_data.datasets.with(|r| r.names()));
+
+    // Empty: the first dataset (left) / the first one that is not the left (right)
+    let left_choice = create_rw_signal(String::new());
+    let right_choice = create_rw_signal(String::new());
+    let left_name = create_memo(move |_| {
+        let choice = left_choice.get();
+        names.with(|n| if n.contains(&choice) { choice } else { app_data.active_name().unwrap_or_default() })
+    });
+    let right_name = create_memo(move |_| {
+        let choice = right_choice.get();
+        let left = left_name.get();
+        names.with(|n| if n.contains(&choice) { choice } else { n.iter().find(|x| **x != left).cloned().unwrap_or_default() })
+    });
+    let frame = move |name: String| app_data.datasets.with(|r| r.get(&name).map(|d| d.df.clone()));
+    let left_df = create_memo(move |_| frame(left_name.get()));
+    let right_df = create_memo(move |_| frame(right_name.get()));
+    let columns = move |df: Option<DataFrame>| -> Vec<String> {
+        df.map(|df| df.get_column_names().iter().map(|s| s.to_string()).collect()).unwrap_or_default()
+    };
+    let left_columns = create_memo(move |_| columns(left_df.get()));
+    let right_columns = create_memo(move |_| columns(right_df.get()));
+
+    // Key pairs (left column, right column); empty while none were chosen
+    let keys = create_rw_signal(Vec::<(String, String)>::new());
+    let kind = create_rw_signal(JoinKind::Left);
+    let (saved, set_saved) = create_signal(Option::<String>::None);
+
+    // Columns present in both datasets are the default key
+    let effective_keys = create_memo(move |_| {
+        let chosen = keys.get();
+        if !chosen.is_empty() {
+            return chosen;
+        }
+        let right = right_columns.get();
+        left_columns.get().into_iter().find(|c| right.contains(c)).map(|c| vec![(c.clone(), c)]).unwrap_or_default()
+    });
+    let options = create_memo(move |_| {
+        let (left_on, right_on) = effective_keys.get().into_iter().unzip();
+        MergeOptions { left_on, right_on, kind: kind.get() }
+    });
+    let check = create_memo(move |_| match (left_df.get(), right_df.get()) {
+        (Some(l), Some(r)) => Some(check_keys(&l, &r, &options.get()).map_err(|e| e.to_string())),
+        _ => None,
+    });
+    let result = create_memo(move |_| match (left_df.get(), right_df.get()) {
+        (Some(l), Some(r)) => Some(merge(&l, &r, &options.get()).map_err(|e| e.to_string())),
+        _ => None,
+    });
+
+    let set_key = move |index: usize, left: Option<String>, right: Option<String>| {
+        let mut pairs = effective_keys.get_untracked();
+        if let Some(pair) = pairs.get_mut(index) {
+            if let Some(l) = left { pair.0 = l; }
+            if let Some(r) = right { pair.1 = r; }
+        }
+        keys.set(pairs);
+    };
+    let add_key = move |_| {
+        let mut pairs = effective_keys.get_untracked();
+        let l = left_columns.get_untracked().into_iter().next().unwrap_or_default();
+        let r = right_columns.get_untracked().into_iter().next().unwrap_or_default();
+        pairs.push((l, r));
+        keys.set(pairs);
+    };
+    let save = move |_| {
+        if let Some(Ok(df)) = result.get_untracked() {
+            let name = format!("{} + {}", left_name.get_untracked(), right_name.get_untracked());
+            let stored = app_data.add_dataset(&name, df);
+            set_saved.set(Some(stored));
+        }
+    };
+
+    let dataset_select = move |label: &'static str, current: Memo<String>, choice: RwSignal<String>| view! {
+        <div class="input-group">
+            <label>{label}</label>
+            <select on:change=move |ev| { choice.set(event_target_value(&ev)); keys.set(Vec::new()); set_saved.set(None); }>
+                {move || names.get().into_iter().map(|n| {
+                    let selected = current.get() == n;
+                    view! { <option value=n.clone() selected=selected>{n}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    };
+    let column_options = move |columns: Vec<String>, current: String| {
+        columns.into_iter().map(|c| {
+            let selected = c == current;
+            view! { <option value=c.clone() selected=selected>{c}</option> }
+        }).collect::<Vec<_>>()
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"データの結合"</h3>
+            {move || if names.with(|n| n.len()) < 2 {
+                view! { <p>"結合するには、2つ以上のデータを読み込んでください。"</p> }.into_view()
+            } else {
+                view! {
+                    <div class="control-panel">
+                        {dataset_select("左のデータ", left_name, left_choice)}
+                        {dataset_select("右のデータ", right_name, right_choice)}
+                        <div class="input-group">
+                            <label>"結合の種類"</label>
+                            <select on:change=move |ev| {
+                                let label = event_target_value(&ev);
+                                if let Some(k) = JoinKind::ALL.into_iter().find(|k| k.label() == label) { kind.set(k); }
+                            }>
+                                {JoinKind::ALL.into_iter().map(|k| view! {
+                                    <option value=k.label() selected=move || kind.get() == k>{k.label()}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </div>
+                        <div class="input-group">
+                            <label>"キー列 (左 = 右)"</label>
+                            {move || effective_keys.get().into_iter().enumerate().map(|(i, (l, r))| view! {
+                                <div style="display: flex; gap: 0.5rem; align-items: center; margin-bottom: 0.5rem;">
+                                    <select on:change=move |ev| set_key(i, Some(event_target_value(&ev)), None)>
+                                        {column_options(left_columns.get(), l)}
+                                    </select>
+                                    "="
+                                    <select on:change=move |ev| set_key(i, None, Some(event_target_value(&ev)))>
+                                        {column_options(right_columns.get(), r)}
+                                    </select>
+                                    <button on:click=move |_| {
+                                        let mut pairs = effective_keys.get_untracked();
+                                        pairs.remove(i);
+                                        keys.set(pairs);
+                                    }>"×"</button>
+                                </div>
+                            }).collect::<Vec<_>>()}
+                            <button on:click=add_key>"キー列を追加"</button>
+                        </div>
+                    </div>
+
+                    {move || match check.get() {
+                        Some(Ok(check)) => view! {
+                            <div>
+                                {(!check.left_duplicates.is_empty()).then(|| view! {
+                                    <p style="color: #b45309;">
+                                        {format!("⚠ 左のデータでキーが重複しています ({} 種類)。結合すると行が増えることがあります。", check.left_duplicates.height())}
+                                    </p>
+                                    <DataTable df=check.left_duplicates.clone() max_rows=PREVIEW_ROWS/>
+                                })}
+                                {(!check.right_duplicates.is_empty()).then(|| view! {
+                                    <p style="color: #b45309;">
+                                        {format!("⚠ 右のデータでキーが重複しています ({} 種類)。結合すると行が増えることがあります。", check.right_duplicates.height())}
+                                    </p>
+                                    <DataTable df=check.right_duplicates.clone() max_rows=PREVIEW_ROWS/>
+                                })}
+                                <p>{format!("右に対応する行がない左のキー: {} 件", check.left_unmatched.height())}</p>
+                                {(!check.left_unmatched.is_empty()).then(|| view! { <DataTable df=check.left_unmatched.clone() max_rows=PREVIEW_ROWS/> })}
+                                <p>{format!("左に対応する行がない右のキー: {} 件", check.right_unmatched.height())}</p>
+                                {(!check.right_unmatched.is_empty()).then(|| view! { <DataTable df=check.right_unmatched.clone() max_rows=PREVIEW_ROWS/> })}
+                            </div>
+                        }.into_view(),
+                        Some(Err(e)) => view! { <p style="color: red;">{format!("Error: {}", e)}</p> }.into_view(),
+                        None => view! { <div/> }.into_view(),
+                    }}
+
+                    {move || match result.get() {
+                        Some(Ok(df)) => view! {
+                            <div>
+                                <h4>{format!("結合結果: {} 行 × {} 列", df.height(), df.width())}</h4>
+                                <DataTable df=df max_rows=PREVIEW_ROWS/>
+                                <button class="primary-btn" on:click=save>"新しいデータとして保存"</button>
+                                {move || saved.get().map(|name| view! { <p>{format!("「{}」として保存しました。", name)}</p> })}
+                            </div>
+                        }.into_view(),
+                        _ => view! { <div/> }.into_view(),
+                    }}
+                }.into_view()
+            }}
+        </div>
+    }
+}
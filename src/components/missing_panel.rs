@@ -0,0 +1,152 @@
+use leptos::*;
+use crate::components::data_table::DataTable;
+use crate::state::AppData;
+use crate::utils::missing::{apply_missing, missing_patterns, null_summary, ImputeMethod, MissingAction};
+use crate::utils::recipe::Step;
+
+/// What the apply button does.
+#[derive(Clone, Copy, PartialEq)]
+enum Treatment {
+    DropRows,
+    DropColumns,
+    Impute,
+}
+
+const IMPUTE_METHODS: [&str; 5] = ["mean", "median", "mode", "constant", "locf"];
+
+fn impute_method(key: &str, constant: String) -> ImputeMethod {
+    match key {
+        "median" => ImputeMethod::Median,
+        "mode" => ImputeMethod::Mode,
+        "constant" => ImputeMethod::Constant(constant),
+        "locf" => ImputeMethod::Locf,
+        _ => ImputeMethod::Mean,
+    }
+}
+
+/// Missing-value counts and patterns for the page's dataset, and deletion /
+/// imputation written back to that dataset.
+#[component]
+pub fn MissingPanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let summary = create_memo(move |_| df.get().map(|df| null_summary(&df)));
+    let patterns = create_memo(move |_| df.get().map(|df| missing_patterns(&df)));
+    // Columns with at least one missing value
+    let incomplete = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| {
+            df.get_columns().iter().filter(|s| s.null_count() > 0).map(|s| s.name().to_string()).collect::<Vec<_>>()
+        }).unwrap_or_default())
+    });
+
+    let treatment = create_rw_signal(Treatment::DropRows);
+    // None: every column with missing values
+    let selected = create_rw_signal(Option::<Vec<String>>::None);
+    let method = create_rw_signal("mean".to_string());
+    let constant = create_rw_signal(String::new());
+    let (outcome, set_outcome) = create_signal(Option::<Result<String, String>>::None);
+
+    let columns = move || selected.get().unwrap_or_else(|| incomplete.get());
+    let toggle = move |name: String, on: bool| {
+        let mut current = selected.get_untracked().unwrap_or_else(|| incomplete.get_untracked());
+        current.retain(|c| *c != name);
+        if on {
+            current.push(name);
+        }
+        selected.set(Some(current));
+    };
+
+    let apply = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let chosen = selected.get_untracked().unwrap_or_else(|| incomplete.get_untracked());
+        let action = match treatment.get_untracked() {
+            Treatment::DropRows => MissingAction::DropRows(chosen),
+            Treatment::DropColumns => MissingAction::DropColumns(chosen),
+            Treatment::Impute => MissingAction::Impute(chosen, impute_method(&method.get_untracked(), constant.get_untracked())),
+        };
+        match apply_missing(&current.df, &action) {
+            Ok(df) => {
+                let message = format!(
+                    "適用前 {} 行 × {} 列 → 適用後 {} 行 × {} 列",
+                    current.df.height(), current.df.width(), df.height(), df.width()
+                );
+                app_data.apply_step(&current.name, Step::Missing { action }, df);
+                selected.set(None);
+                set_outcome.set(Some(Ok(message)));
+            }
+            Err(e) => set_outcome.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"欠損値の処理"</h3>
+            {move || match (summary.get(), patterns.get()) {
+                (Some(summary), Some(patterns)) => {
+                    let width = summary.height();
+                    view! {
+                        <div>
+                            <h4>"列ごとの欠損数"</h4>
+                            <DataTable df=summary max_rows=width/>
+                            <h4>"欠損パターン (同時に欠損している列の組み合わせ)"</h4>
+                            <DataTable df=patterns max_rows=20/>
+                        </div>
+                    }.into_view()
+                }
+                _ => view! { <p>"データを読み込むと、欠損値の状況が表示されます。"</p> }.into_view(),
+            }}
+
+            {move || df.with(|df| df.is_some()).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"処理"</label>
+                        <select on:change=move |ev| treatment.set(match event_target_value(&ev).as_str() {
+                            "drop_columns" => Treatment::DropColumns,
+                            "impute" => Treatment::Impute,
+                            _ => Treatment::DropRows,
+                        })>
+                            <option value="drop_rows" selected=move || treatment.get() == Treatment::DropRows>"欠損のある行を削除 (リストワイズ)"</option>
+                            <option value="drop_columns" selected=move || treatment.get() == Treatment::DropColumns>"列を削除"</option>
+                            <option value="impute" selected=move || treatment.get() == Treatment::Impute>"欠損を補完"</option>
+                        </select>
+                    </div>
+                    {move || (treatment.get() == Treatment::Impute).then(|| view! {
+                        <div class="input-group">
+                            <label>"補完の方法"</label>
+                            <select on:change=move |ev| method.set(event_target_value(&ev))>
+                                {IMPUTE_METHODS.into_iter().map(|key| view! {
+                                    <option value=key selected=move || method.get() == key>{impute_method(key, String::new()).label()}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                            {move || (method.get() == "constant").then(|| view! {
+                                <input type="text" placeholder="補完する値" prop:value=move || constant.get()
+                                    on:input=move |ev| constant.set(event_target_value(&ev))/>
+                            })}
+                        </div>
+                    })}
+                    <div class="input-group">
+                        <label>"対象の列"</label>
+                        {move || df.with(|df| df.as_ref().map(|df| df.get_column_names().iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap_or_default())
+                            .into_iter().map(|name| {
+                                let checked = columns().contains(&name);
+                                let label = name.clone();
+                                view! {
+                                    <label style="margin-right: 1rem;">
+                                        <input type="checkbox" prop:checked=checked
+                                            on:change=move |ev| toggle(name.clone(), event_target_checked(&ev))/>
+                                        {format!(" {}", label)}
+                                    </label>
+                                }
+                            }).collect::<Vec<_>>()}
+                    </div>
+                    <button class="primary-btn" on:click=apply>"適用"</button>
+                    {move || outcome.get().map(|result| match result {
+                        Ok(message) => view! { <p>{message}</p> }.into_view(),
+                        Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
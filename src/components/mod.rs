@@ -4,3 +4,20 @@ pub mod sidebar;
 pub mod guide;
 pub mod file_upload;
 pub mod info_section;
+pub mod result_view;
+pub mod data_table;
+pub mod sample_menu;
+pub mod paste_area;
+pub mod export_menu;
+pub mod dataset_picker;
+pub mod merge_panel;
+pub mod missing_panel;
+pub mod recode_panel;
+pub mod computed_column;
+pub mod scale_builder;
+pub mod filter_builder;
+pub mod outlier_panel;
+pub mod reshape_panel;
+pub mod history_panel;
+pub mod duplicate_panel;
+pub mod binning_panel;
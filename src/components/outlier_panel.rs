@@ -0,0 +1,180 @@
+use leptos::*;
+use polars::prelude::DataFrame;
+use crate::components::data_table::DataTable;
+use crate::state::AppData;
+use crate::utils::outliers::{change_log, detect_outliers, flagged_rows, treat_outliers, OutlierMethod, OutlierTreatment};
+use crate::utils::recipe::Step;
+
+/// Flagged rows and change-log rows shown at most.
+const PREVIEW_ROWS: usize = 50;
+
+const METHODS: [&str; 4] = ["iqr", "z", "mad", "mahalanobis"];
+
+fn method(key: &str, threshold: f64) -> OutlierMethod {
+    match key {
+        "z" => OutlierMethod::ZScore(threshold),
+        "mad" => OutlierMethod::ModifiedZ(threshold),
+        "mahalanobis" => OutlierMethod::Mahalanobis(threshold),
+        _ => OutlierMethod::Iqr(threshold),
+    }
+}
+
+fn default_threshold(key: &str) -> f64 {
+    match key {
+        "z" => 3.0,
+        "mad" => 3.5,
+        "mahalanobis" => 0.001,
+        _ => 1.5,
+    }
+}
+
+fn threshold_label(key: &str) -> &'static str {
+    match key {
+        "mahalanobis" => "有意水準 (χ² 分布)",
+        "iqr" => "IQR の倍数",
+        _ => "しきい値",
+    }
+}
+
+/// Finds outliers in numeric columns of the page's dataset and removes,
+/// winsorizes or blanks them, keeping a log of the changes in this session.
+#[component]
+pub fn OutlierPanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let numeric = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| {
+            df.get_columns().iter().filter(|s| s.dtype().is_numeric()).map(|s| s.name().to_string()).collect::<Vec<_>>()
+        }).unwrap_or_default())
+    });
+    let columns = create_rw_signal(Vec::<String>::new());
+    let method_key = create_rw_signal("iqr".to_string());
+    let threshold = create_rw_signal(default_threshold("iqr"));
+    let treatment = create_rw_signal(OutlierTreatment::SetNull);
+    // (summary, changed cells) per applied treatment, newest first
+    let log = create_rw_signal(Vec::<(String, DataFrame)>::new());
+    let (error, set_error) = create_signal(Option::<String>::None);
+
+    let selected = move || numeric.get().into_iter().filter(|c| columns.with(|l| l.contains(c))).collect::<Vec<_>>();
+    let detection = create_memo(move |_| {
+        let chosen = selected();
+        if chosen.is_empty() {
+            return None;
+        }
+        let m = method(&method_key.get(), threshold.get());
+        df.with(|df| df.as_ref().map(|df| {
+            detect_outliers(df, &chosen, m)
+                .and_then(|d| Ok((flagged_rows(df, &d)?, d)))
+                .map_err(|e| e.to_string())
+        }))
+    });
+
+    let apply = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let Some(Ok((_, found))) = detection.get_untracked() else { return };
+        let how = treatment.get_untracked();
+        match treat_outliers(&current.df, &found, how) {
+            Ok((df, changes)) => {
+                let summary = format!(
+                    "{} ({}): {} 行 {} セル → {}。{} 行 → {} 行",
+                    found.method.label(), found.columns.join(", "), found.rows().len(), changes.len(), how.label(),
+                    current.df.height(), df.height()
+                );
+                let step = Step::Outliers { columns: found.columns.clone(), method: found.method, treatment: how };
+                app_data.apply_step(&current.name, step, df);
+                log.update(|l| l.insert(0, (summary, change_log(&changes))));
+                set_error.set(None);
+            }
+            Err(e) => set_error.set(Some(format!("Error: {}", e))),
+        }
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"外れ値の検出と処理"</h3>
+            {move || (!numeric.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"対象の列"</label>
+                        {move || numeric.get().into_iter().map(|c| {
+                            let (checked, name, label) = (c.clone(), c.clone(), c);
+                            view! {
+                                <label style="margin-right: 1rem;">
+                                    <input type="checkbox" prop:checked=move || columns.with(|l| l.contains(&checked))
+                                        on:change=move |ev| {
+                                            let on = event_target_checked(&ev);
+                                            columns.update(|l| { l.retain(|x| *x != name); if on { l.push(name.clone()); } });
+                                        }/>
+                                    {format!(" {}", label)}
+                                </label>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                    <div class="input-group">
+                        <label>"検出方法"</label>
+                        <select on:change=move |ev| {
+                            let key = event_target_value(&ev);
+                            threshold.set(default_threshold(&key));
+                            method_key.set(key);
+                        }>
+                            {METHODS.into_iter().map(|key| view! {
+                                <option value=key selected=move || method_key.get() == key>{method(key, 0.0).label()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <label>{move || threshold_label(&method_key.get())}</label>
+                        <input type="number" step="any" style="width: 6rem;" prop:value=move || threshold.get().to_string()
+                            on:input=move |ev| if let Ok(v) = event_target_value(&ev).parse() { threshold.set(v) }/>
+                    </div>
+
+                    {move || match detection.get() {
+                        Some(Ok((rows, found))) => view! {
+                            <div>
+                                <p>{format!("外れ値: {} 行 ({} セル)", found.rows().len(), found.flags.len())}</p>
+                                {(!found.bounds.is_empty()).then(|| view! {
+                                    <ul>
+                                        {found.bounds.iter().map(|b| view! {
+                                            <li>{format!("{}: {:.3} 〜 {:.3} の外側", b.column, b.lower, b.upper)}</li>
+                                        }).collect::<Vec<_>>()}
+                                    </ul>
+                                })}
+                                {(rows.height() > 0).then(|| view! { <DataTable df=rows max_rows=PREVIEW_ROWS/> })}
+                            </div>
+                        }.into_view(),
+                        Some(Err(e)) => view! { <p style="color: red;">{format!("Error: {}", e)}</p> }.into_view(),
+                        None => view! { <p>"列を選ぶと外れ値を検出します。"</p> }.into_view(),
+                    }}
+
+                    <div class="input-group">
+                        <label>"処理"</label>
+                        <select on:change=move |ev| treatment.set(match event_target_value(&ev).as_str() {
+                            "remove" => OutlierTreatment::Remove,
+                            "winsorize" => OutlierTreatment::Winsorize,
+                            _ => OutlierTreatment::SetNull,
+                        })>
+                            {[("null", OutlierTreatment::SetNull), ("winsorize", OutlierTreatment::Winsorize), ("remove", OutlierTreatment::Remove)]
+                                .into_iter().map(|(key, t)| view! {
+                                    <option value=key selected=move || treatment.get() == t>{t.label()}</option>
+                                }).collect::<Vec<_>>()}
+                        </select>
+                        <button class="primary-btn" on:click=apply
+                            disabled=move || !matches!(detection.get(), Some(Ok((_, ref d))) if !d.flags.is_empty())>
+                            "適用"
+                        </button>
+                    </div>
+                    {move || error.get().map(|e| view! { <p style="color: red;">{e}</p> })}
+
+                    {move || (!log.with(|l| l.is_empty())).then(|| view! {
+                        <h4>"変更の記録"</h4>
+                        {log.get().into_iter().map(|(summary, changes)| view! {
+                            <details>
+                                <summary>{summary}</summary>
+                                <DataTable df=changes max_rows=PREVIEW_ROWS/>
+                            </details>
+                        }).collect::<Vec<_>>()}
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
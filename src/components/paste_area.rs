@@ -0,0 +1,72 @@
+use leptos::*;
+use crate::components::data_table::DataTable;
+use crate::components::file_upload::ImportReportView;
+use crate::state::AppData;
+use crate::utils::paste_import::{detect_header, read_pasted, split_pasted};
+use crate::utils::type_inference::ImportReport;
+
+/// Number of parsed rows shown before the pasted data is used.
+const PREVIEW_ROWS: usize = 8;
+
+/// Text area for a range copied from Excel / Google Sheets (tab-separated).
+#[component]
+pub fn PasteArea() -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+
+    let text = create_rw_signal(String::new());
+    // None: use the detected header setting
+    let header_choice = create_rw_signal(Option::<bool>::None);
+
+    let detected_header = create_memo(move |_| text.with(|t| detect_header(&split_pasted(t))));
+    let has_header = move || header_choice.get().unwrap_or_else(|| detected_header.get());
+    let parsed = create_memo(move |_| {
+        let header = has_header();
+        text.with(|t| (!t.trim().is_empty()).then(|| read_pasted(t, header).map_err(|e| e.to_string())))
+    });
+    let report = Signal::derive(move || match parsed.get() {
+        Some(Ok((_, report))) => report,
+        _ => ImportReport::default(),
+    });
+
+    let apply = move |_| {
+        if let Some(Ok((df, _))) = parsed.get_untracked() {
+            app_data.add_dataset("貼り付けデータ", df);
+        }
+    };
+
+    view! {
+        <details class="paste-area" style="margin-bottom: 2rem; padding: 1rem 2rem; border: 1px solid var(--border-color); border-radius: 12px; background: var(--surface);">
+            <summary style="cursor: pointer; font-weight: 600;">"表計算ソフトからコピーして貼り付け"</summary>
+            <p style="color: var(--text-secondary);">"Excel や Google スプレッドシートで範囲をコピーし、下の欄に貼り付けてください。"</p>
+            <textarea
+                rows="8"
+                style="width: 100%; font-family: monospace;"
+                placeholder="組\t英語\t国語\nA\t72\t65"
+                prop:value=move || text.get()
+                on:input=move |ev| {
+                    header_choice.set(None);
+                    text.set(event_target_value(&ev));
+                }
+            ></textarea>
+            <label>
+                <input type="checkbox"
+                    prop:checked=has_header
+                    on:change=move |ev| header_choice.set(Some(event_target_checked(&ev)))
+                />
+                " 1行目は見出し"
+                {move || header_choice.get().is_none().then_some(" (自動判定)")}
+            </label>
+
+            {move || match parsed.get() {
+                Some(Ok((df, _))) => view! { <DataTable df=df max_rows=PREVIEW_ROWS/> }.into_view(),
+                Some(Err(e)) => view! { <div style="color: red; margin-top: 1rem;">{e}</div> }.into_view(),
+                None => view! { <div/> }.into_view(),
+            }}
+            <ImportReportView report=report/>
+
+            <button class="primary-btn" style="margin-top: 1rem;" on:click=apply disabled=move || !matches!(parsed.get(), Some(Ok(_)))>
+                "貼り付けたデータを読み込む"
+            </button>
+        </details>
+    }
+}
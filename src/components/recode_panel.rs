@@ -0,0 +1,189 @@
+use anyhow::Result;
+use leptos::*;
+use polars::prelude::DataFrame;
+use std::collections::HashMap;
+use crate::state::AppData;
+use crate::utils::recipe::Step;
+use crate::utils::recode::{cast_column, merge_rare, recode, set_level_order, value_counts, CastTarget};
+
+/// A transform of one column returning the new frame, the step to record and a message for the user.
+type ColumnTransform<'a> = &'a dyn Fn(&DataFrame, &str) -> Result<(DataFrame, Step, String)>;
+
+/// Column type conversion, value recoding, merging rare categories and
+/// category level order for the page's dataset.
+#[component]
+pub fn RecodePanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let columns = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| df.get_column_names().iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap_or_default())
+    });
+    // Empty: the first column
+    let column_choice = create_rw_signal(String::new());
+    let column = create_memo(move |_| {
+        let choice = column_choice.get();
+        columns.with(|c| if c.contains(&choice) { choice } else { c.first().cloned().unwrap_or_default() })
+    });
+    let dtype = move || df.with(|df| df.as_ref().and_then(|df| df.column(&column.get()).ok().map(|s| s.dtype().to_string())).unwrap_or_default());
+    let counts = create_memo(move |_| df.with(|df| df.as_ref().and_then(|df| value_counts(df, &column.get()).ok()).unwrap_or_default()));
+
+    let target = create_rw_signal(CastTarget::Float);
+    let mapping = create_rw_signal(HashMap::<String, String>::new());
+    let into = create_rw_signal(Option::<String>::None);
+    let min_count = create_rw_signal(5usize);
+    let other_label = create_rw_signal("その他".to_string());
+    // None: the current order of the values
+    let order = create_rw_signal(Option::<Vec<String>>::None);
+    let current_order = move || order.get().unwrap_or_else(|| counts.get().into_iter().map(|(v, _)| v).collect());
+    let (outcome, set_outcome) = create_signal(Option::<Result<String, String>>::None);
+
+    let reset = move || {
+        mapping.set(HashMap::new());
+        into.set(None);
+        order.set(None);
+    };
+    // Runs a transform on the page's dataset and stores the result in place
+    let run = move |transform: ColumnTransform| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        match transform(&current.df, &column.get_untracked()) {
+            Ok((df, step, message)) => {
+                app_data.apply_step(&current.name, step, df);
+                reset();
+                set_outcome.set(Some(Ok(message)));
+            }
+            Err(e) => set_outcome.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+
+    let cast = move |_| run(&|df, name| {
+        let target = target.get_untracked();
+        let (df, report) = cast_column(df, name, target)?;
+        let message = if report.failed == 0 {
+            format!("「{}」を{}に変換しました。", name, target.label())
+        } else {
+            format!(
+                "「{}」を{}に変換しました。変換できなかった {} 個のセルは欠損値になりました (例: {})",
+                name, target.label(), report.failed, report.examples.join(", ")
+            )
+        };
+        Ok((df, Step::Cast { column: name.to_string(), target }, message))
+    });
+    let apply_mapping = move |_| run(&|df, name| {
+        let pairs: Vec<(String, String)> = mapping.get_untracked().into_iter().filter(|(_, to)| !to.trim().is_empty()).collect();
+        let output = into.get_untracked().unwrap_or_else(|| name.to_string());
+        let message = format!("{} 個の値を置き換え、「{}」に保存しました。", pairs.len(), output);
+        Ok((recode(df, name, &pairs, &output)?, Step::Recode { column: name.to_string(), mapping: pairs, into: output }, message))
+    });
+    let apply_rare = move |_| run(&|df, name| {
+        let label = other_label.get_untracked();
+        let min_count = min_count.get_untracked();
+        let (df, merged) = merge_rare(df, name, min_count, &label)?;
+        let message = format!("{} 個のカテゴリを「{}」にまとめました: {}", merged.len(), label, merged.join(", "));
+        Ok((df, Step::MergeRare { column: name.to_string(), min_count, other_label: label }, message))
+    });
+    let apply_order = move |_| run(&|df, name| {
+        let levels = current_order();
+        let message = format!("水準の順序を設定しました: {}", levels.join(" < "));
+        Ok((set_level_order(df, name, &levels)?, Step::LevelOrder { column: name.to_string(), levels }, message))
+    });
+    let shift = move |index: usize, up: bool| {
+        let mut levels = current_order();
+        let other = if up { index.checked_sub(1) } else { Some(index + 1).filter(|i| *i < levels.len()) };
+        if let Some(other) = other {
+            levels.swap(index, other);
+            order.set(Some(levels));
+        }
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"型の変換とカテゴリの再コード化"</h3>
+            {move || (!columns.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"列"</label>
+                        <select on:change=move |ev| { column_choice.set(event_target_value(&ev)); reset(); }>
+                            {columns.get().into_iter().map(|c| {
+                                let selected = column.get() == c;
+                                view! { <option value=c.clone() selected=selected>{c}</option> }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <small>{move || format!("現在の型: {}", dtype())}</small>
+                    </div>
+
+                    <h4>"型の変換"</h4>
+                    <div class="input-group">
+                        <select on:change=move |ev| {
+                            let label = event_target_value(&ev);
+                            if let Some(t) = CastTarget::ALL.into_iter().find(|t| t.label() == label) { target.set(t); }
+                        }>
+                            {CastTarget::ALL.into_iter().map(|t| view! {
+                                <option value=t.label() selected=move || target.get() == t>{t.label()}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <button class="primary-btn" on:click=cast>"変換"</button>
+                    </div>
+
+                    <h4>"値の置き換え"</h4>
+                    <table class="dataframe">
+                        <thead><tr><th>"値"</th><th>"件数"</th><th>"新しい値"</th></tr></thead>
+                        <tbody>
+                            {move || counts.get().into_iter().map(|(value, n)| {
+                                let key = value.clone();
+                                let current = value.clone();
+                                view! {
+                                    <tr>
+                                        <td>{value}</td>
+                                        <td>{n}</td>
+                                        <td><input type="text"
+                                            prop:value=move || mapping.with(|m| m.get(&current).cloned().unwrap_or_default())
+                                            on:input=move |ev| {
+                                                let to = event_target_value(&ev);
+                                                mapping.update(|m| { m.insert(key.clone(), to); });
+                                            }/></td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                    <div class="input-group">
+                        <label>"保存先の列名 (元の列を残すときは別の名前に)"</label>
+                        <input type="text" prop:value=move || into.get().unwrap_or_else(|| column.get())
+                            on:input=move |ev| into.set(Some(event_target_value(&ev)))/>
+                        <button class="primary-btn" on:click=apply_mapping>"置き換え"</button>
+                    </div>
+
+                    <h4>"少数カテゴリの統合"</h4>
+                    <div class="input-group">
+                        <label>"件数がこれ未満のカテゴリをまとめる"</label>
+                        <input type="number" min="1" prop:value=move || min_count.get().to_string()
+                            on:input=move |ev| if let Ok(n) = event_target_value(&ev).parse() { min_count.set(n) }/>
+                        <label>"まとめた後の名前"</label>
+                        <input type="text" prop:value=move || other_label.get()
+                            on:input=move |ev| other_label.set(event_target_value(&ev))/>
+                        <button class="primary-btn" on:click=apply_rare>"統合"</button>
+                    </div>
+
+                    <h4>"水準の順序"</h4>
+                    <ol>
+                        {move || current_order().into_iter().enumerate().map(|(i, level)| view! {
+                            <li>
+                                {level}
+                                " "
+                                <button on:click=move |_| shift(i, true)>"↑"</button>
+                                <button on:click=move |_| shift(i, false)>"↓"</button>
+                            </li>
+                        }).collect::<Vec<_>>()}
+                    </ol>
+                    <button class="primary-btn" on:click=apply_order>"この順序に設定"</button>
+
+                    {move || outcome.get().map(|result| match result {
+                        Ok(message) => view! { <p>{message}</p> }.into_view(),
+                        Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
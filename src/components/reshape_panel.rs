@@ -0,0 +1,147 @@
+use leptos::*;
+use crate::components::data_table::DataTable;
+use crate::state::AppData;
+use crate::utils::recipe::Step;
+use crate::utils::reshape::{MeltOptions, PivotOptions};
+
+const PREVIEW_ROWS: usize = 20;
+
+/// Converts the page's dataset between wide format (one column per time
+/// point) and long format (one row per time point) and saves the result as a
+/// new dataset.
+#[component]
+pub fn ReshapePanel(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let all_columns = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| df.get_column_names().iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap_or_default())
+    });
+    // true: wide → long (melt), false: long → wide (pivot)
+    let to_long = create_rw_signal(true);
+    let ids = create_rw_signal(Vec::<String>::new());
+    let values = create_rw_signal(Vec::<String>::new());
+    let variable_name = create_rw_signal("条件".to_string());
+    let value_name = create_rw_signal("値".to_string());
+    let names_from = create_rw_signal(String::new());
+    let values_from = create_rw_signal(String::new());
+    let (saved, set_saved) = create_signal(Option::<String>::None);
+
+    // Selections in column order
+    let in_order = move |chosen: RwSignal<Vec<String>>| all_columns.get().into_iter().filter(|c| chosen.with(|l| l.contains(c))).collect::<Vec<_>>();
+    let pick = move |chosen: RwSignal<String>, skip: usize| {
+        let current = chosen.get();
+        if all_columns.with(|c| c.contains(&current)) { current } else { all_columns.get().into_iter().nth(skip).unwrap_or_default() }
+    };
+    let step = create_memo(move |_| {
+        if to_long.get() {
+            let options = MeltOptions {
+                id_columns: in_order(ids),
+                value_columns: in_order(values),
+                variable_name: variable_name.get(),
+                value_name: value_name.get(),
+            };
+            (!options.value_columns.is_empty()).then_some(Step::Melt { options })
+        } else {
+            let options = PivotOptions { id_columns: in_order(ids), names_from: pick(names_from, 0), values_from: pick(values_from, 1) };
+            (!options.id_columns.is_empty()).then_some(Step::Pivot { options })
+        }
+    });
+    let result = create_memo(move |_| {
+        let step = step.get()?;
+        df.with(|df| df.as_ref().map(|df| step.apply(df).map_err(|e| e.to_string())))
+    });
+
+    let save = move |_| {
+        let (Some(Ok(df)), Some(step)) = (result.get_untracked(), step.get_untracked()) else { return };
+        let Some(source) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        let name = format!("{} ({})", source.name, if to_long.get_untracked() { "縦長" } else { "横長" });
+        set_saved.set(Some(app_data.add_derived_dataset(&name, df, &source.name, step)));
+    };
+    let checkboxes = move |chosen: RwSignal<Vec<String>>| {
+        all_columns.get().into_iter().map(|c| {
+            let (checked, name, label) = (c.clone(), c.clone(), c);
+            view! {
+                <label style="margin-right: 1rem;">
+                    <input type="checkbox" prop:checked=move || chosen.with(|l| l.contains(&checked))
+                        on:change=move |ev| {
+                            let on = event_target_checked(&ev);
+                            chosen.update(|l| { l.retain(|x| *x != name); if on { l.push(name.clone()); } });
+                            set_saved.set(None);
+                        }/>
+                    {format!(" {}", label)}
+                </label>
+            }
+        }).collect::<Vec<_>>()
+    };
+    let column_select = move |label: &'static str, chosen: RwSignal<String>, skip: usize| view! {
+        <div class="input-group">
+            <label>{label}</label>
+            <select on:change=move |ev| chosen.set(event_target_value(&ev))>
+                {move || all_columns.get().into_iter().map(|c| {
+                    let selected = pick(chosen, skip) == c;
+                    view! { <option value=c.clone() selected=selected>{c}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    };
+    let text_input = move |label: &'static str, value: RwSignal<String>| view! {
+        <div class="input-group">
+            <label>{label}</label>
+            <input type="text" prop:value=move || value.get() on:input=move |ev| value.set(event_target_value(&ev))/>
+        </div>
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"データの形の変換 (横長 ⇔ 縦長)"</h3>
+            {move || (!all_columns.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <div class="input-group">
+                        <label>"変換"</label>
+                        <select on:change=move |ev| { to_long.set(event_target_value(&ev) == "melt"); set_saved.set(None); }>
+                            <option value="melt" selected=move || to_long.get()>"横長 → 縦長 (測定時点ごとの列を行にする)"</option>
+                            <option value="pivot" selected=move || !to_long.get()>"縦長 → 横長 (条件ごとに列を作る)"</option>
+                        </select>
+                    </div>
+                    <div class="input-group">
+                        <label>"ID 列 (行を識別する列)"</label>
+                        {move || checkboxes(ids)}
+                    </div>
+                    {move || if to_long.get() {
+                        view! {
+                            <div>
+                                <div class="input-group">
+                                    <label>"値の列 (縦に並べる列)"</label>
+                                    {move || checkboxes(values)}
+                                </div>
+                                {text_input("元の列名を入れる列の名前", variable_name)}
+                                {text_input("値を入れる列の名前", value_name)}
+                            </div>
+                        }.into_view()
+                    } else {
+                        view! {
+                            <div>
+                                {column_select("列名にする列", names_from, 0)}
+                                {column_select("値の列", values_from, 1)}
+                            </div>
+                        }.into_view()
+                    }}
+
+                    {move || match result.get() {
+                        Some(Ok(df)) => view! {
+                            <div>
+                                <h4>{format!("変換結果: {} 行 × {} 列", df.height(), df.width())}</h4>
+                                <DataTable df=df max_rows=PREVIEW_ROWS/>
+                                <button class="primary-btn" on:click=save>"新しいデータとして保存"</button>
+                                {move || saved.get().map(|name| view! { <p>{format!("「{}」として保存しました。", name)}</p> })}
+                            </div>
+                        }.into_view(),
+                        Some(Err(e)) => view! { <p style="color: red;">{format!("Error: {}", e)}</p> }.into_view(),
+                        None => view! { <p>"ID 列と値の列を選ぶと、変換結果が表示されます。"</p> }.into_view(),
+                    }}
+                </div>
+            })}
+        </div>
+    }
+}
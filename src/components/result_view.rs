@@ -0,0 +1,84 @@
+use leptos::*;
+
+use crate::report::{AnalysisResult, Cell, PlotSpec, Table};
+
+/// Renders an analysis result (or the error that replaced it) as HTML tables,
+/// notes, the interpretation box and its plots.
+#[component]
+pub fn ResultView(#[prop(into)] result: Signal<Option<Result<AnalysisResult, String>>>) -> impl IntoView {
+    move || match result.get() {
+        None => view! { <div/> }.into_view(),
+        Some(Err(e)) => view! {
+            <div class="result-box">
+                <p class="error-msg">{format!("Error: {}", e)}</p>
+            </div>
+        }.into_view(),
+        Some(Ok(res)) => view! {
+            <div class="result-box">
+                <h3>{res.title.clone()}</h3>
+                {res.tables.into_iter().map(|t| view! { <ResultTable table=t/> }).collect::<Vec<_>>()}
+                {(!res.notes.is_empty()).then(|| view! {
+                    <ul class="result-notes">
+                        {res.notes.into_iter().map(|n| view! { <li>{n}</li> }).collect::<Vec<_>>()}
+                    </ul>
+                })}
+            </div>
+            {res.interpretation.map(|text| view! {
+                <div class="interpretation-box" style="margin-top: 20px; padding: 15px; background-color: #f9f9f9; border-left: 5px solid #1e90ff;">
+                    <h4 style="margin-top: 0;">"解釈の補助"</h4>
+                    <p style="white-space: pre-wrap;">{text}</p>
+                </div>
+            })}
+            {res.plots.into_iter().map(|p| view! { <PlotView plot=p/> }).collect::<Vec<_>>()}
+        }.into_view(),
+    }
+}
+
+#[component]
+fn ResultTable(table: Table) -> impl IntoView {
+    view! {
+        {table.title.map(|t| view! { <h4>{t}</h4> })}
+        <div class="table-container">
+            <table class="dataframe result-table">
+                <thead>
+                    <tr>{table.header.into_iter().map(|h| view! { <th>{h}</th> }).collect::<Vec<_>>()}</tr>
+                </thead>
+                <tbody>
+                    {table.rows.into_iter().map(|row| view! {
+                        <tr>{row.into_iter().map(|c| view! { <ResultCell cell=c/> }).collect::<Vec<_>>()}</tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+#[component]
+fn ResultCell(cell: Cell) -> impl IntoView {
+    let numeric = cell.as_f64().is_some();
+    let mark = cell.mark();
+    view! {
+        <td class:numeric=numeric>
+            {cell.value_text()}
+            {mark.map(|m| view! { <span class="sig-mark" class:sig=m != "n.s.">{format!(" {}", m)}</span> })}
+        </td>
+    }
+}
+
+/// A plot container; the figure is drawn once the element is in the document.
+#[component]
+fn PlotView(plot: PlotSpec) -> impl IntoView {
+    let node_ref = create_node_ref::<html::Div>();
+    node_ref.on_load(move |el| {
+        request_animation_frame(move || {
+            #[cfg(target_arch = "wasm32")]
+            crate::plot::draw(&el, &plot);
+            #[cfg(not(target_arch = "wasm32"))]
+            let _ = (el, plot);
+        });
+    });
+
+    view! {
+        <div class="plot-area" node_ref=node_ref style="width: 100%; height: 400px; margin-top: 20px;"></div>
+    }
+}
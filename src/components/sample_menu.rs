@@ -0,0 +1,39 @@
+use leptos::*;
+use crate::state::AppData;
+use crate::utils::samples::{self, SAMPLES};
+
+/// Drop-down that loads a bundled sample dataset and makes it the active data.
+/// `ids` limits the list (every sample when empty); `on_load` receives the id
+/// after the data has been set, so a page can preselect matching columns.
+#[component]
+pub fn SampleMenu(
+    #[prop(optional)] ids: &'static [&'static str],
+    #[prop(optional, into)] on_load: Option<Callback<&'static str>>,
+) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+
+    let load_sample = move |ev: ev::Event| {
+        let Some(sample) = samples::find(&event_target_value(&ev)) else { return };
+        match sample.load() {
+            Ok(df) => {
+                // Loading the same sample again replaces it instead of adding a copy
+                app_data.set_dataset(sample.file_name, df);
+                if let Some(on_load) = on_load {
+                    on_load.call(sample.id);
+                }
+            }
+            Err(e) => app_data.error_msg.set(Some(format!("Error: {}", e))),
+        }
+    };
+
+    let choices = SAMPLES.iter().filter(|s| ids.is_empty() || ids.contains(&s.id));
+
+    view! {
+        <div class="sample-menu" style="margin: 0.5rem 0;">
+            <select on:change=load_sample>
+                <option value="" selected>"サンプルデータを読み込む…"</option>
+                {choices.map(|s| view! { <option value=s.id>{s.title}</option> }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    }
+}
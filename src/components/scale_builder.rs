@@ -0,0 +1,117 @@
+use leptos::*;
+use crate::state::AppData;
+use crate::utils::recipe::Step;
+use crate::utils::scale::{score_scale, ScaleSpec, ScoreMethod};
+
+/// Builds a questionnaire scale score from item columns, with reverse-keyed
+/// items, and appends it to the page's dataset.
+#[component]
+pub fn ScaleBuilder(dataset: RwSignal<Option<String>>) -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let df = app_data.source_df(dataset);
+
+    let numeric = create_memo(move |_| {
+        df.with(|df| df.as_ref().map(|df| {
+            df.get_columns().iter().filter(|s| s.dtype().is_numeric()).map(|s| s.name().to_string()).collect::<Vec<_>>()
+        }).unwrap_or_default())
+    });
+    let items = create_rw_signal(Vec::<String>::new());
+    let reversed = create_rw_signal(Vec::<String>::new());
+    let min = create_rw_signal(1.0);
+    let max = create_rw_signal(5.0);
+    let method = create_rw_signal(ScoreMethod::Mean);
+    let max_missing = create_rw_signal(0usize);
+    let name = create_rw_signal("尺度得点".to_string());
+    let (outcome, set_outcome) = create_signal(Option::<Result<String, String>>::None);
+
+    let toggle = |list: RwSignal<Vec<String>>, column: String, on: bool| {
+        list.update(|l| {
+            l.retain(|c| *c != column);
+            if on {
+                l.push(column);
+            }
+        });
+    };
+
+    let build = move |_| {
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        // Keep the items in column order rather than click order
+        let chosen = items.get_untracked();
+        let spec = ScaleSpec {
+            name: name.get_untracked(),
+            items: numeric.get_untracked().into_iter().filter(|c| chosen.contains(c)).collect(),
+            reversed: reversed.get_untracked().into_iter().filter(|c| chosen.contains(c)).collect(),
+            min: min.get_untracked(),
+            max: max.get_untracked(),
+            method: method.get_untracked(),
+            max_missing: max_missing.get_untracked(),
+        };
+        match score_scale(&current.df, &spec) {
+            Ok((df, report)) => {
+                let message = format!(
+                    "列「{}」を追加しました ({} 項目、逆転項目 {})。得点あり {} 行、欠損が多く算出しなかった行 {} 行",
+                    spec.name.trim(), spec.items.len(), spec.reversed.len(), report.scored, report.too_many_missing
+                );
+                app_data.apply_step(&current.name, Step::Scale { spec }, df);
+                set_outcome.set(Some(Ok(message)));
+            }
+            Err(e) => set_outcome.set(Some(Err(format!("Error: {}", e)))),
+        }
+    };
+    let number_input = move |value: RwSignal<f64>| view! {
+        <input type="number" style="width: 6rem;" prop:value=move || value.get().to_string()
+            on:input=move |ev| if let Ok(v) = event_target_value(&ev).parse() { value.set(v) }/>
+    };
+
+    view! {
+        <div>
+            <h3 class="subsection-title">"尺度得点の作成 (逆転項目・合計/平均)"</h3>
+            {move || (!numeric.with(|c| c.is_empty())).then(|| view! {
+                <div class="control-panel">
+                    <table class="dataframe">
+                        <thead><tr><th>"列"</th><th>"項目に含める"</th><th>"逆転項目"</th></tr></thead>
+                        <tbody>
+                            {move || numeric.get().into_iter().map(|column| {
+                                let (c1, c2, c3, c4) = (column.clone(), column.clone(), column.clone(), column.clone());
+                                view! {
+                                    <tr>
+                                        <td>{column}</td>
+                                        <td><input type="checkbox" prop:checked=move || items.with(|l| l.contains(&c1))
+                                            on:change=move |ev| toggle(items, c2.clone(), event_target_checked(&ev))/></td>
+                                        <td><input type="checkbox" prop:checked=move || reversed.with(|l| l.contains(&c3))
+                                            on:change=move |ev| toggle(reversed, c4.clone(), event_target_checked(&ev))/></td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                    <div class="input-group">
+                        <label>"回答の範囲 (逆転: 最小 + 最大 − 回答)"</label>
+                        {number_input(min)}" 〜 "{number_input(max)}
+                    </div>
+                    <div class="input-group">
+                        <label>"得点"</label>
+                        <select on:change=move |ev| method.set(if event_target_value(&ev) == "sum" { ScoreMethod::Sum } else { ScoreMethod::Mean })>
+                            <option value="mean" selected=move || method.get() == ScoreMethod::Mean>{ScoreMethod::Mean.label()}</option>
+                            <option value="sum" selected=move || method.get() == ScoreMethod::Sum>{ScoreMethod::Sum.label()}</option>
+                        </select>
+                    </div>
+                    <div class="input-group">
+                        <label>"未回答を許す項目数 (超えると得点は欠損、合計は回答項目の平均 × 項目数)"</label>
+                        <input type="number" min="0" style="width: 6rem;" prop:value=move || max_missing.get().to_string()
+                            on:input=move |ev| if let Ok(n) = event_target_value(&ev).parse() { max_missing.set(n) }/>
+                    </div>
+                    <div class="input-group">
+                        <label>"新しい列の名前"</label>
+                        <input type="text" prop:value=move || name.get() on:input=move |ev| name.set(event_target_value(&ev))/>
+                    </div>
+                    <button class="primary-btn" on:click=build disabled=move || items.with(|l| l.is_empty())>"尺度得点を追加"</button>
+                    {move || outcome.get().map(|result| match result {
+                        Ok(message) => view! { <p>{message}</p> }.into_view(),
+                        Err(e) => view! { <p style="color: red;">{e}</p> }.into_view(),
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
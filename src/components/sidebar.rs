@@ -1,8 +1,12 @@
 use leptos::*;
 use leptos_router::*;
+use crate::state::AppData;
 
 #[component]
 pub fn Sidebar() -> impl IntoView {
+    let app_data = use_context::<AppData>().expect("AppData context not found");
+    let names = create_memo(move |_| app_data.datasets.with(|r| r.names()));
+
     view! {
         <aside style="width: 250px; background: var(--surface); border-right: 1px solid var(--border-color); height: 100vh; position: fixed; left: 0; top: 0; padding: 2rem 1rem; overflow-y: auto;">
             <div style="margin-bottom: 2rem; font-weight: 700; font-size: 1.5rem; color: var(--primary-color);">
@@ -70,6 +74,32 @@ pub fn Sidebar() -> impl IntoView {
                 </ul>
             </nav>
 
+            {move || (!names.with(|n| n.is_empty())).then(|| view! {
+                <div class="dataset-switcher" style="margin-top: 2rem;">
+                    <h3 style="font-size: 0.9rem; color: var(--text-secondary); margin-bottom: 0.5rem;">"データセット"</h3>
+                    <ul style="list-style: none; padding: 0; font-size: 0.85rem;">
+                        {names.get().into_iter().map(|name| {
+                            let is_active = app_data.active_name().as_deref() == Some(name.as_str());
+                            let select_name = name.clone();
+                            let remove_name = name.clone();
+                            view! {
+                                <li style="display: flex; align-items: center; margin-bottom: 0.25rem;">
+                                    <label style="flex: 1; cursor: pointer; overflow-wrap: anywhere;" style:font-weight=if is_active { "700" } else { "400" }>
+                                        <input type="radio" name="active_dataset" checked=is_active
+                                            on:change=move |_| app_data.datasets.update(|r| r.set_active(&select_name))
+                                        />
+                                        " " {name}
+                                    </label>
+                                    <button class="link-button" title="閉じる" style="padding: 0 0.4rem;"
+                                        on:click=move |_| app_data.datasets.update(|r| r.remove(&remove_name))
+                                    >"×"</button>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                </div>
+            })}
+
             <div style="margin-top: 2rem;">
                  <h3 style="font-size: 0.9rem; color: var(--text-secondary); margin-bottom: 1rem;">"🎓 統計学習のコツ"</h3>
                  <ul style="padding-left: 1.2rem; color: var(--text-secondary); font-size: 0.85rem;">
@@ -0,0 +1,186 @@
+//! Named datasets loaded in the app, one of which is active, each with the
+//! cleaning steps applied to it since it was loaded.
+
+use polars::prelude::*;
+
+use crate::utils::recipe::Step;
+
+/// Steps applied to a dataset, with the frames needed to undo and redo them.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    /// Applied steps with the frame before each
+    done: Vec<(Step, DataFrame)>,
+    /// Undone steps with the frame after each, most recently undone last
+    undone: Vec<(Step, DataFrame)>,
+}
+
+impl History {
+    /// The applied steps in order: the dataset's recipe.
+    pub fn steps(&self) -> Vec<Step> {
+        self.done.iter().map(|(step, _)| step.clone()).collect()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Records a step applied to `before`. Steps undone earlier can no longer be redone.
+    pub fn record(&mut self, step: Step, before: DataFrame) {
+        self.done.push((step, before));
+        self.undone.clear();
+    }
+
+    /// Takes back the last step; returns the frame to go back to.
+    pub fn undo(&mut self, current: DataFrame) -> Option<DataFrame> {
+        let (step, before) = self.done.pop()?;
+        self.undone.push((step, current));
+        Some(before)
+    }
+
+    /// Applies the last undone step again; returns the frame it produced.
+    pub fn redo(&mut self, current: DataFrame) -> Option<DataFrame> {
+        let (step, after) = self.undone.pop()?;
+        self.done.push((step, current));
+        Some(after)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Dataset {
+    pub name: String,
+    pub df: DataFrame,
+    pub history: History,
+}
+
+/// Datasets in load order plus the name of the active one.
+#[derive(Clone, Debug, Default)]
+pub struct DatasetRegistry {
+    datasets: Vec<Dataset>,
+    active: Option<String>,
+}
+
+impl DatasetRegistry {
+    pub fn is_empty(&self) -> bool {
+        self.datasets.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.datasets.iter().map(|d| d.name.clone()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Dataset> {
+        self.datasets.iter().find(|d| d.name == name)
+    }
+
+    pub fn active(&self) -> Option<&Dataset> {
+        self.active.as_deref().and_then(|name| self.get(name))
+    }
+
+    /// The named dataset, or the active one when `name` is `None` or no longer loaded.
+    pub fn resolve(&self, name: Option<&str>) -> Option<&Dataset> {
+        name.and_then(|n| self.get(n)).or_else(|| self.active())
+    }
+
+    /// Adds a dataset under a name not used yet (`data.csv`, `data.csv (2)`, …)
+    /// and makes it active. Returns the name it was stored under.
+    pub fn add(&mut self, name: &str, df: DataFrame) -> String {
+        let name = (1..)
+            .map(|i| if i == 1 { name.to_string() } else { format!("{} ({})", name, i) })
+            .find(|candidate| self.get(candidate).is_none())
+            .expect("unbounded range");
+        self.datasets.push(Dataset { name: name.clone(), df, history: History::default() });
+        self.active = Some(name.clone());
+        name
+    }
+
+    /// Adds a dataset produced from `source` by `step` (e.g. reshaping), which
+    /// inherits the source's history so the whole recipe stays together.
+    pub fn add_derived(&mut self, name: &str, df: DataFrame, source: &str, step: Step) -> String {
+        let history = self.get(source).map(|d| {
+            let mut history = d.history.clone();
+            history.record(step, d.df.clone());
+            history
+        });
+        let name = self.add(name, df);
+        if let (Some(history), Some(dataset)) = (history, self.datasets.last_mut()) {
+            dataset.history = history;
+        }
+        name
+    }
+
+    /// Replaces the dataset with this name (or adds it) and makes it active.
+    /// The data counts as newly loaded, so its history starts over.
+    pub fn set(&mut self, name: &str, df: DataFrame) {
+        match self.datasets.iter_mut().find(|d| d.name == name) {
+            Some(dataset) => {
+                dataset.df = df;
+                dataset.history = History::default();
+            }
+            None => self.datasets.push(Dataset { name: name.to_string(), df, history: History::default() }),
+        }
+        self.active = Some(name.to_string());
+    }
+
+    /// Replaces the frame of an existing dataset without changing the active one.
+    pub fn replace(&mut self, name: &str, df: DataFrame) -> bool {
+        match self.datasets.iter_mut().find(|d| d.name == name) {
+            Some(dataset) => {
+                dataset.df = df;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the frame of an existing dataset with the result of `step`,
+    /// recording it in the history. The active dataset does not change.
+    pub fn apply(&mut self, name: &str, step: Step, df: DataFrame) -> bool {
+        match self.datasets.iter_mut().find(|d| d.name == name) {
+            Some(dataset) => {
+                let before = std::mem::replace(&mut dataset.df, df);
+                dataset.history.record(step, before);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes back the last step applied to the dataset. False if there is none.
+    pub fn undo(&mut self, name: &str) -> bool {
+        self.step_history(name, History::undo)
+    }
+
+    /// Applies the last undone step again. False if there is none.
+    pub fn redo(&mut self, name: &str) -> bool {
+        self.step_history(name, History::redo)
+    }
+
+    fn step_history(&mut self, name: &str, go: fn(&mut History, DataFrame) -> Option<DataFrame>) -> bool {
+        let Some(dataset) = self.datasets.iter_mut().find(|d| d.name == name) else { return false };
+        match go(&mut dataset.history, dataset.df.clone()) {
+            Some(df) => {
+                dataset.df = df;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_active(&mut self, name: &str) {
+        if self.get(name).is_some() {
+            self.active = Some(name.to_string());
+        }
+    }
+
+    /// Removes a dataset; if it was active, the most recently loaded remaining one becomes active.
+    pub fn remove(&mut self, name: &str) {
+        self.datasets.retain(|d| d.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = self.datasets.last().map(|d| d.name.clone());
+        }
+    }
+}
@@ -4,8 +4,14 @@ use wasm_bindgen::prelude::*;
 mod app;
 mod components;
 mod state;
-mod utils;
+pub mod datasets;
+pub mod utils;
 mod pages;
+pub mod stats;
+pub mod report;
+pub mod plot;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
 
 use app::App;
 
@@ -1,14 +1,18 @@
-use leptos::*;
-
-mod app;
-mod components;
-mod state;
-mod utils;
-mod pages;
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use easy_stat_r::cli::{run, Args};
 
-use app::App;
+    let result = Args::parse(std::env::args().skip(1)).and_then(|args| run(&args));
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
 
+#[cfg(target_arch = "wasm32")]
 fn main() {
-    console_error_panic_hook::set_once();
-    mount_to_body(|| view! { <App/> });
+    // In the browser the app is mounted by the library's `#[wasm_bindgen(start)]` entry point.
 }
@@ -1,4 +1,5 @@
 use leptos::*;
+use crate::components::sample_menu::SampleMenu;
 
 #[component]
 pub fn ChiSquare() -> impl IntoView {
@@ -8,6 +9,7 @@ pub fn ChiSquare() -> impl IntoView {
                 <div class="section-icon"><i class="fas fa-border-all"></i></div>
                 "カイ二乗検定"
             </h2>
+            <SampleMenu ids=&["chi_square_demo"]/>
             <p>"Coming soon: Cross tabulation, Chi-square test."</p>
         </div>
     }
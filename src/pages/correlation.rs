@@ -1,4 +1,5 @@
 use leptos::*;
+use crate::components::sample_menu::SampleMenu;
 
 #[component]
 pub fn Correlation() -> impl IntoView {
@@ -8,6 +9,7 @@ pub fn Correlation() -> impl IntoView {
                 <div class="section-icon"><i class="fas fa-project-diagram"></i></div>
                 "相関分析"
             </h2>
+            <SampleMenu ids=&["correlation_demo"]/>
             <p>"Coming soon: Heatmap, scatter matrix."</p>
         </div>
     }
@@ -1,14 +1,38 @@
 use leptos::*;
+use crate::components::binning_panel::BinningPanel;
+use crate::components::computed_column::ComputedColumnPanel;
+use crate::components::dataset_picker::DatasetPicker;
+use crate::components::duplicate_panel::DuplicatePanel;
+use crate::components::history_panel::HistoryPanel;
+use crate::components::merge_panel::MergePanel;
+use crate::components::missing_panel::MissingPanel;
+use crate::components::outlier_panel::OutlierPanel;
+use crate::components::recode_panel::RecodePanel;
+use crate::components::reshape_panel::ReshapePanel;
+use crate::components::scale_builder::ScaleBuilder;
 
 #[component]
 pub fn DataCleaning() -> impl IntoView {
+    // Dataset cleaned on this page (None: the active dataset)
+    let dataset = create_rw_signal(Option::<String>::None);
+
     view! {
         <div class="fade-in">
             <h2 class="section-title">
                 <div class="section-icon"><i class="fas fa-broom"></i></div>
                 "データクレンジング"
             </h2>
-            <p>"Coming soon: Missing value handling, outlier removal."</p>
+            <DatasetPicker choice=dataset/>
+            <HistoryPanel dataset=dataset/>
+            <MissingPanel dataset=dataset/>
+            <DuplicatePanel dataset=dataset/>
+            <OutlierPanel dataset=dataset/>
+            <RecodePanel dataset=dataset/>
+            <ComputedColumnPanel dataset=dataset/>
+            <BinningPanel dataset=dataset/>
+            <ScaleBuilder dataset=dataset/>
+            <ReshapePanel dataset=dataset/>
+            <MergePanel/>
         </div>
     }
 }
@@ -1,10 +1,35 @@
 use leptos::*;
 use crate::state::AppData;
+use crate::components::dataset_picker::DatasetPicker;
+use crate::components::filter_builder::FilterBadge;
+use crate::components::sample_menu::SampleMenu;
+use crate::utils::dates::{add_date_parts, date_columns};
+use crate::utils::recipe::Step;
 use polars::prelude::*;
 
 #[component]
 pub fn Eda() -> impl IntoView {
     let app_data = use_context::<AppData>().expect("AppData context not found");
+    // Dataset analyzed on this page (None: the active dataset)
+    let dataset = create_rw_signal(Option::<String>::None);
+    let df = app_data.page_df(dataset);
+
+    let (date_col, set_date_col) = create_signal(String::new());
+    let (date_error, set_date_error) = create_signal(Option::<String>::None);
+    let dates = create_memo(move |_| df.with(|df| df.as_ref().and_then(|df| df.as_ref().ok()).map(date_columns).unwrap_or_default()));
+
+    let derive_parts = move |_| {
+        let column = date_col.get_untracked();
+        let column = if column.is_empty() { dates.get_untracked().into_iter().next().unwrap_or_default() } else { column };
+        let Some(current) = app_data.dataset_untracked(dataset.get_untracked().as_deref()) else { return };
+        match add_date_parts(&current.df, &column) {
+            Ok(df) => {
+                set_date_error.set(None);
+                app_data.apply_step(&current.name, Step::DateParts { column }, df);
+            }
+            Err(e) => set_date_error.set(Some(format!("Error: {}", e))),
+        }
+    };
 
     view! {
         <div class="fade-in">
@@ -12,12 +37,27 @@ pub fn Eda() -> impl IntoView {
                 <div class="section-icon"><i class="fas fa-search"></i></div>
                 "探索的データ分析 (EDA)"
             </h2>
+            <SampleMenu ids=&["eda_demo"] on_load=move |_| dataset.set(None)/>
+            <DatasetPicker choice=dataset/>
+            <FilterBadge choice=dataset/>
 
-            {move || match app_data.df.get() {
-                Some(df) => {
+            {move || match df.get() {
+                Some(Ok(df)) => {
                     let summary = calculate_summary(&df);
                     view! {
                         <div>
+                            {move || (!dates.get().is_empty()).then(|| view! {
+                                <div class="control-panel">
+                                    <div class="input-group">
+                                        <label>"日付列から年・月・曜日 (1=月曜 … 7=日曜) の列を作成"</label>
+                                        <select on:change=move |ev| set_date_col.set(event_target_value(&ev))>
+                                            {dates.get().into_iter().map(|c| view! { <option value=c.clone()>{c}</option> }).collect::<Vec<_>>()}
+                                        </select>
+                                    </div>
+                                    <button class="primary-btn" on:click=derive_parts>"列を追加"</button>
+                                    {move || date_error.get().map(|e| view! { <p style="color: red;">{e}</p> })}
+                                </div>
+                            })}
                             <h3 class="subsection-title">"データフレーム"</h3>
                             {render_dataframe(df.clone())}
                             <h3 class="subsection-title">"要約統計量"</h3>
@@ -25,6 +65,7 @@ pub fn Eda() -> impl IntoView {
                         </div>
                     }.into_view()
                 }
+                Some(Err(e)) => view! { <p style="color: red;">{e}</p> }.into_view(),
                 None => {
                     view! {
                         <p>"データを表示するには、ファイルをアップロードしてください。"</p>
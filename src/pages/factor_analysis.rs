@@ -1,38 +1,34 @@
 use leptos::*;
 use crate::state::AppData;
-use nalgebra::{DMatrix, DVector, SymmetricEigen};
+use crate::components::dataset_picker::DatasetPicker;
+use crate::components::filter_builder::FilterBadge;
+use crate::components::result_view::ResultView;
+use crate::components::sample_menu::SampleMenu;
+use crate::report::AnalysisResult;
+use crate::stats::factor::{self, Rotation};
 use std::collections::HashSet;
-use serde_json::json;
 
 #[component]
 pub fn FactorAnalysis() -> impl IntoView {
     let app_data = use_context::<AppData>().expect("AppData context not found");
+    // Dataset analyzed on this page (None: the active dataset)
+    let dataset = create_rw_signal(Option::<String>::None);
+    let df = app_data.page_df(dataset);
 
     // UI State
     let (target_cols, set_target_cols) = create_signal(HashSet::<String>::new());
     let (n_factors, set_n_factors) = create_signal(2); 
     let (do_rotation, set_do_rotation) = create_signal(true); 
-    let (result_summary, set_result_summary) = create_signal(Option::<Vec<String>>::None);
-
-    let plot_id = "fa_scree_plot";
+    let (result, set_result) = create_signal(Option::<Result<AnalysisResult, String>>::None);
 
      let columns = create_memo(move |_| {
-        if let Some(df) = app_data.df.get() {
+        if let Some(Ok(df)) = df.get() {
             df.get_column_names().into_iter().map(|s| s.to_string()).collect::<Vec<_>>()
         } else {
             vec![]
         }
     });
 
-    let draw_plot = move |data: serde_json::Value, layout: serde_json::Value| {
-        #[cfg(target_arch = "wasm32")]
-        {
-             let d_str = data.to_string();
-             let l_str = layout.to_string();
-             let _ = js_sys::eval(&format!("window.drawPlot('{}', '{}', '{}')", plot_id, d_str, l_str));
-        }
-    };
-
      let toggle_col = move |col: String| {
         set_target_cols.update(|cols| {
             if cols.contains(&col) {
@@ -43,158 +39,28 @@ pub fn FactorAnalysis() -> impl IntoView {
         });
     };
 
-    fn varimax(loadings: &DMatrix<f64>, max_iter: usize, tol: f64) -> DMatrix<f64> {
-        let (p, k) = loadings.shape();
-        let mut r = DMatrix::identity(k, k);
-        let mut d = 0.0;
-        
-        for _ in 0..max_iter {
-            let d_old = d;
-            let lambda = loadings * &r;
-            let lambda_sq = lambda.map(|x| x*x);
-             let c_sums = lambda_sq.column_sum();
-             let alpha = c_sums.map(|x| x / p as f64);
-             let mut term2 = lambda.clone();
-             for j in 0..k {
-                 let s = alpha[j];
-                 for i in 0..p {
-                     term2[(i,j)] *= s;
-                 }
-             }
-             let b = lambda.map(|x| x.powi(3)) - term2;
-             let m = loadings.transpose() * b;
-             let svd = m.svd(true, true);
-             if let (Some(u), Some(v_t)) = (svd.u, svd.v_t) {
-                 let r_new = u * v_t;
-                 d = svd.singular_values.sum(); 
-                 r = r_new;
-             } else {
-                 break; 
-             }
-             if (d - d_old).abs() < tol {
-                 break;
-             }
-        }
-        loadings * r
-    }
+    // Preselect the questionnaire items (every column except the respondent number)
+    let on_sample = move |_| {
+        dataset.set(None);
+        set_result.set(None);
+        set_target_cols.set(columns.get_untracked().into_iter().filter(|c| c != "No").collect());
+    };
 
     let calculate = move |_| {
-         let df_opt = app_data.df.get_untracked();
-        if df_opt.is_none() { return; }
-        let df = df_opt.unwrap();
-        
+        let df = match df.get_untracked() {
+            Some(Ok(df)) => df,
+            Some(Err(e)) => { set_result.set(Some(Err(e))); return; }
+            None => return,
+        };
+
         let cols_set = target_cols.get();
-        let cols_vec: Vec<String> = cols_set.into_iter().collect();
+        // Keep the DataFrame column order so the output is stable
+        let cols_vec: Vec<String> = columns.get_untracked().into_iter().filter(|c| cols_set.contains(c)).collect();
         let k_factors = n_factors.get();
-        
-        if cols_vec.len() < k_factors {
-             set_result_summary.set(Some(vec!["Error: Number of factors cannot exceed variables.".to_string()]));
-             return;
-        }
-
-        let n_vars = cols_vec.len();
-        let mut col_vecs = vec![];
-        let mut n_samples = 0;
-        
-         for col_name in &cols_vec {
-            if let Some(s) = df.column(col_name).ok().and_then(|c| c.f64().ok()) {
-                let v: Vec<f64> = s.into_no_null_iter().collect();
-                if n_samples == 0 { n_samples = v.len(); }
-                if v.len() != n_samples { return; }
-                col_vecs.push(v);
-            } else { return; }
-        }
-        
-        let mut z_cols = vec![];
-        for v in col_vecs {
-            let mean = v.iter().sum::<f64>() / n_samples as f64;
-            let variance = v.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n_samples - 1) as f64;
-            let std_dev = variance.sqrt();
-             if std_dev == 0.0 {
-                 set_result_summary.set(Some(vec!["Error: Variable has zero variance".to_string()]));
-                 return;
-            }
-            let z: Vec<f64> = v.iter().map(|x| (x - mean) / std_dev).collect();
-            z_cols.push(z);
-        }
-        
-        let mut flat_z = vec![]; 
-        for z in &z_cols { flat_z.extend(z.iter().cloned()); }
-        let z_mat = DMatrix::from_vec(n_samples, n_vars, flat_z);
-        let r_mat = (z_mat.transpose() * &z_mat) / (n_samples - 1) as f64;
-        
-        // Eigen Decomp
-        let eig = SymmetricEigen::new(r_mat);
-        let mut pairs: Vec<(f64, DVector<f64>)> = eig.eigenvalues.iter().zip(eig.eigenvectors.column_iter())
-            .map(|(val, vec)| (*val, vec.into_owned()))
-            .collect();
-        pairs.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
-        
-        // Scree Plot
-        let eigen_vals: Vec<f64> = pairs.iter().map(|p| p.0).collect();
-        let x_axis: Vec<usize> = (1..=n_vars).collect();
-        
-        let data_plot = json!([
-             {
-                "x": x_axis,
-                "y": eigen_vals,
-                "mode": "lines+markers",
-                "type": "scatter",
-                "name": "Eigenvalues",
-                "marker": { "color": "#d62728" }
-            },
-            {
-                 "x": [1, n_vars],
-                 "y": [1, 1],
-                 "mode": "lines",
-                 "type": "scatter",
-                 "name": "Eigenvalue=1",
-                 "line": { "dash": "dash", "color": "gray" }
-            }
-        ]);
-        let layout_plot = json!({
-            "title": "Scree Plot",
-            "xaxis": { "title": "Component Number" },
-            "yaxis": { "title": "Eigenvalue" },
-             "margin": { "t": 40, "b": 40, "l": 50, "r": 20 }
-        });
-        draw_plot(data_plot, layout_plot);
-
-        // Extract top K
-        let mut l_data = vec![];
-        for j in 0..k_factors {
-            let (val, vec) = &pairs[j];
-            if *val <= 0.0 { continue; } 
-            let scaled_vec = vec * val.sqrt();
-            l_data.extend(scaled_vec.iter().cloned());
-        }
-        
-        if l_data.len() != n_vars * k_factors {
-             set_result_summary.set(Some(vec!["Error extracting factors".to_string()]));
-             return;
-        }
-        
-        let l_unrotated = DMatrix::from_vec(n_vars, k_factors, l_data);
-        
-        let final_loadings = if do_rotation.get() {
-            varimax(&l_unrotated, 50, 1e-6)
-        } else {
-            l_unrotated
-        };
-        
-        let mut lines = vec![];
-        lines.push(format!("--- Factor Analysis Results ({} Factors) ---", k_factors));
-        lines.push(format!("Method: Principal Component, Rotation: {}", if do_rotation.get() { "Varimax" } else { "None" }));
-        lines.push("".to_string());
-        lines.push("Factor Loadings:".to_string());
-        
-        for (i, name) in cols_vec.iter().enumerate() {
-            let row = final_loadings.row(i);
-            let s: Vec<String> = row.iter().map(|x| format!("{:.3}", x)).collect();
-            lines.push(format!("{}: {:?}", name, s));
-        }
+        let rotation = if do_rotation.get() { Rotation::Varimax } else { Rotation::None };
 
-        set_result_summary.set(Some(lines));
+        let col_refs: Vec<&str> = cols_vec.iter().map(|s| s.as_str()).collect();
+        set_result.set(Some(factor::factor_analysis_from_df(&df, &col_refs, k_factors, rotation).map(|r| r.report()).map_err(|e| e.to_string())));
     };
 
     view! {
@@ -205,6 +71,9 @@ pub fn FactorAnalysis() -> impl IntoView {
             </h2>
             
              <div class="control-panel">
+                <SampleMenu ids=&["factor_analysis_demo"] on_load=on_sample/>
+                <DatasetPicker choice=dataset/>
+                <FilterBadge choice=dataset/>
                 <div class="input-group">
                     <label>"分析対象変数 (複数選択)"</label>
                      <div class="checkbox-list" style="max_height: 200px; overflow-y: auto; border: 1px solid #ccc; padding: 5px;">
@@ -251,16 +120,7 @@ pub fn FactorAnalysis() -> impl IntoView {
             </div>
             
              <div class="result-area">
-                {move || result_summary.get().map(|lines| view! {
-                    <div class="result-box">
-                        <h3>"分析結果"</h3>
-                         <ul style="list-style-type: none; padding: 0;">
-                            {lines.into_iter().map(|l| view! { <li style="padding: 4px 0; border-bottom: 1px solid #eee;">{l}</li> }).collect::<Vec<_>>()}
-                        </ul>
-                    </div>
-                })}
-                
-                 <div id=plot_id style="width: 100%; height: 400px; margin-top: 20px;"></div>
+                <ResultView result=result/>
             </div>
         </div>
     }
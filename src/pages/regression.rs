@@ -1,42 +1,34 @@
 use leptos::*;
 use crate::state::AppData;
-use nalgebra::{DMatrix, DVector};
-use statrs::distribution::{FisherSnedecor, StudentsT, ContinuousCDF};
+use crate::components::dataset_picker::DatasetPicker;
+use crate::components::filter_builder::FilterBadge;
+use crate::components::result_view::ResultView;
+use crate::components::sample_menu::SampleMenu;
+use crate::report::AnalysisResult;
+use crate::stats::regression;
 use std::collections::HashSet;
-use serde_json::json;
 
 #[component]
 pub fn Regression() -> impl IntoView {
     let app_data = use_context::<AppData>().expect("AppData context not found");
+    // Dataset analyzed on this page (None: the active dataset)
+    let dataset = create_rw_signal(Option::<String>::None);
+    let df = app_data.page_df(dataset);
 
     // UI Signals
     let (target_col, set_target_col) = create_signal(String::new());
     let (explanatory_cols, set_explanatory_cols) = create_signal(HashSet::<String>::new());
-    let (result_summary, set_result_summary) = create_signal(Option::<Vec<String>>::None);
-    let (formula_display, set_formula_display) = create_signal(String::new());
-    let (interpretation, set_interpretation) = create_signal(String::new());
-    
-    let plot_id = "regression_plot";
+    let (result, set_result) = create_signal(Option::<Result<AnalysisResult, String>>::None);
 
     // Available columns
     let columns = create_memo(move |_| {
-        if let Some(df) = app_data.df.get() {
+        if let Some(Ok(df)) = df.get() {
             df.get_column_names().into_iter().map(|s| s.to_string()).collect::<Vec<_>>()
         } else {
             vec![]
         }
     });
 
-    // Helper: Draw Plot
-    let draw_plot = move |data: serde_json::Value, layout: serde_json::Value| {
-        #[cfg(target_arch = "wasm32")]
-        {
-             let d_str = data.to_string();
-             let l_str = layout.to_string();
-             let _ = js_sys::eval(&format!("window.drawPlot('{}', '{}', '{}')", plot_id, d_str, l_str));
-        }
-    };
-
     // Toggle explanatory variable
     let toggle_explanatory = move |col: String| {
         set_explanatory_cols.update(|cols| {
@@ -48,180 +40,34 @@ pub fn Regression() -> impl IntoView {
         });
     };
 
+    // Preselect the variables of the sample dataset
+    let on_sample = move |_| {
+        dataset.set(None);
+        set_result.set(None);
+        set_target_col.set("学校満足度".to_string());
+        set_explanatory_cols.set(["運動頻度", "睡眠の質", "ポジティブ度"].into_iter().map(String::from).collect());
+    };
+
     // Calculation Logic
     let calculate = move |_| {
-        let df_opt = app_data.df.get_untracked();
-        if df_opt.is_none() { return; }
-        let df = df_opt.unwrap();
-        
+        let df = match df.get_untracked() {
+            Some(Ok(df)) => df,
+            Some(Err(e)) => { set_result.set(Some(Err(e))); return; }
+            None => return,
+        };
+
         let target = target_col.get();
         let expl_set = explanatory_cols.get();
-        let expl_vec: Vec<String> = expl_set.into_iter().collect();
-        
-        set_interpretation.set(String::new());
-        set_result_summary.set(None);
+        // Keep the DataFrame column order so the output is stable
+        let expl_vec: Vec<String> = columns.get_untracked().into_iter().filter(|c| expl_set.contains(c)).collect();
 
         if target.is_empty() || expl_vec.is_empty() {
-            set_result_summary.set(Some(vec!["Error: Select target and at least one explanatory variable.".to_string()]));
+            set_result.set(Some(Err("Select target and at least one explanatory variable.".to_string())));
             return;
         }
 
-        // 1. Data Extraction
-        let y_res = df.column(&target).ok().and_then(|c| c.f64().ok()).map(|s| s.into_no_null_iter().collect::<Vec<f64>>());
-        if y_res.is_none() {
-             set_result_summary.set(Some(vec!["Error extracting target variable (must be numeric)".to_string()]));
-             return;
-        }
-        let y_data = y_res.unwrap();
-        let n = y_data.len();
-        let y_dvec = DVector::from_vec(y_data.clone());
-
-        let mut x_data_flat = vec![];
-        let mut intercept = vec![1.0; n];
-        x_data_flat.append(&mut intercept);
-        
-        let mut valid_expl_names = vec![];
-        
-        for name in &expl_vec {
-             if let Some(col_data) = df.column(name).ok().and_then(|c| c.f64().ok()) {
-                 let mut data_vec: Vec<f64> = col_data.into_no_null_iter().collect();
-                 if data_vec.len() != n {
-                      set_result_summary.set(Some(vec![format!("Error: Length mismatch for {}", name)]));
-                      return;
-                 }
-                 x_data_flat.append(&mut data_vec);
-                 valid_expl_names.push(name.clone());
-             } else {
-                  set_result_summary.set(Some(vec![format!("Error: {} is not numeric", name)]));
-                  return;
-             }
-        }
-        
-        let k = valid_expl_names.len(); // number of predictors
-        let p = k + 1; // params
-        
-        if n <= p {
-             set_result_summary.set(Some(vec!["Error: Not enough data points (n <= k + 1)".to_string()]));
-             return;
-        }
-
-        let x_mat = DMatrix::from_vec(n, p, x_data_flat);
-
-        // 2. OLS Solver
-        let xt = x_mat.transpose();
-        let xtx = &xt * &x_mat;
-        let xty = &xt * &y_dvec;
-
-        let xtx_inv_opt = xtx.try_inverse();
-        
-        if let Some(xtx_inv) = xtx_inv_opt {
-            let beta = &xtx_inv * xty;
-            
-            // 3. Statistics
-            let y_predd = &x_mat * &beta;
-            let y_pred_vec: Vec<f64> = y_predd.iter().cloned().collect();
-            let residuals = &y_dvec - &y_predd;
-            let sse = residuals.dot(&residuals);
-            
-            let y_mean = y_data.iter().sum::<f64>() / n as f64;
-            let sst: f64 = y_data.iter().map(|val| (val - y_mean).powi(2)).sum();
-            
-            let r2 = 1.0 - (sse / sst);
-            let adj_r2 = 1.0 - (1.0 - r2) * ((n - 1) as f64 / (n - p) as f64);
-            
-            let msm = (sst - sse) / k as f64;
-            let mse = sse / (n - p) as f64;
-            let f_val = msm / mse;
-            
-            let f_dist = FisherSnedecor::new(k as f64, (n - p) as f64).unwrap();
-            let p_val_f = 1.0 - f_dist.cdf(f_val);
-            
-            let var_beta = xtx_inv.scale(mse);
-            
-            let mut result_lines = vec![];
-            result_lines.push(format!("--- Regression Results (Y: {}) ---", target));
-            result_lines.push(format!("R²: {:.4}", r2));
-            result_lines.push(format!("Adj R²: {:.4}", adj_r2));
-             result_lines.push(format!("F({:.0}, {:.0}): {:.4}, p={:.4e}", k, n-p, f_val, p_val_f));
-            result_lines.push("".to_string());
-            result_lines.push("Coefficients:".to_string());
-            
-            let t_dist = StudentsT::new(0.0, 1.0, (n - p) as f64).unwrap();
-
-            let mut formula_str = format!("{} = {:.4}", target, beta[0]);
-            let mut significant_vars = vec![];
-
-            // Intercept
-            let se_intercept = var_beta[(0,0)].sqrt();
-            let t_intercept = beta[0] / se_intercept;
-            let p_intercept = 2.0 * (1.0 - t_dist.cdf(t_intercept.abs()));
-            
-            result_lines.push(format!("Intercept: Coef={:.4}, SE={:.4}, t={:.4}, p={:.4}", beta[0], se_intercept, t_intercept, p_intercept));
-
-            for i in 0..k {
-                let name = &valid_expl_names[i];
-                let val = beta[i+1];
-                let se = var_beta[(i+1, i+1)].sqrt();
-                let t = val / se;
-                let p = 2.0 * (1.0 - t_dist.cdf(t.abs()));
-                let sig = if p < 0.01 { "**" } else if p < 0.05 { "*" } else { "" };
-                
-                if p < 0.05 { significant_vars.push(name.clone()); }
-
-                result_lines.push(format!("{}: Coef={:.4}, SE={:.4}, t={:.4}, p={:.4} {}", name, val, se, t, p, sig));
-                
-                let sign = if val >= 0.0 { "+" } else { "-" };
-                formula_str.push_str(&format!(" {} {:.4}*{}", sign, val.abs(), name));
-            }
-            
-            set_formula_display.set(formula_str);
-            set_result_summary.set(Some(result_lines));
-            
-            // Interpretation
-            let mut interp = format!("【解釈の補助】\n決定係数(R²)は{:.4}であり、", r2);
-            if r2 > 0.5 {
-                 interp.push_str("モデルの当てはまりは良好です。\n");
-            } else {
-                 interp.push_str("モデルの当てはまりはそれほど高くありません。\n");
-            }
-            if !significant_vars.is_empty() {
-                interp.push_str(&format!("以下の変数が目的変数「{}」に有意な影響を与えています: {}.", target, significant_vars.join(", ")));
-            } else {
-                 interp.push_str("有意な影響を与える説明変数は見つかりませんでした。");
-            }
-            set_interpretation.set(interp);
-
-            // Visualization: Scatter Plot (Y vs Predicted)
-            let data_plot = json!([
-                 {
-                    "x": y_pred_vec,
-                    "y": y_data,
-                    "mode": "markers",
-                    "type": "scatter",
-                    "name": "Data",
-                    "marker": { "color": "#1e90ff" }
-                },
-                {
-                    "x": [y_data.iter().cloned().fold(f64::INFINITY, f64::min), y_data.iter().cloned().fold(f64::NEG_INFINITY, f64::max)],
-                    "y": [y_data.iter().cloned().fold(f64::INFINITY, f64::min), y_data.iter().cloned().fold(f64::NEG_INFINITY, f64::max)],
-                    "mode": "lines",
-                    "type": "scatter",
-                    "name": "Ideal",
-                    "line": { "dash": "dash", "color": "gray" }
-                }
-            ]);
-            
-            let layout_plot = json!({
-                "title": "Observed vs Predicted",
-                "xaxis": { "title": "Predicted Values" },
-                "yaxis": { "title": "Observed Values" },
-                 "margin": { "t": 40, "b": 40, "l": 50, "r": 20 }
-            });
-            draw_plot(data_plot, layout_plot);
-
-        } else {
-             set_result_summary.set(Some(vec!["Error: Singular Matrix. Multicollinearity likely detected.".to_string()]));
-        }
+        let expl_refs: Vec<&str> = expl_vec.iter().map(|s| s.as_str()).collect();
+        set_result.set(Some(regression::ols_from_df(&df, &target, &expl_refs).map(|r| r.report()).map_err(|e| e.to_string())));
     };
 
     view! {
@@ -232,11 +78,14 @@ pub fn Regression() -> impl IntoView {
             </h2>
             
              <div class="control-panel">
+                <SampleMenu ids=&["multiple_regression_demo"] on_load=on_sample/>
+                <DatasetPicker choice=dataset/>
+                <FilterBadge choice=dataset/>
                  <div class="input-group">
                     <label>"目的変数 (Y) [数値]"</label>
                     <select on:change=move |ev| set_target_col.set(event_target_value(&ev))>
                         <option value="">"選択してください"</option>
-                        {columns.get().into_iter().map(|c| view! { <option value=c.clone()>{c}</option> }).collect::<Vec<_>>()}
+                        {move || columns.get().into_iter().map(|c| view! { <option value=c.clone() selected=target_col.get() == c>{c}</option> }).collect::<Vec<_>>()}
                     </select>
                 </div>
 
@@ -268,38 +117,7 @@ pub fn Regression() -> impl IntoView {
             </div>
             
              <div class="result-area">
-                {move || if !formula_display.get().is_empty() {
-                    view! {
-                        <div class="result-box" style="margin-bottom: 1rem; background-color: #f0f7ff; border-left: 4px solid #1e90ff;">
-                            <h3>"数理モデル"</h3>
-                            <p style="font-family: monospace; font-size: 1.1em;">{formula_display.get()}</p>
-                        </div>
-                    }.into_view()
-                } else {
-                    view! { <div/> }.into_view()
-                }}
-
-                {move || result_summary.get().map(|lines| view! {
-                    <div class="result-box">
-                        <h3>"分析結果詳細"</h3>
-                        <ul style="list-style-type: none; padding: 0;">
-                            {lines.into_iter().map(|l| view! { <li style="padding: 4px 0; border-bottom: 1px solid #eee;">{l}</li> }).collect::<Vec<_>>()}
-                        </ul>
-                    </div>
-                })}
-                
-                {move || if !interpretation.get().is_empty() {
-                    view! {
-                         <div class="interpretation-box" style="margin-top: 20px; padding: 15px; background-color: #f9f9f9; border-left: 5px solid #1e90ff;">
-                             <h4 style="margin-top: 0;">"解釈の補助"</h4>
-                             <p style="white-space: pre-wrap;">{interpretation.get()}</p>
-                         </div>
-                    }.into_view()
-                } else {
-                    view! { <div/> }.into_view()
-                }}
-                
-                 <div id=plot_id style="width: 100%; height: 400px; margin-top: 20px;"></div>
+                <ResultView result=result/>
             </div>
         </div>
     }
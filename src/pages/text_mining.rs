@@ -1,11 +1,17 @@
 use leptos::*;
 use polars::prelude::*;
 use crate::state::AppData;
+use crate::components::dataset_picker::DatasetPicker;
+use crate::components::filter_builder::FilterBadge;
+use crate::components::sample_menu::SampleMenu;
 use std::collections::HashMap;
 
 #[component]
 pub fn TextMining() -> impl IntoView {
     let app_data = use_context::<AppData>().expect("AppData context not found");
+    // Dataset analyzed on this page (None: the active dataset)
+    let dataset = create_rw_signal(Option::<String>::None);
+    let df = app_data.page_df(dataset);
 
     // UI state
     let (target_col, set_target_col) = create_signal(String::new());
@@ -15,17 +21,22 @@ pub fn TextMining() -> impl IntoView {
 
     // Columns
     let columns = create_memo(move |_| {
-        if let Some(df) = app_data.df.get() {
+        if let Some(Ok(df)) = df.get() {
             df.get_column_names().into_iter().map(|s| s.to_string()).collect::<Vec<_>>()
         } else {
             vec![]
         }
     });
 
+    let on_sample = move |_| {
+        dataset.set(None);
+        set_word_cloud_ready.set(false);
+        set_target_col.set("今年の抱負".to_string());
+    };
+
     let analyze_text = move |_| {
-         let df_opt = app_data.df.get_untracked();
-        if df_opt.is_none() { return; }
-        let df = df_opt.unwrap();
+        // A filter that does not fit is reported by the filter badge
+        let Some(Ok(df)) = df.get_untracked() else { return };
         
         let col = target_col.get();
         if col.is_empty() { return; }
@@ -37,6 +48,8 @@ pub fn TextMining() -> impl IntoView {
         
         // Extract text data
         if let Ok(s) = df.column(&processed_col) {
+             // Only filled on wasm32, where the browser segmenter is available
+             #[allow(unused_mut)]
              let mut freq_map: HashMap<String, usize> = HashMap::new();
              
              // Iterate rows and collect text
@@ -87,7 +100,7 @@ pub fn TextMining() -> impl IntoView {
                  .filter(|(_, count)| *count >= min_f as usize)
                  .collect();
              
-             words_list.sort_by(|a, b| b.1.cmp(&a.1));
+             words_list.sort_by_key(|w| std::cmp::Reverse(w.1));
              
              if words_list.len() > 100 {
                  words_list.truncate(100);
@@ -108,6 +121,7 @@ pub fn TextMining() -> impl IntoView {
               #[cfg(not(target_arch = "wasm32"))]
              {
                  // Mock
+                 let _ = (full_text, json_str);
              }
 
              set_word_cloud_ready.set(true);
@@ -127,11 +141,14 @@ pub fn TextMining() -> impl IntoView {
             </div>
             
              <div class="control-panel">
+                <SampleMenu ids=&["textmining_demo"] on_load=on_sample/>
+                <DatasetPicker choice=dataset/>
+                <FilterBadge choice=dataset/>
                  <div class="input-group">
                     <label>"分析対象カラム (テキスト)"</label>
                     <select on:change=move |ev| set_target_col.set(event_target_value(&ev))>
                         <option value="">"選択してください"</option>
-                        {columns.get().into_iter().map(|c| view! { <option value=c.clone()>{c}</option> }).collect::<Vec<_>>()}
+                        {move || columns.get().into_iter().map(|c| view! { <option value=c.clone() selected=target_col.get() == c>{c}</option> }).collect::<Vec<_>>()}
                     </select>
                 </div>
                 
@@ -0,0 +1,125 @@
+//! Plot specs built with the `plotly` crate and drawn through a typed
+//! binding to `window.drawPlot` (see `index.html`).
+
+use plotly::box_plot::BoxPoints;
+use plotly::color::NamedColor;
+use plotly::common::{DashType, ErrorData, ErrorType, Line, Marker, Mode, Title};
+use plotly::layout::{Axis, BarMode, Margin};
+use plotly::{Bar, BoxPlot, HeatMap, Layout, Plot, Scatter, Trace};
+
+use crate::report::PlotSpec;
+
+/// Plotly figure under construction: a list of traces plus the layout
+/// settings this app uses (title, axis titles, bar mode).
+pub struct Figure {
+    plot: Plot,
+    layout: Layout,
+}
+
+impl Figure {
+    pub fn new(title: &str) -> Self {
+        Self {
+            plot: Plot::new(),
+            layout: Layout::new()
+                .title(Title::with_text(title))
+                .margin(Margin::new().top(40).bottom(40).left(50).right(20)),
+        }
+    }
+
+    pub fn x_title(mut self, title: &str) -> Self {
+        self.layout = self.layout.x_axis(Axis::new().title(Title::with_text(title)));
+        self
+    }
+
+    pub fn y_title(mut self, title: &str) -> Self {
+        self.layout = self.layout.y_axis(Axis::new().title(Title::with_text(title)));
+        self
+    }
+
+    /// Draws bar traces side by side instead of stacked on top of each other.
+    pub fn grouped(mut self) -> Self {
+        self.layout = self.layout.bar_mode(BarMode::Group);
+        self
+    }
+
+    pub fn trace(mut self, trace: Box<dyn Trace>) -> Self {
+        self.plot.add_trace(trace);
+        self
+    }
+
+    pub fn build(mut self) -> PlotSpec {
+        self.plot.set_layout(self.layout);
+        let mut value = serde_json::to_value(&self.plot).expect("plotly figures serialize to JSON");
+        PlotSpec {
+            data: value["data"].take(),
+            layout: value["layout"].take(),
+        }
+    }
+}
+
+/// Bar chart of category means; `errors` adds symmetric error bars.
+pub fn bar(name: &str, x: Vec<String>, y: Vec<f64>, errors: Option<Vec<f64>>) -> Box<Bar<String, f64>> {
+    let trace = Bar::new(x, y).name(name);
+    match errors {
+        Some(e) => trace.error_y(ErrorData::new(ErrorType::Data).array(e).visible(true)),
+        None => trace,
+    }
+}
+
+/// Points only.
+pub fn scatter(name: &str, x: Vec<f64>, y: Vec<f64>) -> Box<Scatter<f64, f64>> {
+    Scatter::new(x, y).name(name).mode(Mode::Markers)
+}
+
+/// Connected points, e.g. a scree plot.
+pub fn line(name: &str, x: Vec<f64>, y: Vec<f64>) -> Box<Scatter<f64, f64>> {
+    Scatter::new(x, y).name(name).mode(Mode::LinesMarkers)
+}
+
+/// Dashed grey line used for reference lines (y = x, eigenvalue = 1).
+pub fn reference_line(name: &str, x: Vec<f64>, y: Vec<f64>) -> Box<Scatter<f64, f64>> {
+    Scatter::new(x, y)
+        .name(name)
+        .mode(Mode::Lines)
+        .line(Line::new().dash(DashType::Dash).color(NamedColor::Gray))
+}
+
+/// Box plot of one group's values with outliers shown as points.
+pub fn box_plot(name: &str, y: Vec<f64>) -> Box<BoxPlot<f64, f64>> {
+    BoxPlot::new(y).name(name).box_points(BoxPoints::Outliers)
+}
+
+/// Heatmap of a matrix given row-major as `z[row][col]`.
+pub fn heatmap(x: Vec<String>, y: Vec<String>, z: Vec<Vec<f64>>) -> Box<HeatMap<String, String, Vec<f64>>> {
+    HeatMap::new(x, y, z)
+}
+
+/// Solid marker colour shared by all points of a trace.
+pub fn color(c: &'static str) -> Marker {
+    Marker::new().color(c)
+}
+
+/// One marker colour per point/bar.
+pub fn colors(cs: Vec<&'static str>) -> Marker {
+    Marker::new().color_array(cs)
+}
+
+#[cfg(target_arch = "wasm32")]
+mod bindings {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        /// `window.drawPlot(element, data, layout)` defined in `index.html`.
+        #[wasm_bindgen(js_namespace = window, js_name = drawPlot)]
+        pub fn draw_plot(element: &web_sys::HtmlElement, data: &JsValue, layout: &JsValue);
+    }
+}
+
+/// Draws the figure into `element`. The spec is handed over as JS objects,
+/// so labels from user data never end up in evaluated code.
+#[cfg(target_arch = "wasm32")]
+pub fn draw(element: &web_sys::HtmlElement, spec: &PlotSpec) {
+    let to_js = |v: &serde_json::Value| js_sys::JSON::parse(&v.to_string()).unwrap_or(wasm_bindgen::JsValue::NULL);
+    bindings::draw_plot(element, &to_js(&spec.data), &to_js(&spec.layout));
+}
@@ -0,0 +1,184 @@
+//! Typed analysis output shared by the pages, the result renderer and the CLI.
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::stats::significance_mark;
+
+/// One table cell. Numbers keep their value so results can be sorted or exported.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Cell {
+    Text(String),
+    Int(i64),
+    Float { value: f64, decimals: usize },
+    /// p-value; rendered with its significance mark
+    PValue(f64),
+    Empty,
+}
+
+impl Cell {
+    pub fn text(s: impl Into<String>) -> Self {
+        Cell::Text(s.into())
+    }
+
+    pub fn float(value: f64, decimals: usize) -> Self {
+        Cell::Float { value, decimals }
+    }
+
+    /// Numeric value of the cell, if any.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Cell::Int(v) => Some(*v as f64),
+            Cell::Float { value, .. } | Cell::PValue(value) => Some(*value),
+            Cell::Text(_) | Cell::Empty => None,
+        }
+    }
+
+    /// Significance mark for p-value cells (`**`, `*`, `†`, `n.s.`).
+    pub fn mark(&self) -> Option<&'static str> {
+        match self {
+            Cell::PValue(p) => Some(significance_mark(*p)),
+            _ => None,
+        }
+    }
+
+    /// The cell without its significance mark.
+    pub fn value_text(&self) -> String {
+        match self {
+            Cell::Text(s) => s.clone(),
+            Cell::Int(v) => v.to_string(),
+            Cell::Float { value, decimals } => format!("{:.*}", decimals, value),
+            Cell::PValue(p) if *p < 0.0001 => "< 0.0001".to_string(),
+            Cell::PValue(p) => format!("{:.4}", p),
+            Cell::Empty => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mark() {
+            Some(mark) => write!(f, "{} {}", self.value_text(), mark),
+            None => write!(f, "{}", self.value_text()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Table {
+    pub title: Option<String>,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(title: impl Into<String>, header: &[&str]) -> Self {
+        Self {
+            title: Some(title.into()),
+            header: header.iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    pub fn row(mut self, cells: Vec<Cell>) -> Self {
+        self.rows.push(cells);
+        self
+    }
+
+    /// Plain-text rendering with aligned columns.
+    pub fn to_text(&self) -> String {
+        let rows: Vec<Vec<String>> = std::iter::once(self.header.clone())
+            .chain(self.rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()))
+            .collect();
+        let n_cols = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..n_cols)
+            .map(|j| rows.iter().filter_map(|r| r.get(j)).map(|c| display_width(c)).max().unwrap_or(0))
+            .collect();
+
+        let mut out = String::new();
+        if let Some(title) = &self.title {
+            out.push_str(&format!("--- {} ---\n", title));
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{}{}", c, " ".repeat(w - display_width(c))))
+                .collect();
+            out.push_str(line.join("  ").trim_end());
+            out.push('\n');
+            if i == 0 {
+                out.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * n_cols.saturating_sub(1)));
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// Terminal column width, counting CJK and other wide characters as 2.
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if (c as u32) >= 0x1100 { 2 } else { 1 }).sum()
+}
+
+/// Plotly figure: trace array and layout object.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlotSpec {
+    pub data: serde_json::Value,
+    pub layout: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct AnalysisResult {
+    pub title: String,
+    pub tables: Vec<Table>,
+    pub notes: Vec<String>,
+    pub interpretation: Option<String>,
+    pub plots: Vec<PlotSpec>,
+}
+
+impl AnalysisResult {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), ..Default::default() }
+    }
+
+    pub fn table(mut self, table: Table) -> Self {
+        self.tables.push(table);
+        self
+    }
+
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn interpretation(mut self, text: impl Into<String>) -> Self {
+        self.interpretation = Some(text.into());
+        self
+    }
+
+    pub fn plot(mut self, plot: PlotSpec) -> Self {
+        self.plots.push(plot);
+        self
+    }
+
+    /// Plain-text rendering used by the command-line binary.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("=== {} ===\n", self.title);
+        for table in &self.tables {
+            out.push('\n');
+            out.push_str(&table.to_text());
+        }
+        if !self.notes.is_empty() {
+            out.push('\n');
+            for note in &self.notes {
+                out.push_str(&format!("* {}\n", note));
+            }
+        }
+        if let Some(text) = &self.interpretation {
+            out.push_str(&format!("\n【解釈の補助】\n{}\n", text));
+        }
+        out
+    }
+}
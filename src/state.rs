@@ -1,19 +1,89 @@
 use leptos::*;
 use polars::prelude::*;
 
-#[derive(Clone, Debug)]
+use crate::datasets::{Dataset, DatasetRegistry};
+use crate::utils::filter::RowFilter;
+use crate::utils::recipe::Step;
+
+#[derive(Clone, Copy, Debug)]
 pub struct AppData {
-    pub df: RwSignal<Option<DataFrame>>,
-    pub file_name: RwSignal<String>,
+    pub datasets: RwSignal<DatasetRegistry>,
     pub error_msg: RwSignal<Option<String>>,
+    /// Row filter applied to the data the analysis pages see
+    pub filter: RwSignal<RowFilter>,
 }
 
 impl AppData {
     pub fn new() -> Self {
         Self {
-            df: create_rw_signal(None),
-            file_name: create_rw_signal(String::new()),
+            datasets: create_rw_signal(DatasetRegistry::default()),
             error_msg: create_rw_signal(None),
+            filter: create_rw_signal(RowFilter::default()),
         }
     }
+
+    /// Frame of the active dataset.
+    pub fn df(&self) -> Option<DataFrame> {
+        self.datasets.with(|r| r.active().map(|d| d.df.clone()))
+    }
+
+    pub fn active_name(&self) -> Option<String> {
+        self.datasets.with(|r| r.active().map(|d| d.name.clone()))
+    }
+
+    /// The named dataset, or the active one when `name` is `None`.
+    pub fn dataset_untracked(&self, name: Option<&str>) -> Option<Dataset> {
+        self.datasets.with_untracked(|r| r.resolve(name).cloned())
+    }
+
+    /// Frame a page analyzes: the dataset chosen on the page, else the active
+    /// one, with the row filter applied. A filter that does not fit the data is
+    /// an error; pages must not analyze the unfiltered rows instead.
+    pub fn page_df(&self, choice: RwSignal<Option<String>>) -> Signal<Option<Result<DataFrame, String>>> {
+        let source = self.source_df(choice);
+        let filter = self.filter;
+        create_memo(move |_| {
+            let df = source.get()?;
+            Some(filter.with(|f| {
+                if f.is_empty() {
+                    return Ok(df);
+                }
+                f.apply(&df).map_err(|e| format!("フィルタをこのデータに適用できないため、分析できません: {}", e))
+            }))
+        })
+        .into()
+    }
+
+    /// The page's dataset without the row filter, for tools that change the data itself.
+    pub fn source_df(&self, choice: RwSignal<Option<String>>) -> Signal<Option<DataFrame>> {
+        let datasets = self.datasets;
+        Signal::derive(move || datasets.with(|r| r.resolve(choice.get().as_deref()).map(|d| d.df.clone())))
+    }
+
+    /// Stores a newly loaded file under a unique name and makes it active.
+    pub fn add_dataset(&self, name: &str, df: DataFrame) -> String {
+        self.error_msg.set(None);
+        self.datasets.try_update(|r| r.add(name, df)).unwrap_or_default()
+    }
+
+    /// Replaces (or adds) the dataset with this name and makes it active.
+    pub fn set_dataset(&self, name: &str, df: DataFrame) {
+        self.error_msg.set(None);
+        self.datasets.update(|r| r.set(name, df));
+    }
+
+    /// Stores the result of a cleaning step in the dataset a page works on and
+    /// records the step so it can be undone and exported.
+    pub fn apply_step(&self, name: &str, step: Step, df: DataFrame) {
+        self.datasets.update(|r| {
+            r.apply(name, step, df);
+        });
+    }
+
+    /// Stores a frame made from `source` by `step` as a new active dataset
+    /// carrying the source's history. Returns the name it was stored under.
+    pub fn add_derived_dataset(&self, name: &str, df: DataFrame, source: &str, step: Step) -> String {
+        self.error_msg.set(None);
+        self.datasets.try_update(|r| r.add_derived(name, df, source, step)).unwrap_or_default()
+    }
 }
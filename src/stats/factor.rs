@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Result};
+use nalgebra::DMatrix;
+use polars::prelude::*;
+
+use crate::report::{AnalysisResult, Cell, Table};
+
+use super::numeric_columns;
+use super::pca::{correlation_matrix, scree_plot, sorted_eigen};
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub enum Rotation {
+    None,
+    Varimax,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct FactorResult {
+    pub variables: Vec<String>,
+    pub n: usize,
+    pub n_factors: usize,
+    pub rotation: Rotation,
+    /// All eigenvalues of the correlation matrix (for the scree plot)
+    pub eigenvalues: Vec<f64>,
+    /// `loadings[variable][factor]`
+    pub loadings: Vec<Vec<f64>>,
+    /// Sum of squared loadings per variable
+    pub communalities: Vec<f64>,
+}
+
+/// Varimax rotation with Kaiser normalization (same algorithm as R's
+/// `stats::varimax(normalize = TRUE)`).
+pub fn varimax(loadings: &DMatrix<f64>, max_iter: usize, eps: f64) -> DMatrix<f64> {
+    let (p, k) = loadings.shape();
+    let sc: Vec<f64> = loadings.row_iter().map(|row| row.norm()).collect();
+    let mut x = loadings.clone();
+    for (i, mut row) in x.row_iter_mut().enumerate() {
+        if sc[i] > 0.0 {
+            row /= sc[i];
+        }
+    }
+
+    let mut r = DMatrix::identity(k, k);
+    let mut d = 0.0;
+    for _ in 0..max_iter {
+        let z = &x * &r;
+        let alpha = z.map(|v| v * v).row_sum() / p as f64;
+        let mut term2 = z.clone();
+        for (j, mut col) in term2.column_iter_mut().enumerate() {
+            col *= alpha[j];
+        }
+        let b = x.transpose() * (z.map(|v| v.powi(3)) - term2);
+        let svd = b.svd(true, true);
+        let (Some(u), Some(v_t)) = (svd.u, svd.v_t) else { break };
+        r = u * v_t;
+        let d_past = d;
+        d = svd.singular_values.sum();
+        if d < d_past * (1.0 + eps) {
+            break;
+        }
+    }
+
+    let mut rotated = x * r;
+    for (i, mut row) in rotated.row_iter_mut().enumerate() {
+        row *= sc[i];
+    }
+    rotated
+}
+
+/// Orders factors by explained variance and flips each one so its loadings
+/// sum to a non-negative value (the `psych::principal` convention).
+fn sort_and_sign(loadings: DMatrix<f64>) -> DMatrix<f64> {
+    let mut cols: Vec<_> = loadings.column_iter().map(|c| c.into_owned()).collect();
+    cols.sort_by(|a, b| b.norm_squared().total_cmp(&a.norm_squared()));
+    for col in cols.iter_mut() {
+        if col.sum() < 0.0 {
+            *col = -col.clone();
+        }
+    }
+    DMatrix::from_columns(&cols)
+}
+
+/// Factor analysis by the principal component method.
+pub fn factor_analysis(
+    variables: &[&str],
+    cols: &[Vec<f64>],
+    n_factors: usize,
+    rotation: Rotation,
+) -> Result<FactorResult> {
+    let n_vars = cols.len();
+    if n_factors == 0 {
+        return Err(anyhow!("Number of factors must be at least 1."));
+    }
+    if n_vars < n_factors {
+        return Err(anyhow!("Number of factors cannot exceed variables."));
+    }
+
+    let pairs = sorted_eigen(correlation_matrix(cols)?);
+    let mut l_data = Vec::with_capacity(n_vars * n_factors);
+    for (val, vec) in pairs.iter().take(n_factors) {
+        if *val <= 0.0 {
+            return Err(anyhow!("Error extracting factors"));
+        }
+        l_data.extend((vec * val.sqrt()).iter().copied());
+    }
+    let unrotated = DMatrix::from_vec(n_vars, n_factors, l_data);
+
+    let final_loadings = match rotation {
+        Rotation::Varimax => sort_and_sign(varimax(&unrotated, 1000, 1e-5)),
+        // Already in eigenvalue order; only the signs change
+        Rotation::None => sort_and_sign(unrotated),
+    };
+
+    let loadings: Vec<Vec<f64>> = final_loadings
+        .row_iter()
+        .map(|row| row.iter().copied().collect())
+        .collect();
+    let communalities = loadings
+        .iter()
+        .map(|row| row.iter().map(|x| x * x).sum())
+        .collect();
+
+    Ok(FactorResult {
+        variables: variables.iter().map(|s| s.to_string()).collect(),
+        n: cols[0].len(),
+        n_factors,
+        rotation,
+        eigenvalues: pairs.iter().map(|p| p.0).collect(),
+        loadings,
+        communalities,
+    })
+}
+
+pub fn factor_analysis_from_df(
+    df: &DataFrame,
+    variables: &[&str],
+    n_factors: usize,
+    rotation: Rotation,
+) -> Result<FactorResult> {
+    let cols = numeric_columns(df, variables)?;
+    factor_analysis(variables, &cols, n_factors, rotation)
+}
+
+impl FactorResult {
+    pub fn report(&self) -> AnalysisResult {
+        let rotation = match self.rotation {
+            Rotation::Varimax => "Varimax",
+            Rotation::None => "None",
+        };
+        let factor_names: Vec<String> = (1..=self.n_factors).map(|i| format!("F{}", i)).collect();
+        let mut header: Vec<&str> = vec!["変数"];
+        header.extend(factor_names.iter().map(|s| s.as_str()));
+        header.push("共通性");
+
+        let mut loadings = Table::new("因子負荷量", &header);
+        for ((name, row), h2) in self.variables.iter().zip(&self.loadings).zip(&self.communalities) {
+            let mut cells = vec![Cell::text(name)];
+            cells.extend(row.iter().map(|x| Cell::float(*x, 3)));
+            cells.push(Cell::float(*h2, 3));
+            loadings = loadings.row(cells);
+        }
+
+        AnalysisResult::new(format!("Factor Analysis ({} Factors)", self.n_factors))
+            .table(loadings)
+            .note(format!("Method: Principal Component, Rotation: {}", rotation))
+            .plot(scree_plot(&self.eigenvalues, "#d62728"))
+    }
+}
@@ -0,0 +1,185 @@
+//! UI-independent statistics engine.
+//!
+//! Every analysis takes plain slices (or a `DataFrame` plus column names) and
+//! returns a typed result struct, so the same code backs the Leptos pages,
+//! the command-line binary and native tests.
+
+pub mod anova;
+pub mod factor;
+pub mod pca;
+pub mod regression;
+pub mod ttest;
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+use statrs::distribution::{ContinuousCDF, FisherSnedecor, StudentsT};
+
+use crate::utils::recode::level_order;
+
+/// Two-sided p-value of a t statistic.
+pub fn t_p_value(t: f64, df: f64) -> f64 {
+    match StudentsT::new(0.0, 1.0, df) {
+        Ok(dist) => 2.0 * dist.sf(t.abs()),
+        Err(_) => f64::NAN,
+    }
+}
+
+/// Upper-tail p-value of an F statistic.
+pub fn f_p_value(f: f64, df1: f64, df2: f64) -> f64 {
+    match FisherSnedecor::new(df1, df2) {
+        Ok(dist) => dist.sf(f),
+        Err(_) => f64::NAN,
+    }
+}
+
+/// Significance mark used throughout the app (`**`, `*`, `†`, `n.s.`).
+pub fn significance_mark(p: f64) -> &'static str {
+    if p < 0.01 {
+        "**"
+    } else if p < 0.05 {
+        "*"
+    } else if p < 0.1 {
+        "†"
+    } else {
+        "n.s."
+    }
+}
+
+pub fn mean(v: &[f64]) -> f64 {
+    v.iter().sum::<f64>() / v.len() as f64
+}
+
+/// Unbiased (n - 1) sample variance.
+pub fn variance(v: &[f64]) -> f64 {
+    let m = mean(v);
+    v.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (v.len() as f64 - 1.0)
+}
+
+pub fn std_dev(v: &[f64]) -> f64 {
+    variance(v).sqrt()
+}
+
+/// Sample quantile of sorted values with linear interpolation (R's default,
+/// type 7).
+pub fn quantile(sorted: &[f64], p: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * p;
+    let (lo, hi) = (h.floor() as usize, h.ceil() as usize);
+    sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+pub fn median(v: &[f64]) -> f64 {
+    let mut sorted = v.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    quantile(&sorted, 0.5)
+}
+
+/// Descriptive statistics of one group or variable.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct GroupSummary {
+    pub name: String,
+    pub n: usize,
+    pub mean: f64,
+    pub sd: f64,
+    pub se: f64,
+}
+
+impl GroupSummary {
+    pub fn new(name: &str, values: &[f64]) -> Self {
+        let n = values.len();
+        let sd = std_dev(values);
+        Self {
+            name: name.to_string(),
+            n,
+            mean: mean(values),
+            sd,
+            se: sd / (n as f64).sqrt(),
+        }
+    }
+}
+
+fn column_as<'a>(df: &'a DataFrame, name: &str) -> Result<&'a Series> {
+    df.column(name).map_err(|_| anyhow!("Column {} not found", name))
+}
+
+/// Reads the given columns as `f64`, dropping every row that has a missing
+/// value in any of them (listwise deletion).
+pub fn numeric_columns(df: &DataFrame, names: &[&str]) -> Result<Vec<Vec<f64>>> {
+    let mut raw = vec![];
+    for name in names {
+        let s = column_as(df, name)?;
+        if !s.dtype().is_numeric() {
+            return Err(anyhow!("{} is not numeric", name));
+        }
+        let cast = s.cast(&DataType::Float64)?;
+        raw.push(cast.f64()?.into_iter().collect::<Vec<Option<f64>>>());
+    }
+
+    let height = df.height();
+    let mut out = vec![Vec::with_capacity(height); names.len()];
+    for row in 0..height {
+        if raw.iter().all(|c| c[row].is_some_and(f64::is_finite)) {
+            for (dst, src) in out.iter_mut().zip(&raw) {
+                dst.push(src[row].unwrap_or_default());
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Reads a numeric column together with one or more label columns, dropping
+/// rows where any of them is missing. Labels are the string form of the cell.
+pub fn grouped_values(
+    df: &DataFrame,
+    value: &str,
+    groups: &[&str],
+) -> Result<(Vec<f64>, Vec<Vec<String>>)> {
+    let values = column_as(df, value)?;
+    if !values.dtype().is_numeric() {
+        return Err(anyhow!("{} is not numeric", value));
+    }
+    let values = values.cast(&DataType::Float64)?;
+    let values = values.f64()?;
+
+    let mut labels = vec![];
+    for name in groups {
+        let s = column_as(df, name)?.cast(&DataType::String)?;
+        labels.push(
+            s.str()?
+                .into_iter()
+                .map(|v| v.map(|x| x.to_string()))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let mut ys = vec![];
+    let mut gs = vec![vec![]; groups.len()];
+    for (row, y) in values.into_iter().enumerate() {
+        let Some(y) = y.filter(|v| v.is_finite()) else { continue };
+        if labels.iter().any(|l| l[row].is_none()) {
+            continue;
+        }
+        ys.push(y);
+        for (dst, src) in gs.iter_mut().zip(&labels) {
+            dst.push(src[row].clone().unwrap_or_default());
+        }
+    }
+    Ok((ys, gs))
+}
+
+/// Sorted distinct labels.
+pub fn levels(labels: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = labels.to_vec();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Distinct labels of the group column `name`, in the level order set on the
+/// data cleaning page when there is one, otherwise sorted.
+pub fn column_levels(df: &DataFrame, name: &str, labels: &[String]) -> Vec<String> {
+    let present = levels(labels);
+    match df.column(name).ok().and_then(level_order) {
+        Some(order) => order.into_iter().filter(|l| present.contains(l)).collect(),
+        None => present,
+    }
+}
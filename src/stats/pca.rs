@@ -0,0 +1,140 @@
+use anyhow::{anyhow, Result};
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+use polars::prelude::*;
+
+use crate::plot::{self, Figure};
+use crate::report::{AnalysisResult, Cell, PlotSpec, Table};
+
+use super::{mean, numeric_columns, std_dev};
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Component {
+    pub eigenvalue: f64,
+    pub variance_ratio: f64,
+    pub cumulative: f64,
+    /// Eigenvector weights, one per variable
+    pub loadings: Vec<f64>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PcaResult {
+    pub variables: Vec<String>,
+    pub n: usize,
+    /// Sorted by decreasing eigenvalue
+    pub components: Vec<Component>,
+}
+
+impl PcaResult {
+    pub fn eigenvalues(&self) -> Vec<f64> {
+        self.components.iter().map(|c| c.eigenvalue).collect()
+    }
+}
+
+/// Pearson correlation matrix of equally long columns.
+pub fn correlation_matrix(cols: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+    let n_vars = cols.len();
+    let n_samples = cols.first().map(|c| c.len()).unwrap_or(0);
+    if n_samples < 2 {
+        return Err(anyhow!("Not enough data points"));
+    }
+
+    let mut flat_z = Vec::with_capacity(n_samples * n_vars);
+    for v in cols {
+        let m = mean(v);
+        let sd = std_dev(v);
+        if sd == 0.0 {
+            return Err(anyhow!("Variable has zero variance"));
+        }
+        flat_z.extend(v.iter().map(|x| (x - m) / sd));
+    }
+    let z_mat = DMatrix::from_vec(n_samples, n_vars, flat_z);
+    Ok((z_mat.transpose() * &z_mat) / (n_samples - 1) as f64)
+}
+
+/// Eigen pairs of a symmetric matrix, sorted by decreasing eigenvalue.
+///
+/// Eigenvectors are only defined up to sign; each one is flipped so that
+/// its elements sum to a non-negative value, which keeps results stable.
+pub fn sorted_eigen(r_mat: DMatrix<f64>) -> Vec<(f64, DVector<f64>)> {
+    let eig = SymmetricEigen::new(r_mat);
+    let mut pairs: Vec<(f64, DVector<f64>)> = eig
+        .eigenvalues
+        .iter()
+        .zip(eig.eigenvectors.column_iter())
+        .map(|(val, vec)| {
+            let vec = vec.into_owned();
+            if vec.sum() < 0.0 {
+                (*val, -vec)
+            } else {
+                (*val, vec)
+            }
+        })
+        .collect();
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+    pairs
+}
+
+/// Principal component analysis on the correlation matrix.
+pub fn pca(variables: &[&str], cols: &[Vec<f64>]) -> Result<PcaResult> {
+    if cols.len() < 2 {
+        return Err(anyhow!("Select at least 2 variables."));
+    }
+    let pairs = sorted_eigen(correlation_matrix(cols)?);
+    let total_variance: f64 = pairs.iter().map(|p| p.0).sum();
+
+    let mut cumulative = 0.0;
+    let components = pairs
+        .into_iter()
+        .map(|(eigenvalue, vec)| {
+            let variance_ratio = eigenvalue / total_variance;
+            cumulative += variance_ratio;
+            Component {
+                eigenvalue,
+                variance_ratio,
+                cumulative,
+                loadings: vec.iter().copied().collect(),
+            }
+        })
+        .collect();
+
+    Ok(PcaResult {
+        variables: variables.iter().map(|s| s.to_string()).collect(),
+        n: cols[0].len(),
+        components,
+    })
+}
+
+pub fn pca_from_df(df: &DataFrame, variables: &[&str]) -> Result<PcaResult> {
+    let cols = numeric_columns(df, variables)?;
+    pca(variables, &cols)
+}
+
+/// Scree plot of the eigenvalues with the Kaiser criterion line.
+pub(crate) fn scree_plot(eigenvalues: &[f64], color: &'static str) -> PlotSpec {
+    let n_vars = eigenvalues.len();
+    let x_axis: Vec<f64> = (1..=n_vars).map(|i| i as f64).collect();
+    Figure::new("Scree Plot")
+        .x_title("Component Number")
+        .y_title("Eigenvalue")
+        .trace(plot::line("Eigenvalues", x_axis, eigenvalues.to_vec()).marker(plot::color(color)))
+        .trace(plot::reference_line("Eigenvalue=1", vec![1.0, n_vars as f64], vec![1.0, 1.0]))
+        .build()
+}
+
+impl PcaResult {
+    pub fn report(&self) -> AnalysisResult {
+        let mut variance = Table::new("固有値と寄与率", &["成分", "固有値", "寄与率 (%)", "累積寄与率 (%)"]);
+        for (i, c) in self.components.iter().enumerate() {
+            variance = variance.row(vec![
+                Cell::text(format!("PC{}", i + 1)),
+                Cell::float(c.eigenvalue, 4),
+                Cell::float(c.variance_ratio * 100.0, 2),
+                Cell::float(c.cumulative * 100.0, 2),
+            ]);
+        }
+
+        AnalysisResult::new("Principal Component Analysis (PCA)")
+            .table(variance)
+            .plot(scree_plot(&self.eigenvalues(), "#2ca02c"))
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Result};
+use nalgebra::{DMatrix, DVector};
+use polars::prelude::*;
+
+use crate::plot::{self, Figure};
+use crate::report::{AnalysisResult, Cell, Table};
+
+use super::{f_p_value, mean, numeric_columns, t_p_value};
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Coefficient {
+    pub name: String,
+    pub estimate: f64,
+    pub se: f64,
+    pub t: f64,
+    pub p: f64,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct OlsResult {
+    pub target: String,
+    /// Intercept first, then one entry per explanatory variable
+    pub coefficients: Vec<Coefficient>,
+    pub n: usize,
+    pub r2: f64,
+    pub adj_r2: f64,
+    pub f: f64,
+    pub df_model: usize,
+    pub df_resid: usize,
+    pub p: f64,
+    pub observed: Vec<f64>,
+    pub fitted: Vec<f64>,
+}
+
+impl OlsResult {
+    pub fn intercept(&self) -> &Coefficient {
+        &self.coefficients[0]
+    }
+
+    pub fn predictors(&self) -> &[Coefficient] {
+        &self.coefficients[1..]
+    }
+
+    /// `y = b0 + b1*x1 ...` form of the fitted model.
+    pub fn formula(&self) -> String {
+        let mut formula = format!("{} = {:.4}", self.target, self.intercept().estimate);
+        for c in self.predictors() {
+            let sign = if c.estimate >= 0.0 { "+" } else { "-" };
+            formula.push_str(&format!(" {} {:.4}*{}", sign, c.estimate.abs(), c.name));
+        }
+        formula
+    }
+}
+
+/// Ordinary least squares with an intercept.
+pub fn ols(target: &str, y: &[f64], names: &[&str], xs: &[Vec<f64>]) -> Result<OlsResult> {
+    let n = y.len();
+    let k = xs.len();
+    let p = k + 1;
+    if k == 0 {
+        return Err(anyhow!("Select at least one explanatory variable"));
+    }
+    if xs.iter().any(|x| x.len() != n) {
+        return Err(anyhow!("Length mismatch between variables"));
+    }
+    if n <= p {
+        return Err(anyhow!("Not enough data points (n <= k + 1)"));
+    }
+
+    let mut x_data_flat = vec![1.0; n];
+    for x in xs {
+        x_data_flat.extend_from_slice(x);
+    }
+    let x_mat = DMatrix::from_vec(n, p, x_data_flat);
+    let y_dvec = DVector::from_column_slice(y);
+
+    let xt = x_mat.transpose();
+    let xtx_inv = (&xt * &x_mat)
+        .try_inverse()
+        .ok_or_else(|| anyhow!("Singular Matrix. Multicollinearity likely detected."))?;
+    let beta = &xtx_inv * (&xt * &y_dvec);
+
+    let fitted = &x_mat * &beta;
+    let residuals = &y_dvec - &fitted;
+    let sse = residuals.dot(&residuals);
+    let y_mean = mean(y);
+    let sst: f64 = y.iter().map(|v| (v - y_mean).powi(2)).sum();
+
+    let df_resid = n - p;
+    let r2 = 1.0 - sse / sst;
+    let adj_r2 = 1.0 - (1.0 - r2) * ((n - 1) as f64 / df_resid as f64);
+    let mse = sse / df_resid as f64;
+    let f = ((sst - sse) / k as f64) / mse;
+    let var_beta = xtx_inv.scale(mse);
+
+    let coefficients = std::iter::once("Intercept")
+        .chain(names.iter().copied())
+        .enumerate()
+        .map(|(i, name)| {
+            let se = var_beta[(i, i)].sqrt();
+            let t = beta[i] / se;
+            Coefficient {
+                name: name.to_string(),
+                estimate: beta[i],
+                se,
+                t,
+                p: t_p_value(t, df_resid as f64),
+            }
+        })
+        .collect();
+
+    Ok(OlsResult {
+        target: target.to_string(),
+        coefficients,
+        n,
+        r2,
+        adj_r2,
+        f,
+        df_model: k,
+        df_resid,
+        p: f_p_value(f, k as f64, df_resid as f64),
+        observed: y.to_vec(),
+        fitted: fitted.iter().copied().collect(),
+    })
+}
+
+/// Regresses `target` on `explanatory` using complete cases.
+pub fn ols_from_df(df: &DataFrame, target: &str, explanatory: &[&str]) -> Result<OlsResult> {
+    let names: Vec<&str> = std::iter::once(target).chain(explanatory.iter().copied()).collect();
+    let mut cols = numeric_columns(df, &names)?;
+    let xs = cols.split_off(1);
+    ols(target, &cols[0], explanatory, &xs)
+}
+
+impl OlsResult {
+    pub fn report(&self) -> AnalysisResult {
+        let mut fit = Table::new("モデル適合度", &["R²", "調整済み R²", "F", "df", "p"]);
+        fit = fit.row(vec![
+            Cell::float(self.r2, 4),
+            Cell::float(self.adj_r2, 4),
+            Cell::float(self.f, 4),
+            Cell::text(format!("{}, {}", self.df_model, self.df_resid)),
+            Cell::PValue(self.p),
+        ]);
+
+        let mut coefs = Table::new("回帰係数", &["変数", "係数", "SE", "t", "p"]);
+        for c in &self.coefficients {
+            coefs = coefs.row(vec![
+                Cell::text(&c.name),
+                Cell::float(c.estimate, 4),
+                Cell::float(c.se, 4),
+                Cell::float(c.t, 4),
+                Cell::PValue(c.p),
+            ]);
+        }
+
+        let significant_vars: Vec<&str> = self
+            .predictors()
+            .iter()
+            .filter(|c| c.p < 0.05)
+            .map(|c| c.name.as_str())
+            .collect();
+        let mut interp = format!("決定係数(R²)は{:.4}であり、", self.r2);
+        if self.r2 > 0.5 {
+            interp.push_str("モデルの当てはまりは良好です。\n");
+        } else {
+            interp.push_str("モデルの当てはまりはそれほど高くありません。\n");
+        }
+        if !significant_vars.is_empty() {
+            interp.push_str(&format!(
+                "以下の変数が目的変数「{}」に有意な影響を与えています: {}.",
+                self.target,
+                significant_vars.join(", ")
+            ));
+        } else {
+            interp.push_str("有意な影響を与える説明変数は見つかりませんでした。");
+        }
+
+        // Observed vs Predicted
+        let y_min = self.observed.iter().cloned().fold(f64::INFINITY, f64::min);
+        let y_max = self.observed.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        AnalysisResult::new(format!("Regression Results (Y: {})", self.target))
+            .note(format!("回帰式: {}", self.formula()))
+            .table(fit)
+            .table(coefs)
+            .interpretation(interp)
+            .plot(
+                Figure::new("Observed vs Predicted")
+                    .x_title("Predicted Values")
+                    .y_title("Observed Values")
+                    .trace(plot::scatter("Data", self.fitted.clone(), self.observed.clone()).marker(plot::color("#1e90ff")))
+                    .trace(plot::reference_line("Ideal", vec![y_min, y_max], vec![y_min, y_max]))
+                    .build(),
+            )
+    }
+}
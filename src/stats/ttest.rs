@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use crate::plot::{self, Figure};
+use crate::report::{AnalysisResult, Cell, Table};
+
+use super::{column_levels, grouped_values, mean, numeric_columns, std_dev, t_p_value, variance, GroupSummary};
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub enum TTestKind {
+    /// Welch's t-test (independent samples, unequal variances)
+    Welch,
+    /// Paired t-test
+    Paired,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TTestResult {
+    pub kind: TTestKind,
+    pub groups: [GroupSummary; 2],
+    /// Mean of group 1 minus mean of group 2
+    pub mean_diff: f64,
+    /// SD of the pairwise differences (paired test only)
+    pub sd_diff: Option<f64>,
+    pub t: f64,
+    pub df: f64,
+    pub p: f64,
+    /// Cohen's d (pooled SD for Welch, d_z for paired)
+    pub d: f64,
+    /// Dependent and grouping variable names (Welch test on a DataFrame)
+    pub variable: String,
+    pub grouping: String,
+}
+
+/// Welch's t-test for two independent samples.
+pub fn welch(name1: &str, v1: &[f64], name2: &str, v2: &[f64]) -> Result<TTestResult> {
+    if v1.len() < 2 || v2.len() < 2 {
+        return Err(anyhow!("Each group needs at least 2 observations"));
+    }
+    let n1 = v1.len() as f64;
+    let n2 = v2.len() as f64;
+    let mean1 = mean(v1);
+    let mean2 = mean(v2);
+    let var1 = variance(v1);
+    let var2 = variance(v2);
+
+    let t_den = ((var1 / n1) + (var2 / n2)).sqrt();
+    let t = (mean1 - mean2) / t_den;
+    let df_num = ((var1 / n1) + (var2 / n2)).powi(2);
+    let df_den = ((var1 / n1).powi(2) / (n1 - 1.0)) + ((var2 / n2).powi(2) / (n2 - 1.0));
+    let df = df_num / df_den;
+
+    // Effect Size (Cohen's d) - using pooled std for d
+    let pooled_std = (((n1 - 1.0) * var1 + (n2 - 1.0) * var2) / (n1 + n2 - 2.0)).sqrt();
+    let d = (mean1 - mean2).abs() / pooled_std;
+
+    Ok(TTestResult {
+        kind: TTestKind::Welch,
+        groups: [GroupSummary::new(name1, v1), GroupSummary::new(name2, v2)],
+        mean_diff: mean1 - mean2,
+        sd_diff: None,
+        t,
+        df,
+        p: t_p_value(t, df),
+        d,
+        variable: String::new(),
+        grouping: String::new(),
+    })
+}
+
+/// Paired t-test on two equally long samples.
+pub fn paired(name1: &str, v1: &[f64], name2: &str, v2: &[f64]) -> Result<TTestResult> {
+    if v1.len() != v2.len() {
+        return Err(anyhow!("Paired samples must have the same length"));
+    }
+    if v1.len() < 2 {
+        return Err(anyhow!("Need at least 2 pairs"));
+    }
+    let n = v1.len() as f64;
+    let diffs: Vec<f64> = v1.iter().zip(v2).map(|(a, b)| a - b).collect();
+    let d_mean = mean(&diffs);
+    let d_std = std_dev(&diffs);
+    let t = d_mean / (d_std / n.sqrt());
+    let df = n - 1.0;
+
+    Ok(TTestResult {
+        kind: TTestKind::Paired,
+        groups: [GroupSummary::new(name1, v1), GroupSummary::new(name2, v2)],
+        mean_diff: d_mean,
+        sd_diff: Some(d_std),
+        t,
+        df,
+        p: t_p_value(t, df),
+        // d_z = t / sqrt(n)
+        d: t.abs() / n.sqrt(),
+        variable: String::new(),
+        grouping: String::new(),
+    })
+}
+
+/// Welch's t-test of `value` between the two levels of `group`.
+pub fn welch_from_df(df: &DataFrame, value: &str, group: &str) -> Result<TTestResult> {
+    let (ys, gs) = grouped_values(df, value, &[group])?;
+    let labels = &gs[0];
+    let groups = column_levels(df, group, labels);
+    if groups.len() != 2 {
+        return Err(anyhow!(
+            "Group variable must have exactly 2 levels. Found: {:?}",
+            groups
+        ));
+    }
+    let pick = |g: &str| -> Vec<f64> {
+        ys.iter()
+            .zip(labels)
+            .filter(|(_, l)| l.as_str() == g)
+            .map(|(y, _)| *y)
+            .collect()
+    };
+    let mut res = welch(&groups[0], &pick(&groups[0]), &groups[1], &pick(&groups[1]))?;
+    res.variable = value.to_string();
+    res.grouping = group.to_string();
+    Ok(res)
+}
+
+/// Paired t-test between two numeric columns (rows with a missing value are dropped).
+pub fn paired_from_df(df: &DataFrame, col1: &str, col2: &str) -> Result<TTestResult> {
+    let cols = numeric_columns(df, &[col1, col2])?;
+    paired(col1, &cols[0], col2, &cols[1])
+}
+
+impl TTestResult {
+    pub fn report(&self) -> AnalysisResult {
+        let [g1, g2] = &self.groups;
+        let sig_text = difference_text(self.p, &g1.name, g1.mean, &g2.name, g2.mean);
+
+        let (title, interpretation, colors) = match self.kind {
+            TTestKind::Welch => (
+                "Welch's t-test",
+                format!(
+                    "分析の結果、群分け変数「{}」を用いた場合、従属変数「{}」について、{}\n(t({:.2})={:.2}, p={:.3}, d={:.2})",
+                    self.grouping, self.variable, sig_text, self.df, self.t, self.p, self.d
+                ),
+                ["#1e90ff", "#ff7f0e"],
+            ),
+            TTestKind::Paired => (
+                "Paired t-test",
+                format!(
+                    "分析の結果、「{}」と「{}」の間には、{}\n(t({:.0})={:.2}, p={:.3}, d={:.2})",
+                    g1.name, g2.name, sig_text, self.df, self.t, self.p, self.d
+                ),
+                ["#2ca02c", "#d62728"],
+            ),
+        };
+
+        let mut descriptives = Table::new("記述統計", &["変数", "N", "平均", "SD", "SE"]);
+        for g in &self.groups {
+            descriptives = descriptives.row(vec![
+                Cell::text(&g.name),
+                Cell::Int(g.n as i64),
+                Cell::float(g.mean, 4),
+                Cell::float(g.sd, 4),
+                Cell::float(g.se, 4),
+            ]);
+        }
+
+        let df_decimals = if self.kind == TTestKind::Welch { 2 } else { 0 };
+        let mut test = Table::new("検定結果", &["平均差", "t", "df", "p", "効果量 d"]);
+        test = test.row(vec![
+            Cell::float(self.mean_diff, 4),
+            Cell::float(self.t, 4),
+            Cell::float(self.df, df_decimals),
+            Cell::PValue(self.p),
+            Cell::float(self.d, 4),
+        ]);
+
+        let mut result = AnalysisResult::new(title)
+            .table(descriptives)
+            .table(test)
+            .interpretation(interpretation);
+        if let Some(sd) = self.sd_diff {
+            result = result.note(format!("差の標準偏差 SD = {:.4}", sd));
+        }
+
+        // Bar Chart with Error Bars
+        let figure = match self.kind {
+            TTestKind::Welch => Figure::new(&format!("Mean Comparison: {} by {}", self.variable, self.grouping))
+                .x_title(&self.grouping)
+                .y_title(&self.variable),
+            TTestKind::Paired => Figure::new("Paired Comparison"),
+        };
+        let bars = plot::bar(
+            "",
+            vec![g1.name.clone(), g2.name.clone()],
+            vec![g1.mean, g2.mean],
+            Some(vec![g1.se, g2.se]),
+        );
+        result.plot(figure.trace(bars.marker(plot::colors(colors.to_vec()))).build())
+    }
+}
+
+fn difference_text(p: f64, name1: &str, mean1: f64, name2: &str, mean2: f64) -> String {
+    let comp_str = if mean1 > mean2 { format!("{} > {}", name1, name2) } else { format!("{} < {}", name1, name2) };
+    if p < 0.05 {
+        format!("有意な差が認められました ({}) 。", comp_str)
+    } else if p < 0.1 {
+        format!("有意な差がある傾向が認められました ({}) 。", comp_str)
+    } else {
+        "有意な差は認められませんでした。".to_string()
+    }
+}
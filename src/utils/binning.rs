@@ -0,0 +1,178 @@
+//! Cutting a numeric column into groups (equal width, quantiles, custom
+//! breaks or a median split) stored as an ordered categorical column.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use crate::stats::quantile;
+use super::recode::set_level_order;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum BinMethod {
+    /// Bins of equal width between the minimum and the maximum
+    EqualWidth(usize),
+    /// Bins with about the same number of rows, cut at sample quantiles
+    Quantiles(usize),
+    /// Edges given by the user; values outside them become missing
+    Breaks(Vec<f64>),
+    /// Low group (at or below the median) and high group (above it)
+    MedianSplit,
+}
+
+impl BinMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinMethod::EqualWidth(_) => "等間隔",
+            BinMethod::Quantiles(_) => "等頻度 (分位点)",
+            BinMethod::Breaks(_) => "区切り値を指定",
+            BinMethod::MedianSplit => "中央値で 2 群 (高群・低群)",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BinSpec {
+    pub column: String,
+    pub method: BinMethod,
+    /// Name of the new column
+    pub into: String,
+    /// One label per bin, lowest first; empty: the interval, e.g. `[10, 20)`
+    pub labels: Vec<String>,
+    /// List the highest bin first in the level order
+    pub descending: bool,
+}
+
+/// Edges used and rows per bin (lowest first).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BinReport {
+    pub edges: Vec<f64>,
+    pub labels: Vec<String>,
+    pub counts: Vec<usize>,
+    /// Non-missing values outside the custom breaks
+    pub outside: usize,
+}
+
+/// An edge in labels: up to 3 decimals, without trailing zeros.
+fn format_edge(x: f64) -> String {
+    let text = format!("{:.3}", x);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Bin edges for the method. Quantile edges that coincide (many tied values)
+/// are merged, so there may be fewer bins than asked for.
+fn edges(sorted: &[f64], method: &BinMethod) -> Result<Vec<f64>> {
+    let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+    let edges = match method {
+        BinMethod::EqualWidth(k) | BinMethod::Quantiles(k) if *k < 2 => return Err(anyhow!("Use at least 2 bins")),
+        BinMethod::EqualWidth(k) => {
+            if min == max {
+                return Err(anyhow!("All values are equal; there is nothing to cut"));
+            }
+            let width = (max - min) / *k as f64;
+            (0..=*k).map(|i| if i == *k { max } else { min + width * i as f64 }).collect()
+        }
+        BinMethod::Quantiles(k) => {
+            let mut edges: Vec<f64> = (0..=*k).map(|i| quantile(sorted, i as f64 / *k as f64)).collect();
+            edges.dedup();
+            if edges.len() < 3 {
+                return Err(anyhow!("Too many tied values to form more than one bin"));
+            }
+            edges
+        }
+        BinMethod::Breaks(breaks) => {
+            let mut edges = breaks.clone();
+            edges.sort_by(f64::total_cmp);
+            edges.dedup();
+            if edges.len() < 2 || edges.iter().any(|x| !x.is_finite()) {
+                return Err(anyhow!("Give at least 2 distinct break values"));
+            }
+            edges
+        }
+        BinMethod::MedianSplit => vec![min, quantile(sorted, 0.5), max],
+    };
+    Ok(edges)
+}
+
+/// The bin of a value: bins include their lower edge, the last one also its
+/// upper edge. A median split puts values equal to the median in the low group.
+fn bin_of(x: f64, edges: &[f64], median_split: bool) -> Option<usize> {
+    let last = edges.len() - 2;
+    if x < edges[0] || x > edges[last + 1] {
+        return None;
+    }
+    if median_split {
+        return Some(usize::from(x > edges[1]));
+    }
+    Some(edges[1..=last].iter().take_while(|edge| x >= **edge).count())
+}
+
+/// Adds `spec.into` with the bin of each value of `spec.column`, as a
+/// categorical column whose levels run from the lowest bin (or the highest
+/// when `descending`). Fails rather than overwrite an existing column.
+pub fn bin_column(df: &DataFrame, spec: &BinSpec) -> Result<(DataFrame, BinReport)> {
+    let into = spec.into.trim();
+    if into.is_empty() {
+        return Err(anyhow!("Output column name is empty"));
+    }
+    if df.column(into).is_ok() {
+        return Err(anyhow!("Column '{}' already exists", into));
+    }
+    let s = df.column(&spec.column).map_err(|_| anyhow!("Column '{}' not found", spec.column))?;
+    if !s.dtype().is_numeric() {
+        return Err(anyhow!("'{}' is not numeric", spec.column));
+    }
+    let values: Vec<Option<f64>> = s.cast(&DataType::Float64)?.f64()?.into_iter().map(|v| v.filter(|x| x.is_finite())).collect();
+    let mut sorted: Vec<f64> = values.iter().flatten().copied().collect();
+    if sorted.is_empty() {
+        return Err(anyhow!("'{}' has no values", spec.column));
+    }
+    sorted.sort_by(f64::total_cmp);
+    let edges = edges(&sorted, &spec.method)?;
+    let bins = edges.len() - 1;
+
+    let median_split = spec.method == BinMethod::MedianSplit;
+    let labels: Vec<String> = if !spec.labels.is_empty() {
+        if spec.labels.len() != bins {
+            return Err(anyhow!("{} labels given for {} bins", spec.labels.len(), bins));
+        }
+        spec.labels.iter().map(|l| l.trim().to_string()).collect()
+    } else if median_split {
+        vec!["低群".to_string(), "高群".to_string()]
+    } else {
+        (0..bins)
+            .map(|i| {
+                let close = if i == bins - 1 { "]" } else { ")" };
+                format!("[{}, {}{}", format_edge(edges[i]), format_edge(edges[i + 1]), close)
+            })
+            .collect()
+    };
+    if let Some(label) = labels.iter().enumerate().find_map(|(i, l)| labels[..i].contains(l).then_some(l)) {
+        return Err(anyhow!("Label '{}' is used for more than one bin", label));
+    }
+
+    let mut report = BinReport { edges: edges.clone(), labels: labels.clone(), counts: vec![0; bins], outside: 0 };
+    let binned: Vec<Option<&str>> = values
+        .iter()
+        .map(|v| {
+            let x = (*v)?;
+            match bin_of(x, &edges, median_split) {
+                Some(b) => {
+                    report.counts[b] += 1;
+                    Some(labels[b].as_str())
+                }
+                None => {
+                    report.outside += 1;
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let mut out = df.clone();
+    out.with_column(Series::new(into, binned))?;
+    let mut levels = labels;
+    if spec.descending {
+        levels.reverse();
+    }
+    Ok((set_level_order(&out, into, &levels)?, report))
+}
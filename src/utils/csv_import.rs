@@ -0,0 +1,207 @@
+//! Delimited text import: encoding and delimiter detection plus the parse
+//! options (quote character, decimal comma, NA tokens) passed to Polars.
+
+use anyhow::{anyhow, Result};
+use encoding_rs::{Encoding, EUC_JP, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8};
+use polars::prelude::*;
+use std::io::Cursor;
+
+/// Text encodings offered for CSV files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    /// Shift_JIS as written by Japanese Windows / Excel (decoded as CP932)
+    ShiftJis,
+    EucJp,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 5] = [
+        TextEncoding::Utf8,
+        TextEncoding::ShiftJis,
+        TextEncoding::EucJp,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::ShiftJis => "Shift_JIS",
+            TextEncoding::EucJp => "EUC-JP",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        let normalized = label.trim().to_lowercase().replace(['_', '-'], "");
+        match normalized.as_str() {
+            "utf8" => Some(TextEncoding::Utf8),
+            "shiftjis" | "sjis" | "cp932" | "windows31j" => Some(TextEncoding::ShiftJis),
+            "eucjp" => Some(TextEncoding::EucJp),
+            "utf16" | "utf16le" => Some(TextEncoding::Utf16Le),
+            "utf16be" => Some(TextEncoding::Utf16Be),
+            _ => None,
+        }
+    }
+
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            TextEncoding::Utf8 => UTF_8,
+            TextEncoding::ShiftJis => SHIFT_JIS,
+            TextEncoding::EucJp => EUC_JP,
+            TextEncoding::Utf16Le => UTF_16LE,
+            TextEncoding::Utf16Be => UTF_16BE,
+        }
+    }
+
+    /// Guesses the encoding from a byte-order mark, NUL-byte layout (UTF-16
+    /// without BOM) or whichever decoder reads the bytes without errors.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return match encoding.name() {
+                "UTF-16LE" => TextEncoding::Utf16Le,
+                "UTF-16BE" => TextEncoding::Utf16Be,
+                _ => TextEncoding::Utf8,
+            };
+        }
+        let sample = &bytes[..bytes.len().min(4096)];
+        let zeros_at = |parity: usize| sample.iter().skip(parity).step_by(2).filter(|b| **b == 0).count();
+        let half = sample.len() / 2;
+        if half > 0 && zeros_at(1) * 3 > half {
+            return TextEncoding::Utf16Le;
+        }
+        if half > 0 && zeros_at(0) * 3 > half {
+            return TextEncoding::Utf16Be;
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return TextEncoding::Utf8;
+        }
+        // Both accept most Japanese text; Shift_JIS is what Excel writes
+        [TextEncoding::ShiftJis, TextEncoding::EucJp]
+            .into_iter()
+            .find(|e| !e.encoding().decode_without_bom_handling(bytes).1)
+            .unwrap_or(TextEncoding::ShiftJis)
+    }
+
+    /// Decodes to UTF-8, dropping a BOM; undecodable bytes become U+FFFD.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        self.encoding().decode_with_bom_removal(bytes).0.into_owned()
+    }
+}
+
+/// Delimiters tried by [`detect_delimiter`].
+pub const DELIMITER_CANDIDATES: [char; 4] = [',', '\t', ';', '|'];
+
+/// Picks the candidate that splits the first lines into the same number of
+/// fields most consistently (ignoring delimiters inside quotes).
+pub fn detect_delimiter(text: &str, quote: Option<char>) -> char {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).take(20).collect();
+    let count = |line: &str, delimiter: char| {
+        let mut quoted = false;
+        line.chars()
+            .filter(|c| {
+                if Some(*c) == quote {
+                    quoted = !quoted;
+                }
+                !quoted && *c == delimiter
+            })
+            .count()
+    };
+    DELIMITER_CANDIDATES
+        .into_iter()
+        .map(|d| {
+            let counts: Vec<usize> = lines.iter().map(|l| count(l, d)).collect();
+            let first = counts.first().copied().unwrap_or(0);
+            let consistent = counts.iter().filter(|c| **c == first).count();
+            (d, first > 0, consistent, first)
+        })
+        .filter(|(_, found, _, _)| *found)
+        .max_by_key(|(_, _, consistent, first)| (*consistent, *first))
+        .map(|(d, ..)| d)
+        .unwrap_or(',')
+}
+
+/// How to read a delimited text file. `None` means detect from the content.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: Option<char>,
+    pub encoding: Option<TextEncoding>,
+    /// `None` disables quoting
+    pub quote: Option<char>,
+    /// `1,5` is one and a half (needs a delimiter other than `,`)
+    pub decimal_comma: bool,
+    /// Cell values read as missing, in addition to empty cells
+    pub na_values: Vec<String>,
+    pub has_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            encoding: None,
+            quote: Some('"'),
+            decimal_comma: false,
+            na_values: vec!["NA".to_string()],
+            has_header: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Encoding and delimiter actually used for these bytes.
+    pub fn resolve(&self, bytes: &[u8]) -> (TextEncoding, char) {
+        let encoding = self.encoding.unwrap_or_else(|| TextEncoding::detect(bytes));
+        let delimiter = self.delimiter.unwrap_or_else(|| detect_delimiter(&encoding.decode(bytes), self.quote));
+        (encoding, delimiter)
+    }
+}
+
+fn ascii_byte(c: char, what: &str) -> Result<u8> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        Err(anyhow!("{} must be an ASCII character, got '{}'", what, c))
+    }
+}
+
+fn parse(bytes: &[u8], options: &CsvOptions, n_rows: Option<usize>) -> Result<DataFrame> {
+    let encoding = options.encoding.unwrap_or_else(|| TextEncoding::detect(bytes));
+    let text = encoding.decode(bytes);
+    let delimiter = options.delimiter.unwrap_or_else(|| detect_delimiter(&text, options.quote));
+    if options.decimal_comma && delimiter == ',' {
+        return Err(anyhow!("Decimal comma needs a delimiter other than ','"));
+    }
+
+    let separator = ascii_byte(delimiter, "Delimiter")?;
+    let quote = options.quote.map(|q| ascii_byte(q, "Quote character")).transpose()?;
+    let na_values: Vec<String> = options.na_values.iter().filter(|v| !v.is_empty()).cloned().collect();
+    let parse_options = CsvParseOptions::default()
+        .with_separator(separator)
+        .with_quote_char(quote)
+        .with_decimal_comma(options.decimal_comma)
+        .with_try_parse_dates(true)
+        .with_null_values((!na_values.is_empty()).then_some(NullValues::AllColumns(na_values)));
+
+    CsvReadOptions::default()
+        .with_has_header(options.has_header)
+        .with_n_rows(n_rows)
+        .with_parse_options(parse_options)
+        .into_reader_with_file_handle(Cursor::new(text.into_bytes()))
+        .finish()
+        .map_err(|e| anyhow!("CSV Parse Error: {}", e))
+}
+
+/// Reads the whole file.
+pub fn read_csv(bytes: &[u8], options: &CsvOptions) -> Result<DataFrame> {
+    parse(bytes, options, None)
+}
+
+/// Reads the first `n` data rows, for showing a preview while options are chosen.
+pub fn preview_csv(bytes: &[u8], options: &CsvOptions, n: usize) -> Result<DataFrame> {
+    parse(bytes, options, Some(n))
+}
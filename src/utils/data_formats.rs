@@ -0,0 +1,113 @@
+//! JSON / JSON Lines / Parquet import and the export formats for the current data.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+use std::io::Cursor;
+
+use super::xlsx_writer::write_xlsx;
+
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// Whether the file is Parquet, by magic bytes or extension.
+pub fn is_parquet(file_name: &str, bytes: &[u8]) -> bool {
+    bytes.starts_with(PARQUET_MAGIC) || file_name.to_lowercase().ends_with(".parquet")
+}
+
+/// Whether the file name looks like JSON or JSON Lines.
+pub fn is_json(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    [".json", ".jsonl", ".ndjson"].iter().any(|ext| lower.ends_with(ext))
+}
+
+pub fn read_parquet(bytes: &[u8]) -> Result<DataFrame> {
+    ParquetReader::new(Cursor::new(bytes))
+        .finish()
+        .map_err(|e| anyhow!("Parquet Read Error: {}", e))
+}
+
+/// Reads an array of records (`[{...}, {...}]`) or one record per line.
+/// The layout is taken from the content, since form tools often save
+/// JSON Lines with a `.json` extension.
+pub fn read_json(bytes: &[u8]) -> Result<DataFrame> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let format = match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'[') => JsonFormat::Json,
+        Some(b'{') => JsonFormat::JsonLines,
+        _ => return Err(anyhow!("JSON Read Error: expected an array of records or one record per line")),
+    };
+    JsonReader::new(Cursor::new(bytes))
+        .with_json_format(format)
+        .finish()
+        .map_err(|e| anyhow!("JSON Read Error: {}", e))
+}
+
+/// Download formats for the current data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Json,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [ExportFormat::Csv, ExportFormat::Xlsx, ExportFormat::Json, ExportFormat::Parquet];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Json => "json",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Xlsx => "Excel (.xlsx)",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Parquet => "Parquet",
+        }
+    }
+
+    /// Download name derived from the source file, e.g. `survey.xlsx` → `survey.csv`.
+    pub fn file_name(self, source: &str) -> String {
+        let stem = source.rsplit_once('.').map_or(source, |(stem, _)| stem);
+        let stem = if stem.trim().is_empty() { "data" } else { stem };
+        format!("{}.{}", stem, self.extension())
+    }
+}
+
+/// Serializes the DataFrame. CSV gets a UTF-8 BOM so Excel opens Japanese text correctly.
+pub fn export_dataframe(df: &DataFrame, format: ExportFormat) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut df = df.clone();
+    match format {
+        ExportFormat::Csv => CsvWriter::new(&mut buf)
+            .include_bom(true)
+            .finish(&mut df)
+            .map_err(|e| anyhow!("CSV Write Error: {}", e))?,
+        ExportFormat::Xlsx => buf = write_xlsx(&df, "data")?,
+        ExportFormat::Json => JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::Json)
+            .finish(&mut df)
+            .map_err(|e| anyhow!("JSON Write Error: {}", e))?,
+        ExportFormat::Parquet => {
+            ParquetWriter::new(&mut buf)
+                .with_compression(ParquetCompression::Snappy)
+                .finish(&mut df)
+                .map_err(|e| anyhow!("Parquet Write Error: {}", e))?;
+        }
+    }
+    Ok(buf)
+}
@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+/// Names of the `Date`/`Datetime` columns, in DataFrame order.
+pub fn date_columns(df: &DataFrame) -> Vec<String> {
+    df.get_columns()
+        .iter()
+        .filter(|s| matches!(s.dtype(), DataType::Date | DataType::Datetime(_, _)))
+        .map(|s| s.name().to_string())
+        .collect()
+}
+
+/// Appends `<column>_年`, `<column>_月` and `<column>_曜日` (ISO weekday,
+/// 1 = Monday … 7 = Sunday) derived from a date column. Fails rather than
+/// overwrite a column that already has one of these names.
+pub fn add_date_parts(df: &DataFrame, column: &str) -> Result<DataFrame> {
+    let dtype = df.column(column).map_err(|e| anyhow!("{}", e))?.dtype().clone();
+    if !matches!(dtype, DataType::Date | DataType::Datetime(_, _)) {
+        return Err(anyhow!("{} is not a date column", column));
+    }
+    let [year, month, weekday] = ["年", "月", "曜日"].map(|part| format!("{}_{}", column, part));
+    if let Some(name) = [&year, &month, &weekday].into_iter().find(|name| df.column(name).is_ok()) {
+        return Err(anyhow!("Column '{}' already exists", name));
+    }
+    df.clone()
+        .lazy()
+        .with_columns([
+            col(column).dt().year().alias(&year),
+            col(column).dt().month().cast(DataType::Int32).alias(&month),
+            col(column).dt().weekday().cast(DataType::Int32).alias(&weekday),
+        ])
+        .collect()
+        .map_err(|e| anyhow!("Failed to derive date parts: {}", e))
+}
@@ -0,0 +1,98 @@
+//! Duplicate rows: finding rows that repeat on every column or on chosen key
+//! columns (e.g. a student ID submitted twice), and keeping one per group.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+/// Which row of each duplicate group stays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum KeepRule {
+    First,
+    Last,
+    /// The row with the fewest missing cells (the first of those on a tie)
+    MostComplete,
+}
+
+impl KeepRule {
+    pub const ALL: [KeepRule; 3] = [KeepRule::First, KeepRule::Last, KeepRule::MostComplete];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeepRule::First => "最初の行を残す",
+            KeepRule::Last => "最後の行を残す",
+            KeepRule::MostComplete => "欠損が最も少ない行を残す",
+        }
+    }
+}
+
+/// Rows (from 0) of each group sharing the same keys, for groups with more
+/// than one row, in order of first appearance. No keys: compare whole rows.
+/// Rows with a missing key are not duplicates of each other.
+fn groups(df: &DataFrame, keys: &[String]) -> Result<Vec<Vec<usize>>> {
+    for key in keys {
+        df.column(key).map_err(|_| anyhow!("Column '{}' not found", key))?;
+    }
+    let index = "__row_index";
+    let mut rows = df.clone().lazy().with_row_index(index, None);
+    let keys: Vec<Expr> = if keys.is_empty() {
+        df.get_column_names().iter().map(|c| col(c)).collect()
+    } else {
+        let complete = keys.iter().map(|c| col(c).is_not_null()).reduce(|a, b| a.and(b)).expect("keys are not empty");
+        rows = rows.filter(complete);
+        keys.iter().map(|c| col(c)).collect()
+    };
+    let grouped = rows
+        .group_by_stable(keys)
+        .agg([col(index)])
+        .collect()
+        .map_err(|e| anyhow!("Duplicate check error: {}", e))?;
+    let rows = grouped.column(index)?.list()?.clone();
+    Ok(rows
+        .into_iter()
+        .flatten()
+        .map(|s| s.idx().map(|idx| idx.into_no_null_iter().map(|i| i as usize).collect::<Vec<_>>()))
+        .collect::<PolarsResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .collect())
+}
+
+/// The rows of every duplicate group, with the group number and the 1-based
+/// `row` number in front.
+pub fn duplicate_groups(df: &DataFrame, keys: &[String]) -> Result<DataFrame> {
+    let groups = groups(df, keys)?;
+    let rows: Vec<usize> = groups.iter().flatten().copied().collect();
+    let numbers: Vec<u32> = groups.iter().enumerate().flat_map(|(g, rows)| std::iter::repeat_n((g + 1) as u32, rows.len())).collect();
+    let mut out = df.take(&IdxCa::new("idx", rows.iter().map(|r| *r as IdxSize).collect::<Vec<_>>()))?;
+    out.insert_column(0, Series::new("row", rows.iter().map(|r| (*r + 1) as u32).collect::<Vec<_>>()))?;
+    out.insert_column(0, Series::new("group", numbers))?;
+    Ok(out)
+}
+
+/// How a de-duplication went.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DedupReport {
+    /// Groups of rows that repeated
+    pub groups: usize,
+    pub dropped: usize,
+}
+
+/// Keeps one row per duplicate group; other rows keep their order.
+pub fn deduplicate(df: &DataFrame, keys: &[String], keep: KeepRule) -> Result<(DataFrame, DedupReport)> {
+    let groups = groups(df, keys)?;
+    let missing = |row: usize| df.get_columns().iter().filter(|s| s.get(row).is_ok_and(|v| v.is_null())).count();
+    let mut mask = vec![true; df.height()];
+    for group in &groups {
+        let kept = match keep {
+            KeepRule::First => group[0],
+            KeepRule::Last => group[group.len() - 1],
+            // min_by_key returns the first of equal minima
+            KeepRule::MostComplete => *group.iter().min_by_key(|r| missing(**r)).expect("groups are not empty"),
+        };
+        for &row in group {
+            mask[row] = row == kept;
+        }
+    }
+    let report = DedupReport { groups: groups.len(), dropped: mask.iter().filter(|k| !**k).count() };
+    Ok((df.filter(&BooleanChunked::new("keep", mask))?, report))
+}
@@ -1,59 +1,225 @@
 use anyhow::{anyhow, Result};
-use calamine::{Reader, Xlsx, open_workbook_from_rs, Data};
+use calamine::{open_workbook_auto_from_rs, open_workbook_from_rs, Data, Range, Reader, Sheets};
 use polars::prelude::*;
 use std::io::Cursor;
 
-pub fn convert_excel_to_df(bytes: &[u8]) -> Result<DataFrame> {
+use super::type_inference::{build_dataframe, parse_datetime, ImportReport, RawCell};
+
+/// Spreadsheet formats calamine can read.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorkbookFormat {
+    Xls,
+    Xlsx,
+    Xlsb,
+    Ods,
+}
+
+const OLE2_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+impl WorkbookFormat {
+    /// Detects the format from the file's magic bytes, falling back to the
+    /// extension when the content is not conclusive.
+    pub fn detect(file_name: &str, bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(OLE2_MAGIC) {
+            return Some(Self::Xls);
+        }
+        if bytes.starts_with(ZIP_MAGIC) {
+            // ODF stores an uncompressed `mimetype` entry first; OOXML names its
+            // parts xl/workbook.xml or xl/workbook.bin.
+            let head = &bytes[..bytes.len().min(4096)];
+            if contains(head, b"application/vnd.oasis.opendocument.spreadsheet") {
+                return Some(Self::Ods);
+            }
+            if contains(bytes, b"xl/workbook.bin") {
+                return Some(Self::Xlsb);
+            }
+            if contains(bytes, b"xl/workbook.xml") {
+                return Some(Self::Xlsx);
+            }
+        }
+        Self::from_extension(file_name)
+    }
+
+    pub fn from_extension(file_name: &str) -> Option<Self> {
+        let lower = file_name.to_lowercase();
+        let ext = lower.rsplit('.').next()?;
+        match ext {
+            "xls" => Some(Self::Xls),
+            "xlsx" | "xlsm" => Some(Self::Xlsx),
+            "xlsb" => Some(Self::Xlsb),
+            "ods" => Some(Self::Ods),
+            _ => None,
+        }
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Opens a workbook of the given format, or lets calamine try every reader
+/// when the format is unknown.
+pub fn open_workbook(bytes: &[u8], format: Option<WorkbookFormat>) -> Result<Sheets<Cursor<&[u8]>>> {
     let cursor = Cursor::new(bytes);
-    let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)
-        .map_err(|e| anyhow!("Failed to open Excel file: {}", e))?;
+    let sheets = match format {
+        Some(WorkbookFormat::Xls) => open_workbook_from_rs(cursor).map(Sheets::Xls).map_err(|e| e.to_string()),
+        Some(WorkbookFormat::Xlsx) => open_workbook_from_rs(cursor).map(Sheets::Xlsx).map_err(|e| e.to_string()),
+        Some(WorkbookFormat::Xlsb) => open_workbook_from_rs(cursor).map(Sheets::Xlsb).map_err(|e| e.to_string()),
+        Some(WorkbookFormat::Ods) => open_workbook_from_rs(cursor).map(Sheets::Ods).map_err(|e| e.to_string()),
+        None => open_workbook_auto_from_rs(cursor).map_err(|e| e.to_string()),
+    };
+    sheets.map_err(|e| anyhow!("Failed to open Excel file: {}", e))
+}
+
+/// Which part of a workbook to import.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SheetOptions {
+    /// Sheet name; the first sheet when `None`
+    pub sheet: Option<String>,
+    /// 1-based row number of the header row; the first row of the area when `None`
+    pub header_row: Option<usize>,
+    /// Cell range in A1 notation, e.g. `B3:F120`; the whole used area when `None`
+    pub range: Option<String>,
+}
+
+/// Parses an A1-style cell reference into a 0-based `(row, column)` pair.
+pub fn parse_cell_ref(cell: &str) -> Result<(u32, u32)> {
+    let cell = cell.trim().to_uppercase();
+    let split = cell.find(|c: char| c.is_ascii_digit()).unwrap_or(cell.len());
+    let (letters, digits) = cell.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(anyhow!("Invalid cell reference: {}", cell));
+    }
+    let row: u32 = digits.parse().map_err(|_| anyhow!("Invalid cell reference: {}", cell))?;
+    if row == 0 {
+        return Err(anyhow!("Invalid cell reference: {}", cell));
+    }
+    let col = letters.chars().fold(0u32, |acc, c| acc * 26 + (c as u32 - 'A' as u32 + 1));
+    Ok((row - 1, col - 1))
+}
+
+/// Parses `B3:F120` into absolute 0-based start and end positions.
+pub fn parse_cell_range(range: &str) -> Result<((u32, u32), (u32, u32))> {
+    let (start, end) = range
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Cell range must look like A1:D20"))?;
+    let (start, end) = (parse_cell_ref(start)?, parse_cell_ref(end)?);
+    if start.0 > end.0 || start.1 > end.1 {
+        return Err(anyhow!("Cell range {} is reversed", range));
+    }
+    Ok((start, end))
+}
+
+/// Names of all sheets in the workbook, in workbook order.
+pub fn sheet_names(file_name: &str, bytes: &[u8]) -> Result<Vec<String>> {
+    let workbook = open_workbook(bytes, WorkbookFormat::detect(file_name, bytes))?;
+    Ok(workbook.sheet_names())
+}
+
+fn read_range(file_name: &str, bytes: &[u8], options: &SheetOptions) -> Result<Range<Data>> {
+    let mut workbook = open_workbook(bytes, WorkbookFormat::detect(file_name, bytes))?;
+    let range = match &options.sheet {
+        Some(name) => workbook
+            .worksheet_range(name)
+            .map_err(|e| anyhow!("Failed to read worksheet {}: {}", name, e))?,
+        None => workbook
+            .worksheet_range_at(0)
+            .ok_or_else(|| anyhow!("No worksheets found"))?
+            .map_err(|e| anyhow!("Failed to read worksheet: {}", e))?,
+    };
 
-    // Assume the first sheet is the target
-    let range = workbook
-        .worksheet_range_at(0)
-        .ok_or_else(|| anyhow!("No worksheets found"))?
-        .map_err(|e| anyhow!("Failed to read worksheet: {}", e))?;
+    let Some(cells) = options.range.as_deref().map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(range);
+    };
+    let (start, end) = parse_cell_range(cells)?;
+    let used_end = range.end().ok_or_else(|| anyhow!("Empty worksheet"))?;
+    // Clamp to the used area so `A1:Z99999` does not allocate empty rows
+    Ok(range.range(start, (end.0.min(used_end.0), end.1.min(used_end.1))))
+}
 
-    let mut rows_iter = range.rows();
+/// The first `n_rows` rows of the selected sheet and range as text, each with
+/// its 1-based spreadsheet row number, for choosing the header row.
+pub fn preview_rows(file_name: &str, bytes: &[u8], options: &SheetOptions, n_rows: usize) -> Result<Vec<(usize, Vec<String>)>> {
+    let range = read_range(file_name, bytes, options)?;
+    let first_row = range.start().map(|(r, _)| r as usize).unwrap_or(0);
+    Ok(range
+        .rows()
+        .take(n_rows)
+        .enumerate()
+        .map(|(i, row)| (first_row + i + 1, row.iter().map(|c| c.to_string()).collect()))
+        .collect())
+}
 
-    // Get headers
+/// Reads the first sheet of an Excel/ODS workbook, detecting the format from its content.
+pub fn convert_excel_to_df(bytes: &[u8]) -> Result<DataFrame> {
+    read_workbook("", bytes, &SheetOptions::default())
+}
+
+/// Builds a DataFrame from the sheet, header row and range in `options`.
+/// The file name is only used as a fallback for format detection.
+pub fn read_workbook(file_name: &str, bytes: &[u8], options: &SheetOptions) -> Result<DataFrame> {
+    read_workbook_with_report(file_name, bytes, options).map(|(df, _)| df)
+}
+
+/// Like [`read_workbook`], also returning which cells did not fit their
+/// column's inferred type and were set to null.
+pub fn read_workbook_with_report(file_name: &str, bytes: &[u8], options: &SheetOptions) -> Result<(DataFrame, ImportReport)> {
+    let range = read_range(file_name, bytes, options)?;
+    let first_row = range.start().ok_or_else(|| anyhow!("Empty worksheet"))?.0 as usize;
+    let skip = match options.header_row {
+        Some(0) => return Err(anyhow!("Header row numbers start at 1")),
+        Some(row) if row - 1 < first_row => {
+            return Err(anyhow!("Header row {} is above the selected area (starts at row {})", row, first_row + 1))
+        }
+        Some(row) => row - 1 - first_row,
+        None => 0,
+    };
+
+    let mut rows_iter = range.rows().skip(skip);
+
+    // Get headers; blank header cells get a positional name so columns stay unique
     let headers: Vec<String> = rows_iter
         .next()
-        .ok_or_else(|| anyhow!("Empty worksheet"))?
+        .ok_or_else(|| anyhow!("Header row is outside the worksheet"))?
         .iter()
-        .map(|c| c.to_string())
+        .enumerate()
+        .map(|(i, c)| match c.to_string().trim() {
+            "" => format!("Column{}", i + 1),
+            name => name.to_string(),
+        })
         .collect();
 
-    let mut columns_data: Vec<Vec<AnyValue>> = vec![vec![]; headers.len()];
+    let mut columns_data: Vec<Vec<RawCell>> = vec![vec![]; headers.len()];
 
     // Iterate over rows and collect data
     for row in rows_iter {
         for (i, cell) in row.iter().enumerate() {
             if i < columns_data.len() {
-                let value = match cell {
-                    Data::Int(v) => AnyValue::Int64(*v),
-                    Data::Float(v) => AnyValue::Float64(*v),
-                    Data::String(v) => AnyValue::StringOwned(v.into()),
-                    Data::Bool(v) => AnyValue::Boolean(*v),
-                    Data::DateTime(v) => AnyValue::Float64(v.as_f64()), // Simplification
-                    Data::Error(_) => AnyValue::Null,
-                    Data::Empty => AnyValue::Null,
-                    Data::DateTimeIso(v) => AnyValue::StringOwned(v.into()), 
-                    Data::DurationIso(v) => AnyValue::StringOwned(v.into()), 
-                };
-                columns_data[i].push(value);
+                columns_data[i].push(raw_cell(cell));
             }
         }
     }
 
-    // Create Series
-    let series_vec: Vec<Series> = headers
-        .into_iter()
-        .zip(columns_data.into_iter())
-        .map(|(name, data)| {
-            Series::from_any_values(&name, &data, false).unwrap()
-        })
-        .collect();
+    // Data starts on the row after the header (1-based row numbers for the report)
+    let first_data_row = first_row + skip + 2;
+    build_dataframe(headers, columns_data, first_data_row)
+}
 
-    DataFrame::new(series_vec).map_err(|e| anyhow!("Failed to create DataFrame: {}", e))
+fn raw_cell(cell: &Data) -> RawCell {
+    match cell {
+        Data::Int(v) => RawCell::Int(*v),
+        Data::Float(v) => RawCell::Float(*v),
+        Data::String(v) => RawCell::Text(v.clone()),
+        Data::Bool(v) => RawCell::Bool(*v),
+        // Excel stores dates as serial numbers with a date format
+        Data::DateTime(v) => match v.as_datetime() {
+            Some(dt) if v.is_datetime() => RawCell::DateTime(dt),
+            _ => RawCell::Float(v.as_f64()),
+        },
+        // ODS stores dates as ISO 8601 strings
+        Data::DateTimeIso(v) => parse_datetime(v).map(RawCell::DateTime).unwrap_or_else(|| RawCell::Text(v.clone())),
+        Data::DurationIso(v) => RawCell::Text(v.clone()),
+        Data::Error(_) | Data::Empty => RawCell::Empty,
+    }
 }
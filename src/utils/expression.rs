@@ -0,0 +1,495 @@
+//! A small expression language for computed columns, e.g.
+//! `(q1 + q2 + q3) / 3`, `log(income)` or `if(score >= 60, "pass", "fail")`,
+//! parsed into a syntax tree and compiled to a Polars expression.
+//!
+//! Column names are written as they are (`英語 * 2`); names with spaces or
+//! symbols go in backticks (`` `Q1 (pre)` ``). Functions:
+//!
+//! - `log(x)`, `log(x, base)`, `log10`, `exp`, `sqrt`, `abs`, `round(x, digits)`
+//! - `zscore(x)`: standardized with the sample SD
+//! - `mean`, `sum`, `min`, `max`: over the row when given several arguments
+//!   (missing if any of them is), over the column with one (so `x - mean(x)`
+//!   centers `x`)
+//! - `if(condition, then, else)`, `is_missing(x)`
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+/// Parsed expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Number(f64),
+    Integer(i64),
+    Text(String),
+    Bool(bool),
+    Column(String),
+    Negate(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Integer(i64),
+    Text(String),
+    Name(String),
+    /// Backtick-quoted column name
+    Quoted(String),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 18] =
+    ["==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "^", "<", ">", "=", "!", "(", ")"];
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == ',' {
+            tokens.push((start, Token::Op(",")));
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent: 1e-3
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let sign = usize::from(chars.get(i + 1).is_some_and(|s| matches!(s, '+' | '-')));
+                if chars.get(i + 1 + sign).is_some_and(|d| d.is_ascii_digit()) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let token = match text.parse::<i64>() {
+                Ok(n) => Token::Integer(n),
+                Err(_) => Token::Number(text.parse().map_err(|_| anyhow!("Invalid number '{}' at position {}", text, start + 1))?),
+            };
+            tokens.push((start, token));
+        } else if matches!(c, '"' | '\'' | '`') {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(anyhow!("Unclosed {} starting at position {}", c, start + 1)),
+                    Some(&q) if q == c => break,
+                    Some('\\') if c != '`' && i + 1 < chars.len() => {
+                        text.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            i += 1;
+            tokens.push((start, if c == '`' { Token::Quoted(text) } else { Token::Text(text) }));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push((start, Token::Name(chars[start..i].iter().collect())));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| anyhow!("Unexpected character '{}' at position {}", c, start + 1))?;
+            i += op.chars().count();
+            tokens.push((start, Token::Op(op)));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.len, |(p, _)| *p) + 1
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    /// Consumes the operator (or keyword) if it comes next.
+    fn eat(&mut self, ops: &[&str]) -> Option<&'static str> {
+        let found = match self.peek() {
+            Some(Token::Op(op)) => ops.iter().find(|o| *o == op).map(|_| *op),
+            Some(Token::Name(name)) => match name.as_str() {
+                "and" if ops.contains(&"&&") => Some("&&"),
+                "or" if ops.contains(&"||") => Some("||"),
+                "not" if ops.contains(&"!") => Some("!"),
+                _ => None,
+            },
+            _ => None,
+        };
+        if found.is_some() {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, op: &str) -> Result<()> {
+        match self.eat(&[op]) {
+            Some(_) => Ok(()),
+            None => Err(self.unexpected(&format!("'{}'", op))),
+        }
+    }
+
+    fn unexpected(&self, wanted: &str) -> anyhow::Error {
+        match self.peek() {
+            None => anyhow!("Expression ends early: expected {}", wanted),
+            Some(token) => anyhow!("Unexpected {} at position {}: expected {}", describe(token), self.position(), wanted),
+        }
+    }
+
+    fn binary(&mut self, ops: &[&str], next: fn(&mut Self) -> Result<Node>) -> Result<Node> {
+        let mut left = next(self)?;
+        while let Some(op) = self.eat(ops) {
+            let right = next(self)?;
+            left = Node::Binary(binary_op(op), Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Node> {
+        self.binary(&["||"], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Node> {
+        self.binary(&["&&"], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Node> {
+        let left = self.additive()?;
+        match self.eat(&["==", "=", "!=", "<=", ">=", "<", ">"]) {
+            Some(op) => Ok(Node::Binary(binary_op(op), Box::new(left), Box::new(self.additive()?))),
+            None => Ok(left),
+        }
+    }
+
+    fn additive(&mut self) -> Result<Node> {
+        self.binary(&["+", "-"], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Node> {
+        self.binary(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        match self.eat(&["-", "!"]) {
+            Some("-") => Ok(Node::Negate(Box::new(self.unary()?))),
+            Some(_) => Ok(Node::Not(Box::new(self.unary()?))),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Node> {
+        let base = self.primary()?;
+        match self.eat(&["^"]) {
+            // Right-associative and binds tighter than a leading minus: -2^2 = -4
+            Some(_) => Ok(Node::Binary(BinaryOp::Pow, Box::new(base), Box::new(self.unary()?))),
+            None => Ok(base),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node> {
+        let position = self.position();
+        match self.next() {
+            Some(Token::Integer(n)) => Ok(Node::Integer(n)),
+            Some(Token::Number(x)) => Ok(Node::Number(x)),
+            Some(Token::Text(s)) => Ok(Node::Text(s)),
+            Some(Token::Quoted(name)) => Ok(Node::Column(name)),
+            Some(Token::Op("(")) => {
+                let inner = self.or()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Some(Token::Name(name)) => {
+                if self.eat(&["("]).is_some() {
+                    let mut args = Vec::new();
+                    if self.eat(&[")"]).is_none() {
+                        loop {
+                            args.push(self.or()?);
+                            if self.eat(&[")"]).is_some() {
+                                break;
+                            }
+                            self.expect(",")?;
+                        }
+                    }
+                    return Ok(Node::Call(name.to_lowercase(), args));
+                }
+                Ok(match name.as_str() {
+                    "true" | "TRUE" => Node::Bool(true),
+                    "false" | "FALSE" => Node::Bool(false),
+                    _ => Node::Column(name),
+                })
+            }
+            Some(token) => Err(anyhow!("Unexpected {} at position {}: expected a value", describe(&token), position)),
+            None => Err(anyhow!("Expression ends early: expected a value")),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(x) => format!("number {}", x),
+        Token::Integer(n) => format!("number {}", n),
+        Token::Text(s) => format!("text \"{}\"", s),
+        Token::Name(n) | Token::Quoted(n) => format!("'{}'", n),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+fn binary_op(op: &str) -> BinaryOp {
+    match op {
+        "+" => BinaryOp::Add,
+        "-" => BinaryOp::Sub,
+        "*" => BinaryOp::Mul,
+        "/" => BinaryOp::Div,
+        "%" => BinaryOp::Rem,
+        "^" => BinaryOp::Pow,
+        "==" | "=" => BinaryOp::Eq,
+        "!=" => BinaryOp::NotEq,
+        "<" => BinaryOp::Lt,
+        "<=" => BinaryOp::LtEq,
+        ">" => BinaryOp::Gt,
+        ">=" => BinaryOp::GtEq,
+        "&&" => BinaryOp::And,
+        _ => BinaryOp::Or,
+    }
+}
+
+/// Parses the whole source into a syntax tree.
+pub fn parse(source: &str) -> Result<Node> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+        return Err(anyhow!("Expression is empty"));
+    }
+    let mut parser = Parser { tokens, pos: 0, len: source.chars().count() };
+    let node = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(parser.unexpected("an operator or the end of the expression"));
+    }
+    Ok(node)
+}
+
+impl Node {
+    /// Column names referenced, in order of first use.
+    pub fn columns(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.collect_columns(&mut out);
+        out
+    }
+
+    fn collect_columns(&self, out: &mut Vec<String>) {
+        match self {
+            Node::Column(name) if !out.contains(name) => out.push(name.clone()),
+            Node::Negate(inner) | Node::Not(inner) => inner.collect_columns(out),
+            Node::Binary(_, l, r) => {
+                l.collect_columns(out);
+                r.collect_columns(out);
+            }
+            Node::Call(_, args) => args.iter().for_each(|a| a.collect_columns(out)),
+            _ => {}
+        }
+    }
+}
+
+fn arity(name: &str, args: &[Node], allowed: std::ops::RangeInclusive<usize>) -> Result<()> {
+    if allowed.contains(&args.len()) {
+        return Ok(());
+    }
+    let expected = match (allowed.start(), allowed.end()) {
+        (a, b) if a == b => format!("{}", a),
+        (a, &usize::MAX) => format!("at least {}", a),
+        (a, b) => format!("{} or {}", a, b),
+    };
+    Err(anyhow!("{}() takes {} argument(s), got {}", name, expected, args.len()))
+}
+
+/// Combines the arguments of a row-wise function pairwise; a missing argument makes the row missing.
+fn row_fold(args: Vec<Expr>, combine: impl Fn(Expr, Expr) -> Expr) -> Expr {
+    let any_missing = args.iter().cloned().map(|a| a.is_null()).reduce(|a, b| a.or(b)).expect("at least one argument");
+    let value = args.into_iter().reduce(combine).expect("at least one argument");
+    when(any_missing).then(lit(NULL)).otherwise(value)
+}
+
+fn compile_call(name: &str, args: &[Node]) -> Result<Expr> {
+    let compiled = args.iter().map(compile).collect::<Result<Vec<_>>>()?;
+    let x = || compiled[0].clone().cast(DataType::Float64);
+    let expr = match name {
+        "log" => {
+            arity(name, args, 1..=2)?;
+            let base = match args.get(1) {
+                None => std::f64::consts::E,
+                Some(Node::Integer(n)) => *n as f64,
+                Some(Node::Number(b)) => *b,
+                Some(_) => return Err(anyhow!("log(): the base must be a number")),
+            };
+            x().log(base)
+        }
+        "log10" => {
+            arity(name, args, 1..=1)?;
+            x().log(10.0)
+        }
+        "exp" => {
+            arity(name, args, 1..=1)?;
+            x().exp()
+        }
+        "sqrt" => {
+            arity(name, args, 1..=1)?;
+            x().sqrt()
+        }
+        "abs" => {
+            arity(name, args, 1..=1)?;
+            compiled[0].clone().abs()
+        }
+        "round" => {
+            arity(name, args, 1..=2)?;
+            let digits = match args.get(1) {
+                None => 0,
+                Some(Node::Integer(n)) if (0..=15).contains(n) => *n as u32,
+                Some(_) => return Err(anyhow!("round(): digits must be a whole number from 0 to 15")),
+            };
+            x().round(digits)
+        }
+        "zscore" => {
+            arity(name, args, 1..=1)?;
+            (x() - x().mean()) / x().std(1)
+        }
+        "mean" | "sum" | "min" | "max" => {
+            arity(name, args, 1..=usize::MAX)?;
+            match (name, compiled.len()) {
+                ("mean", 1) => compiled[0].clone().mean(),
+                ("sum", 1) => compiled[0].clone().sum(),
+                ("min", 1) => compiled[0].clone().min(),
+                ("max", 1) => compiled[0].clone().max(),
+                ("mean", n) => row_fold(compiled, |a, b| a + b) / lit(n as f64),
+                ("sum", _) => row_fold(compiled, |a, b| a + b),
+                ("min", _) => row_fold(compiled, |a, b| when(a.clone().lt_eq(b.clone())).then(a).otherwise(b)),
+                _ => row_fold(compiled, |a, b| when(a.clone().gt_eq(b.clone())).then(a).otherwise(b)),
+            }
+        }
+        "if" => {
+            arity(name, args, 3..=3)?;
+            when(compiled[0].clone()).then(compiled[1].clone()).otherwise(compiled[2].clone())
+        }
+        "is_missing" => {
+            arity(name, args, 1..=1)?;
+            compiled[0].clone().is_null()
+        }
+        _ => return Err(anyhow!("Unknown function '{}'", name)),
+    };
+    Ok(expr)
+}
+
+fn compile(node: &Node) -> Result<Expr> {
+    Ok(match node {
+        Node::Number(x) => lit(*x),
+        Node::Integer(n) => lit(*n),
+        Node::Text(s) => lit(s.clone()),
+        Node::Bool(b) => lit(*b),
+        Node::Column(name) => col(name),
+        Node::Negate(inner) => lit(0) - compile(inner)?,
+        Node::Not(inner) => compile(inner)?.not(),
+        Node::Binary(op, l, r) => {
+            let (l, r) = (compile(l)?, compile(r)?);
+            match op {
+                BinaryOp::Add => l + r,
+                BinaryOp::Sub => l - r,
+                BinaryOp::Mul => l * r,
+                // Always a true division, also between integer columns
+                BinaryOp::Div => binary_expr(l, Operator::TrueDivide, r),
+                BinaryOp::Rem => l % r,
+                BinaryOp::Pow => l.cast(DataType::Float64).pow(r),
+                BinaryOp::Eq => l.eq(r),
+                BinaryOp::NotEq => l.neq(r),
+                BinaryOp::Lt => l.lt(r),
+                BinaryOp::LtEq => l.lt_eq(r),
+                BinaryOp::Gt => l.gt(r),
+                BinaryOp::GtEq => l.gt_eq(r),
+                BinaryOp::And => l.and(r),
+                BinaryOp::Or => l.or(r),
+            }
+        }
+        Node::Call(name, args) => compile_call(name, args)?,
+    })
+}
+
+/// Parses `source` and compiles it against the columns of `df`.
+pub fn compile_expression(df: &DataFrame, source: &str) -> Result<Expr> {
+    let node = parse(source)?;
+    let names = df.get_column_names();
+    let unknown: Vec<String> = node.columns().into_iter().filter(|c| !names.contains(&c.as_str())).collect();
+    if !unknown.is_empty() {
+        return Err(anyhow!(
+            "Unknown column(s): {} (names with spaces or symbols go in backticks; available: {})",
+            unknown.join(", "),
+            names.join(", ")
+        ));
+    }
+    compile(&node)
+}
+
+/// Evaluates the expression and appends it to the frame as column `name`.
+pub fn add_computed_column(df: &DataFrame, name: &str, source: &str) -> Result<DataFrame> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("New column name is empty"));
+    }
+    if df.get_column_names().contains(&name) {
+        return Err(anyhow!("Column '{}' already exists", name));
+    }
+    let expr = compile_expression(df, source)?;
+    let out = df
+        .clone()
+        .lazy()
+        .with_column(expr.alias(name))
+        .collect()
+        .map_err(|e| anyhow!("Evaluation error: {}", e))?;
+    if out.height() != df.height() {
+        return Err(anyhow!("Expression does not give one value per row"));
+    }
+    Ok(out)
+}
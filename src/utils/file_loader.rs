@@ -0,0 +1,55 @@
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use super::csv_import::{read_csv, CsvOptions};
+use super::data_formats::{is_json, is_parquet, read_json, read_parquet};
+use super::excel_to_polars::{read_workbook_with_report, SheetOptions, WorkbookFormat};
+use super::type_inference::ImportReport;
+
+/// File types accepted by the upload picker.
+pub const ACCEPTED_EXTENSIONS: &str = ".csv,.tsv,.txt,.xlsx,.xls,.xlsb,.ods,.json,.jsonl,.ndjson,.parquet";
+
+/// Import settings for either kind of file; only the matching half is used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportOptions {
+    pub sheet: SheetOptions,
+    pub csv: CsvOptions,
+}
+
+/// Builds a DataFrame from an uploaded file. Spreadsheets are recognised by
+/// their magic bytes, so a mislabelled `.xls` that is really `.xlsx` still loads.
+pub fn read_dataframe(file_name: &str, bytes: &[u8]) -> Result<DataFrame> {
+    read_dataframe_with(file_name, bytes, &ImportOptions::default())
+}
+
+/// Same as [`read_dataframe`] with explicit workbook or CSV settings.
+pub fn read_dataframe_with(file_name: &str, bytes: &[u8], options: &ImportOptions) -> Result<DataFrame> {
+    read_dataframe_with_report(file_name, bytes, options).map(|(df, _)| df)
+}
+
+/// Same as [`read_dataframe_with`], also returning the type-inference report
+/// (cells set to null because they did not fit their column's type).
+pub fn read_dataframe_with_report(file_name: &str, bytes: &[u8], options: &ImportOptions) -> Result<(DataFrame, ImportReport)> {
+    if is_workbook(file_name, bytes) {
+        read_workbook_with_report(file_name, bytes, &options.sheet)
+    } else if is_parquet(file_name, bytes) {
+        read_parquet(bytes).map(|df| (df, ImportReport::default()))
+    } else if is_json(file_name) {
+        read_json(bytes).map(|df| (df, ImportReport::default()))
+    } else if is_delimited_text(file_name) {
+        read_csv(bytes, &options.csv).map(|df| (df, ImportReport::default()))
+    } else {
+        Err(anyhow!("Unsupported file format"))
+    }
+}
+
+/// Whether the file is a spreadsheet workbook rather than delimited text.
+pub fn is_workbook(file_name: &str, bytes: &[u8]) -> bool {
+    WorkbookFormat::detect(file_name, bytes).is_some()
+}
+
+/// Whether the file name looks like CSV/TSV text.
+pub fn is_delimited_text(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    [".csv", ".tsv", ".txt"].iter().any(|ext| lower.ends_with(ext))
+}
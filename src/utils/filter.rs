@@ -0,0 +1,161 @@
+//! Row filter shared by every analysis page: conditions on columns combined
+//! with AND or OR, applied to a copy of the data.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use super::type_inference::parse_datetime;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FilterOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    /// Text contains the value
+    Contains,
+    IsMissing,
+    NotMissing,
+}
+
+impl FilterOp {
+    pub const ALL: [FilterOp; 9] = [
+        FilterOp::Eq,
+        FilterOp::NotEq,
+        FilterOp::Lt,
+        FilterOp::LtEq,
+        FilterOp::Gt,
+        FilterOp::GtEq,
+        FilterOp::Contains,
+        FilterOp::IsMissing,
+        FilterOp::NotMissing,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::NotEq => "≠",
+            FilterOp::Lt => "<",
+            FilterOp::LtEq => "≤",
+            FilterOp::Gt => ">",
+            FilterOp::GtEq => "≥",
+            FilterOp::Contains => "を含む",
+            FilterOp::IsMissing => "が欠損",
+            FilterOp::NotMissing => "が欠損でない",
+        }
+    }
+
+    /// Whether the operator compares against a value.
+    pub fn takes_value(self) -> bool {
+        !matches!(self, FilterOp::IsMissing | FilterOp::NotMissing)
+    }
+
+    fn holds(self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            FilterOp::Eq => ordering == Equal,
+            FilterOp::NotEq => ordering != Equal,
+            FilterOp::Lt => ordering == Less,
+            FilterOp::LtEq => ordering != Greater,
+            FilterOp::Gt => ordering == Greater,
+            FilterOp::GtEq => ordering != Less,
+            _ => false,
+        }
+    }
+}
+
+/// One condition, e.g. `学年 = 2`. The value is parsed according to the column type.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Condition {
+    pub column: String,
+    pub op: FilterOp,
+    pub value: String,
+}
+
+impl Condition {
+    /// Rows meeting the condition; missing cells never match a comparison.
+    fn mask(&self, df: &DataFrame) -> Result<BooleanChunked> {
+        let s = df.column(&self.column).map_err(|_| anyhow!("Column '{}' not found", self.column))?;
+        let value = self.value.trim();
+        let matches: BooleanChunked = match self.op {
+            FilterOp::IsMissing => return Ok(s.is_null()),
+            FilterOp::NotMissing => return Ok(s.is_not_null()),
+            FilterOp::Contains => {
+                let text = s.cast(&DataType::String)?;
+                text.str()?.into_iter().map(|v| Some(v.is_some_and(|v| v.contains(value)))).collect()
+            }
+            op if s.dtype().is_numeric() => {
+                let target: f64 = value
+                    .parse()
+                    .map_err(|_| anyhow!("'{}' is not a number (column '{}' is numeric)", value, self.column))?;
+                let values = s.cast(&DataType::Float64)?;
+                values.f64()?.into_iter().map(|v| Some(v.is_some_and(|x| x.partial_cmp(&target).is_some_and(|o| op.holds(o))))).collect()
+            }
+            op => {
+                // Dates compare as YYYY-MM-DD text, which sorts chronologically
+                let (text, target) = match s.dtype() {
+                    DataType::Date | DataType::Datetime(..) => {
+                        let date = parse_datetime(value)
+                            .ok_or_else(|| anyhow!("'{}' is not a date (column '{}' holds dates)", value, self.column))?;
+                        (s.cast(&DataType::Date)?.cast(&DataType::String)?, date.format("%Y-%m-%d").to_string())
+                    }
+                    _ => (s.cast(&DataType::String)?, value.to_string()),
+                };
+                text.str()?.into_iter().map(|v| Some(v.is_some_and(|v| op.holds(v.cmp(target.as_str()))))).collect()
+            }
+        };
+        Ok(matches)
+    }
+
+    pub fn describe(&self) -> String {
+        match self.op {
+            op if op.takes_value() && op != FilterOp::Contains => format!("{} {} {}", self.column, op.label(), self.value.trim()),
+            FilterOp::Contains => format!("{} が「{}」{}", self.column, self.value.trim(), self.op.label()),
+            op => format!("{} {}", self.column, op.label()),
+        }
+    }
+}
+
+/// Conditions combined with AND (`all`) or OR.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RowFilter {
+    pub conditions: Vec<Condition>,
+    pub all: bool,
+}
+
+impl Default for RowFilter {
+    fn default() -> Self {
+        Self { conditions: Vec::new(), all: true }
+    }
+}
+
+impl RowFilter {
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// The rows that pass; the frame itself is unchanged.
+    pub fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        let mut combined: Option<BooleanChunked> = None;
+        for condition in &self.conditions {
+            let mask = condition.mask(df)?;
+            combined = Some(match combined {
+                None => mask,
+                Some(acc) if self.all => &acc & &mask,
+                Some(acc) => &acc | &mask,
+            });
+        }
+        match combined {
+            Some(mask) => Ok(df.filter(&mask)?),
+            None => Ok(df.clone()),
+        }
+    }
+
+    /// Conditions as text, e.g. `学年 = 2 AND 英語 ≥ 60`.
+    pub fn describe(&self) -> String {
+        let joiner = if self.all { " AND " } else { " OR " };
+        self.conditions.iter().map(Condition::describe).collect::<Vec<_>>().join(joiner)
+    }
+}
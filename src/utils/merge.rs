@@ -0,0 +1,126 @@
+//! Combining two datasets on key columns (Polars joins) and checking the
+//! keys beforehand: which ones find no partner and which ones repeat.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+/// Join types offered in the merge tool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Rows whose key is in both datasets
+    Inner,
+    /// Every left row, with right columns missing where there is no match
+    Left,
+    /// Every row of both datasets
+    Outer,
+    /// Left rows whose key is not in the right dataset
+    Anti,
+}
+
+impl JoinKind {
+    pub const ALL: [JoinKind; 4] = [JoinKind::Inner, JoinKind::Left, JoinKind::Outer, JoinKind::Anti];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            JoinKind::Inner => "内部結合 (両方にある行)",
+            JoinKind::Left => "左結合 (左のすべての行)",
+            JoinKind::Outer => "完全外部結合 (すべての行)",
+            JoinKind::Anti => "アンチ結合 (右にない左の行)",
+        }
+    }
+
+    fn args(self) -> JoinArgs {
+        match self {
+            JoinKind::Inner => JoinArgs::new(JoinType::Inner),
+            JoinKind::Left => JoinArgs::new(JoinType::Left),
+            // One key column instead of `id` and `id_right`
+            JoinKind::Outer => JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+            JoinKind::Anti => JoinArgs::new(JoinType::Anti),
+        }
+    }
+}
+
+/// Key columns on each side (paired by position; names may differ) and the join type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeOptions {
+    pub left_on: Vec<String>,
+    pub right_on: Vec<String>,
+    pub kind: JoinKind,
+}
+
+/// What the keys look like before joining.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyCheck {
+    /// Distinct left keys with no row in the right dataset
+    pub left_unmatched: DataFrame,
+    /// Distinct right keys with no row in the left dataset
+    pub right_unmatched: DataFrame,
+    /// Left keys appearing more than once, with a `rows` count
+    pub left_duplicates: DataFrame,
+    pub right_duplicates: DataFrame,
+}
+
+fn validate(left: &DataFrame, right: &DataFrame, options: &MergeOptions) -> Result<()> {
+    if options.left_on.is_empty() {
+        return Err(anyhow!("Select at least one key column"));
+    }
+    if options.left_on.len() != options.right_on.len() {
+        return Err(anyhow!(
+            "Key columns must come in pairs: {} on the left, {} on the right",
+            options.left_on.len(),
+            options.right_on.len()
+        ));
+    }
+    for (l, r) in options.left_on.iter().zip(&options.right_on) {
+        let l_type = left.column(l).map_err(|_| anyhow!("Column '{}' not found in the left dataset", l))?.dtype();
+        let r_type = right.column(r).map_err(|_| anyhow!("Column '{}' not found in the right dataset", r))?.dtype();
+        if l_type != r_type {
+            return Err(anyhow!("Key columns have different types: '{}' is {}, '{}' is {}", l, l_type, r, r_type));
+        }
+    }
+    Ok(())
+}
+
+fn key_exprs(names: &[String]) -> Vec<Expr> {
+    names.iter().map(|n| col(n)).collect()
+}
+
+fn duplicates(df: &DataFrame, keys: &[String]) -> Result<DataFrame> {
+    df.clone()
+        .lazy()
+        .group_by_stable(key_exprs(keys))
+        .agg([len().alias("rows")])
+        .filter(col("rows").gt(lit(1)))
+        .collect()
+        .map_err(|e| anyhow!("Key check error: {}", e))
+}
+
+fn unmatched(df: &DataFrame, keys: &[String], other: &DataFrame, other_keys: &[String]) -> Result<DataFrame> {
+    let distinct = df.clone().lazy().select(key_exprs(keys)).unique_stable(None, UniqueKeepStrategy::First);
+    let other = other.clone().lazy().select(key_exprs(other_keys));
+    distinct
+        .join(other, key_exprs(keys), key_exprs(other_keys), JoinArgs::new(JoinType::Anti))
+        .collect()
+        .map_err(|e| anyhow!("Key check error: {}", e))
+}
+
+/// Unmatched and duplicated keys on both sides, for a preview before merging.
+pub fn check_keys(left: &DataFrame, right: &DataFrame, options: &MergeOptions) -> Result<KeyCheck> {
+    validate(left, right, options)?;
+    Ok(KeyCheck {
+        left_unmatched: unmatched(left, &options.left_on, right, &options.right_on)?,
+        right_unmatched: unmatched(right, &options.right_on, left, &options.left_on)?,
+        left_duplicates: duplicates(left, &options.left_on)?,
+        right_duplicates: duplicates(right, &options.right_on)?,
+    })
+}
+
+/// Joins `right` onto `left`. Right columns whose names clash with left ones get a `_right` suffix.
+pub fn merge(left: &DataFrame, right: &DataFrame, options: &MergeOptions) -> Result<DataFrame> {
+    validate(left, right, options)?;
+    left.clone()
+        .lazy()
+        .join(right.clone().lazy(), key_exprs(&options.left_on), key_exprs(&options.right_on), options.kind.args())
+        .collect()
+        .map_err(|e| anyhow!("Merge error: {}", e))
+}
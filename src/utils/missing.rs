@@ -0,0 +1,175 @@
+//! Missing values: per-column counts, which combinations of columns are
+//! missing together, and listwise deletion / column dropping / imputation.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// How missing cells are filled.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ImputeMethod {
+    /// Column mean (integer columns become floats)
+    Mean,
+    /// Column median (integer columns become floats)
+    Median,
+    /// Most frequent value; ties go to the value seen first
+    Mode,
+    /// A fixed value, parsed as the column's type
+    Constant(String),
+    /// Last observation carried forward; leading missing cells stay missing
+    Locf,
+}
+
+impl ImputeMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImputeMethod::Mean => "平均値",
+            ImputeMethod::Median => "中央値",
+            ImputeMethod::Mode => "最頻値",
+            ImputeMethod::Constant(_) => "定数",
+            ImputeMethod::Locf => "直前の値 (LOCF)",
+        }
+    }
+}
+
+/// A missing-value treatment applied to the selected columns.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MissingAction {
+    /// Drop rows with a missing value in any of the columns (listwise deletion)
+    DropRows(Vec<String>),
+    DropColumns(Vec<String>),
+    Impute(Vec<String>, ImputeMethod),
+}
+
+/// Columns: `column`, `missing` (count) and `percent`.
+pub fn null_summary(df: &DataFrame) -> DataFrame {
+    let height = df.height().max(1) as f64;
+    let names: Vec<&str> = df.get_column_names();
+    let counts: Vec<u32> = df.get_columns().iter().map(|s| s.null_count() as u32).collect();
+    let percents: Vec<f64> = counts.iter().map(|c| *c as f64 / height * 100.0).collect();
+    DataFrame::new(vec![
+        Series::new("column", names),
+        Series::new("missing", counts),
+        Series::new("percent", percents),
+    ])
+    .expect("columns of equal length")
+}
+
+/// Each combination of missing columns that occurs, most frequent first.
+/// Columns: `missing columns` (comma separated, empty for complete rows) and `rows`.
+pub fn missing_patterns(df: &DataFrame) -> DataFrame {
+    let masks: Vec<(String, BooleanChunked)> = df
+        .get_columns()
+        .iter()
+        .filter(|s| s.null_count() > 0)
+        .map(|s| (s.name().to_string(), s.is_null()))
+        .collect();
+    let mut order: Vec<String> = Vec::new();
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for i in 0..df.height() {
+        let pattern = masks
+            .iter()
+            .filter(|(_, mask)| mask.get(i).unwrap_or(false))
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let count = counts.entry(pattern.clone()).or_insert(0);
+        if *count == 0 {
+            order.push(pattern);
+        }
+        *count += 1;
+    }
+    // Stable sort keeps first-seen order among equally frequent patterns
+    order.sort_by_key(|p| std::cmp::Reverse(counts[p]));
+    let rows: Vec<u32> = order.iter().map(|p| counts[p]).collect();
+    DataFrame::new(vec![Series::new("missing columns", order), Series::new("rows", rows)])
+        .expect("columns of equal length")
+}
+
+fn column<'a>(df: &'a DataFrame, name: &str) -> Result<&'a Series> {
+    df.column(name).map_err(|_| anyhow!("Column '{}' not found", name))
+}
+
+/// Replaces the missing cells of `s` with the single value in `value`.
+fn fill_with(s: &Series, value: &Series) -> Result<Series> {
+    let value = value.cast(s.dtype())?.new_from_index(0, s.len());
+    Ok(s.zip_with(&s.is_not_null(), &value)?)
+}
+
+fn mode(s: &Series) -> Result<Option<Series>> {
+    let name = s.name().to_string();
+    let counts = DataFrame::new(vec![s.drop_nulls()])?
+        .lazy()
+        .group_by_stable([col(&name)])
+        .agg([len().alias("n")])
+        .sort(["n"], SortMultipleOptions::default().with_order_descending(true).with_maintain_order(true))
+        .limit(1)
+        .collect()?;
+    Ok((counts.height() == 1).then(|| counts.column(&name).expect("grouped column").clone()))
+}
+
+fn impute(s: &Series, method: &ImputeMethod) -> Result<Series> {
+    if s.null_count() == 0 {
+        return Ok(s.clone());
+    }
+    let numeric = |what: &str| -> Result<Series> {
+        if !s.dtype().is_numeric() {
+            return Err(anyhow!("{} imputation needs a numeric column: '{}' is {}", what, s.name(), s.dtype()));
+        }
+        Ok(s.cast(&DataType::Float64)?)
+    };
+    let filled = match method {
+        ImputeMethod::Mean => {
+            let s = numeric("Mean")?;
+            match s.mean() {
+                Some(mean) => fill_with(&s, &Series::new(s.name(), [mean]))?,
+                None => s,
+            }
+        }
+        ImputeMethod::Median => {
+            let s = numeric("Median")?;
+            match s.median() {
+                Some(median) => fill_with(&s, &Series::new(s.name(), [median]))?,
+                None => s,
+            }
+        }
+        ImputeMethod::Mode => match mode(s)? {
+            Some(value) => fill_with(s, &value)?,
+            None => s.clone(),
+        },
+        ImputeMethod::Constant(text) => {
+            let value = Series::new(s.name(), [text.trim()]).strict_cast(s.dtype()).ok().filter(|v| v.null_count() == 0);
+            let value = value.ok_or_else(|| anyhow!("'{}' cannot be used as a {} value for column '{}'", text, s.dtype(), s.name()))?;
+            fill_with(s, &value)?
+        }
+        ImputeMethod::Locf => s.fill_null(FillNullStrategy::Forward(None))?,
+    };
+    Ok(filled)
+}
+
+/// Applies the treatment and returns the new frame.
+pub fn apply_missing(df: &DataFrame, action: &MissingAction) -> Result<DataFrame> {
+    match action {
+        MissingAction::DropRows(columns) => {
+            let mut keep = BooleanChunked::full("keep", true, df.height());
+            for name in columns {
+                keep = &keep & &column(df, name)?.is_not_null();
+            }
+            Ok(df.filter(&keep)?)
+        }
+        MissingAction::DropColumns(columns) => {
+            for name in columns {
+                column(df, name)?;
+            }
+            Ok(df.drop_many(columns))
+        }
+        MissingAction::Impute(columns, method) => {
+            let mut out = df.clone();
+            for name in columns {
+                let filled = impute(column(df, name)?, method)?;
+                out.replace(name, filled)?;
+            }
+            Ok(out)
+        }
+    }
+}
@@ -1 +1,20 @@
+pub mod binning;
+pub mod csv_import;
+pub mod data_formats;
+pub mod dates;
+pub mod duplicates;
 pub mod excel_to_polars;
+pub mod expression;
+pub mod file_loader;
+pub mod filter;
+pub mod merge;
+pub mod missing;
+pub mod outliers;
+pub mod paste_import;
+pub mod recipe;
+pub mod recode;
+pub mod reshape;
+pub mod samples;
+pub mod scale;
+pub mod type_inference;
+pub mod xlsx_writer;
@@ -0,0 +1,291 @@
+//! Outlier detection (IQR fences, z-score, modified z-score, Mahalanobis
+//! distance) and treatment with a log of every changed cell.
+
+use anyhow::{anyhow, Result};
+use nalgebra::{DMatrix, DVector};
+use polars::prelude::*;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use crate::stats::{mean, median, quantile, std_dev};
+
+/// Scale factor making the MAD a consistent estimate of the SD for normal data.
+const MAD_SCALE: f64 = 1.4826;
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum OutlierMethod {
+    /// Outside `Q1 - k·IQR` .. `Q3 + k·IQR` (k = 1.5 by convention)
+    Iqr(f64),
+    /// `|x - mean| / SD` above the threshold
+    ZScore(f64),
+    /// `|x - median| / (1.4826·MAD)` above the threshold (3.5 is common)
+    ModifiedZ(f64),
+    /// Squared Mahalanobis distance across all selected columns beyond the
+    /// chi-square quantile at this significance level (e.g. 0.001)
+    Mahalanobis(f64),
+}
+
+impl OutlierMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            OutlierMethod::Iqr(_) => "IQR (箱ひげ図の外側)",
+            OutlierMethod::ZScore(_) => "z 得点",
+            OutlierMethod::ModifiedZ(_) => "修正 z 得点 (MAD)",
+            OutlierMethod::Mahalanobis(_) => "マハラノビス距離 (多変量)",
+        }
+    }
+
+    fn is_per_column(self) -> bool {
+        !matches!(self, OutlierMethod::Mahalanobis(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OutlierTreatment {
+    /// Drop the flagged rows
+    Remove,
+    /// Replace flagged values by the nearest bound
+    Winsorize,
+    /// Make flagged values missing
+    SetNull,
+}
+
+impl OutlierTreatment {
+    pub fn label(self) -> &'static str {
+        match self {
+            OutlierTreatment::Remove => "行を削除",
+            OutlierTreatment::Winsorize => "境界値に置き換え (ウィンソライズ)",
+            OutlierTreatment::SetNull => "欠損値にする",
+        }
+    }
+}
+
+/// Values outside `lower`..`upper` in `column` count as outliers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounds {
+    pub column: String,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// A flagged cell (row index from 0). For Mahalanobis distance every
+/// selected column of the row is flagged with the row's D².
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flag {
+    pub row: usize,
+    pub column: String,
+    pub value: f64,
+    /// z, modified z, D², or for IQR the distance beyond the fence in IQRs
+    pub score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+    pub method: OutlierMethod,
+    pub columns: Vec<String>,
+    pub flags: Vec<Flag>,
+    /// Per-column bounds; empty for Mahalanobis distance
+    pub bounds: Vec<Bounds>,
+}
+
+impl Detection {
+    /// Flagged row indices, ascending.
+    pub fn rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self.flags.iter().map(|f| f.row).collect();
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+}
+
+/// One changed cell; `row` is 1-based in the data before the change.
+/// Removing a row logs each flag that caused the removal, with `after`
+/// empty; the row's other cells are not logged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub row: usize,
+    pub column: String,
+    pub before: f64,
+    pub after: Option<f64>,
+}
+
+fn values(df: &DataFrame, name: &str) -> Result<Vec<Option<f64>>> {
+    let s = df.column(name).map_err(|_| anyhow!("Column '{}' not found", name))?;
+    if !s.dtype().is_numeric() {
+        return Err(anyhow!("'{}' is not numeric", name));
+    }
+    Ok(s.cast(&DataType::Float64)?.f64()?.into_iter().map(|v| v.filter(|x| x.is_finite())).collect())
+}
+
+/// How far out a value is, in the method's units.
+type Score = Box<dyn Fn(f64) -> f64>;
+
+/// Bounds of one column and the score of a value.
+fn column_bounds(name: &str, observed: &[f64], method: OutlierMethod) -> Result<(Bounds, Score)> {
+    if observed.len() < 3 {
+        return Err(anyhow!("'{}' needs at least 3 values", name));
+    }
+    let (lower, upper, score): (f64, f64, Score) = match method {
+        OutlierMethod::Iqr(k) => {
+            let mut sorted = observed.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+            let iqr = q3 - q1;
+            let (lower, upper) = (q1 - k * iqr, q3 + k * iqr);
+            let score = move |x: f64| if iqr > 0.0 { ((lower - x).max(x - upper)).max(0.0) / iqr } else { 0.0 };
+            (lower, upper, Box::new(score))
+        }
+        OutlierMethod::ZScore(t) => {
+            let (m, sd) = (mean(observed), std_dev(observed));
+            (m - t * sd, m + t * sd, Box::new(move |x: f64| if sd > 0.0 { (x - m).abs() / sd } else { 0.0 }))
+        }
+        OutlierMethod::ModifiedZ(t) => {
+            let med = median(observed);
+            let deviations: Vec<f64> = observed.iter().map(|x| (x - med).abs()).collect();
+            let spread = MAD_SCALE * median(&deviations);
+            let score = move |x: f64| if spread > 0.0 { (x - med).abs() / spread } else { 0.0 };
+            (med - t * spread, med + t * spread, Box::new(score))
+        }
+        OutlierMethod::Mahalanobis(_) => unreachable!("not a per-column method"),
+    };
+    Ok((Bounds { column: name.to_string(), lower, upper }, score))
+}
+
+fn mahalanobis(df: &DataFrame, columns: &[String], alpha: f64) -> Result<Vec<Flag>> {
+    if columns.len() < 2 {
+        return Err(anyhow!("Mahalanobis distance needs at least 2 columns"));
+    }
+    if !(0.0 < alpha && alpha < 1.0) {
+        return Err(anyhow!("Significance level must be between 0 and 1"));
+    }
+    let data = columns.iter().map(|c| values(df, c)).collect::<Result<Vec<_>>>()?;
+    // Complete rows only
+    let rows: Vec<usize> = (0..df.height()).filter(|&r| data.iter().all(|c| c[r].is_some())).collect();
+    let p = columns.len();
+    if rows.len() <= p {
+        return Err(anyhow!("Not enough complete rows for {} columns", p));
+    }
+    let x = DMatrix::from_fn(rows.len(), p, |i, j| data[j][rows[i]].unwrap_or_default());
+    let means = DVector::from_fn(p, |j, _| x.column(j).mean());
+    let centered = DMatrix::from_fn(rows.len(), p, |i, j| x[(i, j)] - means[j]);
+    let covariance = centered.transpose() * &centered / (rows.len() - 1) as f64;
+    let inverse = covariance
+        .try_inverse()
+        .ok_or_else(|| anyhow!("Covariance matrix is singular (columns are collinear)"))?;
+    let cutoff = ChiSquared::new(p as f64).map_err(|e| anyhow!("{}", e))?.inverse_cdf(1.0 - alpha);
+
+    let mut flags = Vec::new();
+    for (i, &row) in rows.iter().enumerate() {
+        let d = centered.row(i).transpose();
+        let d2 = (d.transpose() * &inverse * &d)[(0, 0)];
+        if d2 > cutoff {
+            for (j, column) in columns.iter().enumerate() {
+                flags.push(Flag { row, column: column.clone(), value: x[(i, j)], score: d2 });
+            }
+        }
+    }
+    Ok(flags)
+}
+
+/// Flags outliers in the selected numeric columns. Missing values are ignored.
+pub fn detect_outliers(df: &DataFrame, columns: &[String], method: OutlierMethod) -> Result<Detection> {
+    if columns.is_empty() {
+        return Err(anyhow!("Select at least one numeric column"));
+    }
+    let mut detection = Detection { method, columns: columns.to_vec(), flags: Vec::new(), bounds: Vec::new() };
+    if let OutlierMethod::Mahalanobis(alpha) = method {
+        detection.flags = mahalanobis(df, columns, alpha)?;
+        return Ok(detection);
+    }
+    for name in columns {
+        let column = values(df, name)?;
+        let observed: Vec<f64> = column.iter().flatten().copied().collect();
+        let (bounds, score) = column_bounds(name, &observed, method)?;
+        for (row, value) in column.iter().enumerate() {
+            if let Some(x) = *value {
+                if x < bounds.lower || x > bounds.upper {
+                    detection.flags.push(Flag { row, column: name.clone(), value: x, score: score(x) });
+                }
+            }
+        }
+        detection.bounds.push(bounds);
+    }
+    detection.flags.sort_by_key(|f| f.row);
+    Ok(detection)
+}
+
+/// The flagged rows with their 1-based `row` number and the flagged columns.
+pub fn flagged_rows(df: &DataFrame, detection: &Detection) -> Result<DataFrame> {
+    let rows = detection.rows();
+    let flagged: Vec<String> = rows
+        .iter()
+        .map(|r| detection.flags.iter().filter(|f| f.row == *r).map(|f| f.column.as_str()).collect::<Vec<_>>().join(", "))
+        .collect();
+    let indices = IdxCa::new("idx", rows.iter().map(|r| *r as IdxSize).collect::<Vec<_>>());
+    let mut out = df.take(&indices)?;
+    out.insert_column(0, Series::new("flagged", flagged))?;
+    out.insert_column(0, Series::new("row", rows.iter().map(|r| (*r + 1) as u32).collect::<Vec<_>>()))?;
+    Ok(out)
+}
+
+/// Applies the treatment to the flagged cells (or rows) and logs each change.
+pub fn treat_outliers(df: &DataFrame, detection: &Detection, treatment: OutlierTreatment) -> Result<(DataFrame, Vec<Change>)> {
+    let mut changes = Vec::new();
+    match treatment {
+        OutlierTreatment::Remove => {
+            let rows = detection.rows();
+            let mut keep = vec![true; df.height()];
+            for flag in &detection.flags {
+                changes.push(Change { row: flag.row + 1, column: flag.column.clone(), before: flag.value, after: None });
+            }
+            for r in &rows {
+                keep[*r] = false;
+            }
+            let out = df.filter(&BooleanChunked::new("keep", keep))?;
+            Ok((out, changes))
+        }
+        OutlierTreatment::Winsorize | OutlierTreatment::SetNull => {
+            if treatment == OutlierTreatment::Winsorize && !detection.method.is_per_column() {
+                return Err(anyhow!("Winsorizing needs per-column bounds; use IQR, z-score or MAD"));
+            }
+            let mut out = df.clone();
+            for name in &detection.columns {
+                let flags: Vec<&Flag> = detection.flags.iter().filter(|f| f.column == *name).collect();
+                if flags.is_empty() {
+                    continue;
+                }
+                let original = df.column(name)?;
+                let mut column: Vec<Option<f64>> = original.cast(&DataType::Float64)?.f64()?.into_iter().collect();
+                let bounds = detection.bounds.iter().find(|b| b.column == *name);
+                for flag in flags {
+                    let after = match (treatment, bounds) {
+                        (OutlierTreatment::Winsorize, Some(b)) => Some(flag.value.clamp(b.lower, b.upper)),
+                        _ => None,
+                    };
+                    column[flag.row] = after;
+                    changes.push(Change { row: flag.row + 1, column: name.clone(), before: flag.value, after });
+                }
+                let replaced = match treatment {
+                    // Only the flagged cells change, so integer columns stay integer
+                    OutlierTreatment::SetNull => {
+                        let keep: BooleanChunked = column.iter().map(|v| Some(v.is_some())).collect();
+                        original.zip_with(&keep, &Series::full_null(name, df.height(), original.dtype()))?
+                    }
+                    _ => Series::new(name, column),
+                };
+                out.replace(name, replaced)?;
+            }
+            Ok((out, changes))
+        }
+    }
+}
+
+/// The change log as a table: `row`, `column`, `before`, `after` (empty when removed or set missing).
+pub fn change_log(changes: &[Change]) -> DataFrame {
+    DataFrame::new(vec![
+        Series::new("row", changes.iter().map(|c| c.row as u32).collect::<Vec<_>>()),
+        Series::new("column", changes.iter().map(|c| c.column.as_str()).collect::<Vec<_>>()),
+        Series::new("before", changes.iter().map(|c| c.before).collect::<Vec<_>>()),
+        Series::new("after", changes.iter().map(|c| c.after).collect::<Vec<_>>()),
+    ])
+    .expect("columns of equal length")
+}
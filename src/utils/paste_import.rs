@@ -0,0 +1,88 @@
+//! Tab-separated text pasted from a spreadsheet (Excel, Google Sheets, LibreOffice).
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use super::type_inference::{build_dataframe, parse_datetime, ImportReport, RawCell};
+
+/// Splits pasted text into rows of cells. Spreadsheets quote a cell with `"`
+/// when it contains a tab, line break or quote (doubled inside the quotes).
+pub fn split_pasted(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.is_empty() => quoted = true,
+            '\t' if !quoted => row.push(std::mem::take(&mut cell)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    // Trailing blank lines from the copied selection carry no data
+    while rows.last().is_some_and(|r| r.iter().all(|c| c.trim().is_empty())) {
+        rows.pop();
+    }
+    rows
+}
+
+/// Whether the first row looks like column names: no numbers or dates in it,
+/// no repeated names, and at least one more row below it.
+pub fn detect_header(rows: &[Vec<String>]) -> bool {
+    let Some(first) = rows.first() else { return false };
+    let mut seen = std::collections::HashSet::new();
+    rows.len() > 1
+        && first.iter().all(|c| {
+            let c = c.trim();
+            c.is_empty()
+                || (matches!(RawCell::from_text(c), RawCell::Text(_))
+                    && c.parse::<f64>().is_err()
+                    && parse_datetime(c).is_none()
+                    && seen.insert(c))
+        })
+        && first.iter().any(|c| !c.trim().is_empty())
+}
+
+/// Builds a DataFrame from pasted text with the same type inference as
+/// spreadsheet imports.
+pub fn read_pasted(text: &str, has_header: bool) -> Result<(DataFrame, ImportReport)> {
+    let rows = split_pasted(text);
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    if width == 0 {
+        return Err(anyhow!("No data to import"));
+    }
+
+    let (headers, body) = if has_header {
+        let headers = (0..width)
+            .map(|i| match rows[0].get(i).map(|h| h.trim()).unwrap_or("") {
+                "" => format!("Column{}", i + 1),
+                name => name.to_string(),
+            })
+            .collect();
+        (headers, &rows[1..])
+    } else {
+        ((0..width).map(|i| format!("Column{}", i + 1)).collect(), &rows[..])
+    };
+
+    let columns = (0..width)
+        .map(|i| body.iter().map(|r| r.get(i).map_or(RawCell::Empty, |c| RawCell::from_text(c))).collect())
+        .collect();
+    // Row numbers in the report count pasted lines from 1
+    build_dataframe(headers, columns, if has_header { 2 } else { 1 })
+}
@@ -0,0 +1,128 @@
+//! Cleaning steps as data. Every change made to a dataset on the Data
+//! Cleaning page is a [`Step`]; the steps of a dataset form a recipe that can
+//! be saved as JSON and replayed on a fresh upload.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::binning::{bin_column, BinSpec};
+use super::dates::add_date_parts;
+use super::duplicates::{deduplicate, KeepRule};
+use super::expression::add_computed_column;
+use super::filter::RowFilter;
+use super::missing::{apply_missing, MissingAction};
+use super::outliers::{detect_outliers, treat_outliers, OutlierMethod, OutlierTreatment};
+use super::recode::{cast_column, merge_rare, recode, set_level_order, CastTarget};
+use super::reshape::{melt, pivot, MeltOptions, PivotOptions};
+use super::scale::{score_scale, ScaleSpec};
+
+/// Recipe file format version, bumped when steps change incompatibly.
+pub const RECIPE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    /// Keep only the rows matching the filter
+    Filter { filter: RowFilter },
+    Missing { action: MissingAction },
+    Cast { column: String, target: CastTarget },
+    Recode { column: String, mapping: Vec<(String, String)>, into: String },
+    MergeRare { column: String, min_count: usize, other_label: String },
+    LevelOrder { column: String, levels: Vec<String> },
+    Compute { name: String, expression: String },
+    DateParts { column: String },
+    Bin { spec: BinSpec },
+    /// No keys: whole rows are compared
+    Deduplicate { keys: Vec<String>, keep: KeepRule },
+    Scale { spec: ScaleSpec },
+    /// Outliers are detected again on the frame the step is applied to
+    Outliers { columns: Vec<String>, method: OutlierMethod, treatment: OutlierTreatment },
+    Melt { options: MeltOptions },
+    Pivot { options: PivotOptions },
+}
+
+impl Step {
+    pub fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        match self {
+            Step::Filter { filter } => filter.apply(df),
+            Step::Missing { action } => apply_missing(df, action),
+            Step::Cast { column, target } => Ok(cast_column(df, column, *target)?.0),
+            Step::Recode { column, mapping, into } => recode(df, column, mapping, into),
+            Step::MergeRare { column, min_count, other_label } => Ok(merge_rare(df, column, *min_count, other_label)?.0),
+            Step::LevelOrder { column, levels } => set_level_order(df, column, levels),
+            Step::Compute { name, expression } => add_computed_column(df, name, expression),
+            Step::DateParts { column } => add_date_parts(df, column),
+            Step::Bin { spec } => Ok(bin_column(df, spec)?.0),
+            Step::Deduplicate { keys, keep } => Ok(deduplicate(df, keys, *keep)?.0),
+            Step::Scale { spec } => Ok(score_scale(df, spec)?.0),
+            Step::Outliers { columns, method, treatment } => {
+                let detection = detect_outliers(df, columns, *method)?;
+                Ok(treat_outliers(df, &detection, *treatment)?.0)
+            }
+            Step::Melt { options } => melt(df, options),
+            Step::Pivot { options } => pivot(df, options),
+        }
+    }
+
+    /// One line for the history list.
+    pub fn describe(&self) -> String {
+        match self {
+            Step::Filter { filter } => format!("行の絞り込み: {}", filter.describe()),
+            Step::Missing { action } => match action {
+                MissingAction::DropRows(columns) => format!("欠損のある行を削除: {}", columns.join(", ")),
+                MissingAction::DropColumns(columns) => format!("列を削除: {}", columns.join(", ")),
+                MissingAction::Impute(columns, method) => format!("欠損を補完 ({}): {}", method.label(), columns.join(", ")),
+            },
+            Step::Cast { column, target } => format!("「{}」を{}に変換", column, target.label()),
+            Step::Recode { column, mapping, into } => format!("「{}」の値を {} 個置き換え →「{}」", column, mapping.len(), into),
+            Step::MergeRare { column, min_count, other_label } => {
+                format!("「{}」の {} 行未満のカテゴリを「{}」にまとめる", column, min_count, other_label)
+            }
+            Step::LevelOrder { column, levels } => format!("「{}」の水準の順序: {}", column, levels.join(" < ")),
+            Step::Compute { name, expression } => format!("列「{}」= {}", name.trim(), expression),
+            Step::DateParts { column } => format!("「{}」から年・月・曜日の列を追加", column),
+            Step::Bin { spec } => format!("「{}」を{}で区切り →「{}」", spec.column, spec.method.label(), spec.into.trim()),
+            Step::Deduplicate { keys, keep } => {
+                let on = if keys.is_empty() { "すべての列".to_string() } else { keys.join(", ") };
+                format!("重複行を削除 ({}で比較、{})", on, keep.label())
+            }
+            Step::Scale { spec } => format!("尺度得点「{}」({} 項目)", spec.name.trim(), spec.items.len()),
+            Step::Outliers { columns, method, treatment } => {
+                format!("外れ値 ({}) を{}: {}", method.label(), treatment.label(), columns.join(", "))
+            }
+            Step::Melt { options } => format!("横長 → 縦長: {}", options.value_columns.join(", ")),
+            Step::Pivot { options } => format!("縦長 → 横長: 「{}」の値を列に", options.names_from),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Recipe {
+    version: u32,
+    steps: Vec<Step>,
+}
+
+pub fn recipe_to_json(steps: &[Step]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&Recipe { version: RECIPE_VERSION, steps: steps.to_vec() })?)
+}
+
+pub fn recipe_from_json(text: &str) -> Result<Vec<Step>> {
+    let recipe: Recipe = serde_json::from_str(text).map_err(|e| anyhow!("Not a valid recipe file: {}", e))?;
+    if recipe.version != RECIPE_VERSION {
+        return Err(anyhow!("Unsupported recipe version {} (expected {})", recipe.version, RECIPE_VERSION));
+    }
+    Ok(recipe.steps)
+}
+
+/// Applies the steps in order and returns the frame after each one. Fails at
+/// the first step that does not fit the data, naming it.
+pub fn replay(df: &DataFrame, steps: &[Step]) -> Result<Vec<DataFrame>> {
+    let mut frames: Vec<DataFrame> = Vec::with_capacity(steps.len());
+    for (i, step) in steps.iter().enumerate() {
+        let current = frames.last().unwrap_or(df);
+        let next = step.apply(current).map_err(|e| anyhow!("Step {} ({}): {}", i + 1, step.describe(), e))?;
+        frames.push(next);
+    }
+    Ok(frames)
+}
@@ -0,0 +1,219 @@
+//! Column type conversion and categorical recoding: casting with a report of
+//! the cells that could not be converted, value mapping tables, merging rare
+//! categories and fixing the order of category levels.
+
+use anyhow::{anyhow, Result};
+use polars::export::arrow::array::Utf8ViewArray;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+use super::type_inference::parse_datetime;
+
+/// How many unconvertible values a [`CastReport`] lists.
+const REPORT_EXAMPLES: usize = 10;
+
+/// Types a column can be converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CastTarget {
+    Text,
+    Integer,
+    Float,
+    Categorical,
+    Date,
+}
+
+impl CastTarget {
+    pub const ALL: [CastTarget; 5] =
+        [CastTarget::Text, CastTarget::Integer, CastTarget::Float, CastTarget::Categorical, CastTarget::Date];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CastTarget::Text => "文字列",
+            CastTarget::Integer => "整数",
+            CastTarget::Float => "小数",
+            CastTarget::Categorical => "カテゴリ",
+            CastTarget::Date => "日付",
+        }
+    }
+}
+
+/// Cells that had a value before the conversion and are missing after it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CastReport {
+    pub failed: usize,
+    /// Up to ten distinct original values that could not be converted
+    pub examples: Vec<String>,
+}
+
+fn column<'a>(df: &'a DataFrame, name: &str) -> Result<&'a Series> {
+    df.column(name).map_err(|_| anyhow!("Column '{}' not found", name))
+}
+
+/// String form of each cell (dates as `YYYY-MM-DD`), missing cells as `None`.
+fn text_values(s: &Series) -> Result<Vec<Option<String>>> {
+    let text = s.cast(&DataType::String)?;
+    Ok(text.str()?.into_iter().map(|v| v.map(str::to_string)).collect())
+}
+
+fn parse_with<T, F>(s: &Series, parse: F) -> Result<Vec<Option<T>>>
+where
+    F: Fn(&str) -> Option<T>,
+{
+    Ok(text_values(s)?.into_iter().map(|v| v.and_then(|v| parse(v.trim()))).collect())
+}
+
+fn converted(s: &Series, target: CastTarget) -> Result<Series> {
+    let name = s.name();
+    let out = match target {
+        CastTarget::Text => s.cast(&DataType::String)?,
+        CastTarget::Categorical => s.cast(&DataType::String)?.cast(&DataType::Categorical(None, Default::default()))?,
+        // Floats with a fractional part are reported rather than truncated
+        CastTarget::Integer if s.dtype().is_float() => {
+            let values = s.cast(&DataType::Float64)?;
+            let values: Vec<Option<i64>> = values
+                .f64()?
+                .into_iter()
+                .map(|v| v.filter(|x| x.fract() == 0.0 && x.abs() < i64::MAX as f64).map(|x| x as i64))
+                .collect();
+            Series::new(name, values)
+        }
+        CastTarget::Integer if s.dtype().is_numeric() || s.dtype() == &DataType::Boolean => s.cast(&DataType::Int64)?,
+        CastTarget::Integer => Series::new(name, parse_with(s, |v| v.parse::<i64>().ok())?),
+        CastTarget::Float if s.dtype().is_numeric() || s.dtype() == &DataType::Boolean => s.cast(&DataType::Float64)?,
+        CastTarget::Float => Series::new(name, parse_with(s, |v| v.parse::<f64>().ok().filter(|x| x.is_finite()))?),
+        CastTarget::Date => match s.dtype() {
+            DataType::Date => s.clone(),
+            DataType::Datetime(..) => s.cast(&DataType::Date)?,
+            dtype if dtype.is_numeric() => return Err(anyhow!("Numbers cannot be converted to dates: '{}' is {}", name, dtype)),
+            _ => Series::new(name, parse_with(s, |v| parse_datetime(v).map(|d| d.date()))?),
+        },
+    };
+    Ok(out)
+}
+
+/// Converts a column in place. Cells that cannot be converted become missing
+/// and are counted in the report.
+pub fn cast_column(df: &DataFrame, name: &str, target: CastTarget) -> Result<(DataFrame, CastReport)> {
+    let s = column(df, name)?;
+    let out = converted(s, target)?;
+
+    let lost = s.is_not_null() & out.is_null();
+    let mut report = CastReport { failed: lost.sum().unwrap_or(0) as usize, examples: Vec::new() };
+    for (value, lost) in text_values(s)?.into_iter().zip(&lost) {
+        if report.examples.len() == REPORT_EXAMPLES {
+            break;
+        }
+        if let (Some(value), Some(true)) = (value, lost) {
+            if !report.examples.contains(&value) {
+                report.examples.push(value);
+            }
+        }
+    }
+
+    let mut df = df.clone();
+    df.replace(name, out)?;
+    Ok((df, report))
+}
+
+/// Distinct values with their counts: levels in their set order for
+/// categorical columns with a fixed order, otherwise in order of appearance.
+pub fn value_counts(df: &DataFrame, name: &str) -> Result<Vec<(String, usize)>> {
+    let s = column(df, name)?;
+    let mut order: Vec<String> = level_order(s).unwrap_or_default();
+    let mut counts: HashMap<String, usize> = order.iter().map(|l| (l.clone(), 0)).collect();
+    for value in text_values(s)?.into_iter().flatten() {
+        let count = counts.entry(value.clone()).or_insert_with(|| {
+            order.push(value);
+            0
+        });
+        *count += 1;
+    }
+    Ok(order.into_iter().map(|v| {
+        let n = counts[&v];
+        (v, n)
+    }).collect())
+}
+
+/// Levels of a column whose category order was set with [`set_level_order`].
+pub fn level_order(s: &Series) -> Option<Vec<String>> {
+    match s.dtype() {
+        DataType::Enum(Some(rev_map), _) => {
+            Some(rev_map.get_categories().values_iter().map(str::to_string).collect())
+        }
+        _ => None,
+    }
+}
+
+/// The column as a categorical one with the given levels, in that order.
+fn with_levels(s: &Series, levels: &[String]) -> Result<Series> {
+    let dtype = create_enum_data_type(Utf8ViewArray::from_slice_values(levels));
+    Ok(s.cast(&DataType::String)?.strict_cast(&dtype)?)
+}
+
+/// Replaces values by the mapping (`"1"` → `"男性"`, matched on the string
+/// form of each cell) and stores the result in `into`, which may be the
+/// source column itself. Values not in the mapping are kept. A column with a
+/// set level order keeps it: unmapped levels stay where they were and new
+/// labels follow them.
+pub fn recode(df: &DataFrame, name: &str, mapping: &[(String, String)], into: &str) -> Result<DataFrame> {
+    let into = into.trim();
+    if into.is_empty() {
+        return Err(anyhow!("Output column name is empty"));
+    }
+    let s = column(df, name)?;
+    let table: HashMap<&str, &str> = mapping.iter().map(|(from, to)| (from.as_str(), to.as_str())).collect();
+    let values: Vec<Option<String>> = text_values(s)?
+        .into_iter()
+        .map(|v| v.map(|v| table.get(v.as_str()).map_or(v.clone(), |to| to.to_string())))
+        .collect();
+    let mut out = Series::new(into, values);
+    if let Some(old) = level_order(s) {
+        let mut levels: Vec<String> = old.iter().filter(|l| table.get(l.as_str()).is_none_or(|to| to == l)).cloned().collect();
+        for to in old.iter().filter_map(|l| table.get(l.as_str())) {
+            if !levels.iter().any(|l| l == to) {
+                levels.push(to.to_string());
+            }
+        }
+        out = with_levels(&out, &levels)?;
+    }
+    let mut df = df.clone();
+    df.with_column(out)?;
+    Ok(df)
+}
+
+/// Replaces categories with fewer than `min_count` rows by `other_label`,
+/// which comes last in a set level order. Returns the new frame and the
+/// categories that were merged.
+pub fn merge_rare(df: &DataFrame, name: &str, min_count: usize, other_label: &str) -> Result<(DataFrame, Vec<String>)> {
+    let rare: Vec<String> = value_counts(df, name)?
+        .into_iter()
+        .filter(|(value, n)| *n < min_count && value != other_label)
+        .map(|(value, _)| value)
+        .collect();
+    let mapping: Vec<(String, String)> = rare.iter().map(|v| (v.clone(), other_label.to_string())).collect();
+    Ok((recode(df, name, &mapping, name)?, rare))
+}
+
+/// Turns the column into a categorical one whose levels come in the given
+/// order; analyses list groups in this order. Every value must be a level.
+pub fn set_level_order(df: &DataFrame, name: &str, levels: &[String]) -> Result<DataFrame> {
+    let s = column(df, name)?;
+    if levels.iter().any(|l| l.trim().is_empty()) {
+        return Err(anyhow!("The level order contains an empty level"));
+    }
+    if let Some(level) = levels.iter().enumerate().find_map(|(i, l)| levels[..i].contains(l).then_some(l)) {
+        return Err(anyhow!("Level '{}' appears more than once in the level order", level));
+    }
+    let missing: Vec<String> = value_counts(df, name)?
+        .into_iter()
+        .map(|(value, _)| value)
+        .filter(|value| !levels.contains(value))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!("Values missing from the level order: {}", missing.join(", ")));
+    }
+    let ordered = with_levels(s, levels)?;
+    let mut df = df.clone();
+    df.replace(name, ordered)?;
+    Ok(df)
+}
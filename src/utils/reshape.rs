@@ -0,0 +1,125 @@
+//! Wide ↔ long reshaping for repeated measures: melt (one column per time
+//! point → one row per time point) and pivot (the reverse).
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use super::recode::{set_level_order, value_counts};
+
+/// Wide → long: the `value_columns` are stacked into `value_name`, with the
+/// column each value came from in `variable_name`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MeltOptions {
+    /// Columns repeated on every long row (e.g. the participant id)
+    pub id_columns: Vec<String>,
+    pub value_columns: Vec<String>,
+    pub variable_name: String,
+    pub value_name: String,
+}
+
+/// Long → wide: one row per distinct combination of `id_columns`, one
+/// column per distinct value of `names_from`, filled from `values_from`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PivotOptions {
+    pub id_columns: Vec<String>,
+    pub names_from: String,
+    pub values_from: String,
+}
+
+fn require(df: &DataFrame, name: &str) -> Result<()> {
+    df.column(name).map(|_| ()).map_err(|_| anyhow!("Column '{}' not found", name))
+}
+
+/// Rejects empty names and names used twice in the output.
+fn check_output_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    let mut seen = Vec::new();
+    for name in names {
+        if name.trim().is_empty() {
+            return Err(anyhow!("Output column name is empty"));
+        }
+        if seen.contains(&name) {
+            return Err(anyhow!("Column '{}' would appear twice in the result", name));
+        }
+        seen.push(name);
+    }
+    Ok(())
+}
+
+/// Stacks the value columns. Rows stay grouped by the original row (all time
+/// points of the first participant, then the second, ...), and the variable
+/// column is categorical with the value columns' order as its level order.
+pub fn melt(df: &DataFrame, options: &MeltOptions) -> Result<DataFrame> {
+    if options.value_columns.is_empty() {
+        return Err(anyhow!("Select at least one value column"));
+    }
+    for name in options.id_columns.iter().chain(&options.value_columns) {
+        require(df, name)?;
+    }
+    if let Some(both) = options.value_columns.iter().find(|c| options.id_columns.contains(c)) {
+        return Err(anyhow!("'{}' is selected both as an id and as a value column", both));
+    }
+    let (variable, value) = (options.variable_name.trim(), options.value_name.trim());
+    check_output_names(options.id_columns.iter().map(String::as_str).chain([variable, value]))?;
+
+    let stacked = df
+        .unpivot2(UnpivotArgs {
+            on: options.value_columns.iter().map(|c| c.as_str().into()).collect(),
+            index: options.id_columns.iter().map(|c| c.as_str().into()).collect(),
+            variable_name: Some(variable.into()),
+            value_name: Some(value.into()),
+            streamable: false,
+        })
+        .map_err(|e| anyhow!("Melt error: {}", e))?;
+    // Polars stacks column by column; reorder to row by row
+    let (rows, columns) = (df.height(), options.value_columns.len());
+    let order = IdxCa::new("idx", (0..rows).flat_map(|r| (0..columns).map(move |c| (c * rows + r) as IdxSize)).collect::<Vec<_>>());
+    set_level_order(&stacked.take(&order)?, variable, &options.value_columns)
+}
+
+/// Spreads `values_from` into one column per value of `names_from` (in level
+/// order for ordered categorical columns, otherwise in order of appearance).
+/// Id combinations without a row for some name get a missing value there.
+pub fn pivot(df: &DataFrame, options: &PivotOptions) -> Result<DataFrame> {
+    if options.id_columns.is_empty() {
+        return Err(anyhow!("Select at least one id column"));
+    }
+    for name in options.id_columns.iter().chain([&options.names_from, &options.values_from]) {
+        require(df, name)?;
+    }
+    check_output_names(options.id_columns.iter().chain([&options.names_from, &options.values_from]).map(String::as_str))?;
+    let missing_names = df.column(&options.names_from)?.null_count();
+    if missing_names > 0 {
+        return Err(anyhow!("'{}' is missing in {} row(s)", options.names_from, missing_names));
+    }
+
+    let ids: Vec<Expr> = options.id_columns.iter().map(|c| col(c)).collect();
+    let mut keys = ids.clone();
+    keys.push(col(&options.names_from));
+    let repeated = df
+        .clone()
+        .lazy()
+        .group_by_stable(keys)
+        .agg([len().alias("rows")])
+        .filter(col("rows").gt(lit(1)))
+        .collect()?;
+    if repeated.height() > 0 {
+        return Err(anyhow!(
+            "{} id/'{}' combination(s) occur in more than one row, so the wide table would need to drop values",
+            repeated.height(),
+            options.names_from
+        ));
+    }
+
+    let names: Vec<String> = value_counts(df, &options.names_from)?.into_iter().map(|(name, _)| name).collect();
+    check_output_names(options.id_columns.iter().map(String::as_str).chain(names.iter().map(String::as_str)))?;
+    let source = df.clone().lazy().with_column(col(&options.names_from).cast(DataType::String));
+    let mut wide = df.clone().lazy().select(ids.clone()).unique_stable(None, UniqueKeepStrategy::First);
+    for name in &names {
+        let mut selection = ids.clone();
+        selection.push(col(&options.values_from).alias(name));
+        let part = source.clone().filter(col(&options.names_from).eq(lit(name.as_str()))).select(selection);
+        let args = JoinArgs { join_nulls: true, ..JoinArgs::new(JoinType::Left) };
+        wide = wide.join(part, ids.clone(), ids.clone(), args);
+    }
+    wide.collect().map_err(|e| anyhow!("Pivot error: {}", e))
+}
@@ -0,0 +1,53 @@
+//! Demo datasets from `datasets/`, embedded in the binary so they can be
+//! loaded without downloading and uploading them again.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+use super::file_loader::read_dataframe;
+
+/// One bundled dataset.
+#[derive(Clone, Copy, Debug)]
+pub struct SampleDataset {
+    pub id: &'static str,
+    pub file_name: &'static str,
+    pub title: &'static str,
+    bytes: &'static [u8],
+}
+
+macro_rules! sample {
+    ($id:literal, $title:literal) => {
+        SampleDataset {
+            id: $id,
+            file_name: concat!($id, ".xlsx"),
+            title: $title,
+            bytes: include_bytes!(concat!("../../datasets/", $id, ".xlsx")),
+        }
+    };
+}
+
+pub const SAMPLES: &[SampleDataset] = &[
+    sample!("eda_demo", "生活時間の調査 (探索的データ分析)"),
+    sample!("correlation_demo", "勉強時間・スマホ時間・睡眠時間 (相関分析)"),
+    sample!("chi_square_demo", "血液型と好きな食べ物 (カイ二乗検定)"),
+    sample!("ttest_demo", "クラス別のテスト得点 (対応なし t検定)"),
+    sample!("ttest_rel_demo", "前期・後期のテスト得点 (対応あり t検定)"),
+    sample!("anova_demo", "3クラスのテスト得点 (一要因分散分析)"),
+    sample!("anova_demo_rel", "学期ごとのテスト得点 (反復測定)"),
+    sample!("2way_anova_demo", "部活動・学年別の学習時間 (二要因分散分析)"),
+    sample!("2way_anova_demo_mix", "前後テストの得点 (混合計画)"),
+    sample!("multiple_regression_demo", "学校満足度の要因 (重回帰分析)"),
+    sample!("factor_analysis_demo", "ICT 機器への意識調査 (主成分分析・因子分析)"),
+    sample!("textmining_demo", "今年の抱負 (テキストマイニング)"),
+];
+
+/// Looks a sample up by its id (the file name without extension).
+pub fn find(id: &str) -> Option<&'static SampleDataset> {
+    SAMPLES.iter().find(|s| s.id == id)
+}
+
+impl SampleDataset {
+    pub fn load(&self) -> Result<DataFrame> {
+        read_dataframe(self.file_name, self.bytes).map_err(|e| anyhow!("Sample {}: {}", self.id, e))
+    }
+}
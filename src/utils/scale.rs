@@ -0,0 +1,101 @@
+//! Questionnaire scale scores: reverse-keyed items and sum / mean scores
+//! with a limit on how many items a respondent may have skipped.
+
+use anyhow::{anyhow, Result};
+use polars::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ScoreMethod {
+    /// Sum of the items; with skipped items, the mean of the answered ones
+    /// times the number of items (prorated), so scores stay comparable
+    Sum,
+    Mean,
+}
+
+impl ScoreMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            ScoreMethod::Sum => "合計",
+            ScoreMethod::Mean => "平均",
+        }
+    }
+}
+
+/// How a scale score is built from item columns.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScaleSpec {
+    /// Name of the new column
+    pub name: String,
+    pub items: Vec<String>,
+    /// Items scored as `min + max - answer`
+    pub reversed: Vec<String>,
+    /// Lowest and highest answer of the response scale (e.g. 1 and 5)
+    pub min: f64,
+    pub max: f64,
+    pub method: ScoreMethod,
+    /// Respondents with more skipped items get a missing score
+    pub max_missing: usize,
+}
+
+/// Rows that got a score and rows left missing by the skipped-item rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScaleReport {
+    pub scored: usize,
+    pub too_many_missing: usize,
+}
+
+fn item_values(df: &DataFrame, spec: &ScaleSpec, name: &str) -> Result<Vec<Option<f64>>> {
+    let s = df.column(name).map_err(|_| anyhow!("Column '{}' not found", name))?;
+    if !s.dtype().is_numeric() {
+        return Err(anyhow!("Item '{}' is not numeric ({}); convert it first", name, s.dtype()));
+    }
+    let values: Vec<Option<f64>> = s.cast(&DataType::Float64)?.f64()?.into_iter().collect();
+    if let Some(out) = values.iter().flatten().find(|v| **v < spec.min || **v > spec.max) {
+        return Err(anyhow!("Item '{}' has the value {} outside the scale range {} to {}", name, out, spec.min, spec.max));
+    }
+    let reverse = spec.reversed.iter().any(|r| r == name);
+    Ok(values.into_iter().map(|v| v.map(|x| if reverse { spec.min + spec.max - x } else { x })).collect())
+}
+
+/// Appends the scale score as a new column.
+pub fn score_scale(df: &DataFrame, spec: &ScaleSpec) -> Result<(DataFrame, ScaleReport)> {
+    let name = spec.name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Scale name is empty"));
+    }
+    if df.get_column_names().contains(&name) {
+        return Err(anyhow!("Column '{}' already exists", name));
+    }
+    if spec.items.is_empty() {
+        return Err(anyhow!("Select at least one item"));
+    }
+    if spec.min >= spec.max {
+        return Err(anyhow!("Scale minimum must be below the maximum"));
+    }
+    if let Some(r) = spec.reversed.iter().find(|r| !spec.items.contains(r)) {
+        return Err(anyhow!("Reverse-keyed item '{}' is not one of the items", r));
+    }
+
+    let columns = spec.items.iter().map(|item| item_values(df, spec, item)).collect::<Result<Vec<_>>>()?;
+    let k = columns.len();
+    let mut report = ScaleReport::default();
+    let scores: Vec<Option<f64>> = (0..df.height())
+        .map(|row| {
+            let answered: Vec<f64> = columns.iter().filter_map(|c| c[row]).collect();
+            if answered.is_empty() || k - answered.len() > spec.max_missing {
+                report.too_many_missing += 1;
+                return None;
+            }
+            report.scored += 1;
+            let mean = answered.iter().sum::<f64>() / answered.len() as f64;
+            Some(match spec.method {
+                ScoreMethod::Mean => mean,
+                ScoreMethod::Sum => mean * k as f64,
+            })
+        })
+        .collect();
+
+    let mut out = df.clone();
+    out.with_column(Series::new(name, scores))?;
+    Ok((out, report))
+}
@@ -0,0 +1,284 @@
+//! Column type inference for imported cells.
+//!
+//! Each column gets one dtype chosen from its cells; cells that do not fit
+//! (e.g. "N/A" typed into a score column) become null and are listed in the
+//! [`ImportReport`] instead of failing the whole import.
+
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use polars::prelude::*;
+use serde::Serialize;
+
+/// A cell as read from a spreadsheet or text, before the column type is known.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RawCell {
+    Empty,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    DateTime(NaiveDateTime),
+}
+
+impl RawCell {
+    /// A cell typed as text (pasted or delimited): blank is empty, `TRUE`/`FALSE`
+    /// are booleans, whole numbers are integers; anything else is left for inference.
+    pub fn from_text(s: &str) -> RawCell {
+        let s = s.trim();
+        if s.is_empty() {
+            return RawCell::Empty;
+        }
+        match s {
+            "TRUE" | "true" | "True" => return RawCell::Bool(true),
+            "FALSE" | "false" | "False" => return RawCell::Bool(false),
+            _ => {}
+        }
+        match s.parse::<i64>() {
+            Ok(v) => RawCell::Int(v),
+            Err(_) => RawCell::Text(s.to_string()),
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            RawCell::Empty => String::new(),
+            RawCell::Bool(v) => v.to_string(),
+            RawCell::Int(v) => v.to_string(),
+            RawCell::Float(v) => v.to_string(),
+            RawCell::Text(s) => s.clone(),
+            RawCell::DateTime(dt) if dt.time() == NaiveTime::MIN => dt.date().to_string(),
+            RawCell::DateTime(dt) => dt.to_string(),
+        }
+    }
+
+    /// Numeric value, accepting numbers typed as text (including full-width digits).
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            RawCell::Int(v) => Some(*v as f64),
+            RawCell::Float(v) => Some(*v),
+            RawCell::Text(s) => parse_number(s),
+            _ => None,
+        }
+    }
+
+    fn as_datetime(&self) -> Option<NaiveDateTime> {
+        match self {
+            RawCell::DateTime(dt) => Some(*dt),
+            RawCell::Text(s) => parse_datetime(s),
+            _ => None,
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Option<f64> {
+    let normalized: String = s
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '．' => '.',
+            '－' | '−' => '-',
+            '＋' => '+',
+            _ => c,
+        })
+        .collect();
+    normalized.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// ISO 8601 date or date-time (`2024-04-01`, `2024-04-01T08:30:00`), also with `/`.
+pub fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y/%m/%d %H:%M:%S", "%Y/%m/%d %H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%Y/%m/%d"]
+                .iter()
+                .find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })
+}
+
+/// A cell that did not fit its column's type and was set to null.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CoercedCell {
+    /// 1-based row number in the source (spreadsheet row or text line)
+    pub row: usize,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ColumnReport {
+    pub column: String,
+    pub dtype: String,
+    pub coerced: Vec<CoercedCell>,
+}
+
+/// What the type inference decided, per column.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    pub columns: Vec<ColumnReport>,
+}
+
+impl ImportReport {
+    pub fn coerced_total(&self) -> usize {
+        self.columns.iter().map(|c| c.coerced.len()).sum()
+    }
+
+    /// Columns in which at least one cell was set to null.
+    pub fn coerced_columns(&self) -> impl Iterator<Item = &ColumnReport> {
+        self.columns.iter().filter(|c| !c.coerced.is_empty())
+    }
+
+    /// One line per affected column, e.g. `英語 (f64): 2 cells set to null (row 5 "N/A", row 9 "-")`.
+    pub fn summary_lines(&self, max_examples: usize) -> Vec<String> {
+        self.coerced_columns()
+            .map(|c| {
+                let examples: Vec<String> = c
+                    .coerced
+                    .iter()
+                    .take(max_examples)
+                    .map(|cell| format!("row {} \"{}\"", cell.row, cell.value))
+                    .collect();
+                let more = if c.coerced.len() > max_examples { ", ..." } else { "" };
+                format!(
+                    "{} ({}): {} cells set to null ({}{})",
+                    c.column,
+                    c.dtype,
+                    c.coerced.len(),
+                    examples.join(", "),
+                    more
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Boolean,
+    Int,
+    Float,
+    Date,
+    Datetime,
+    Text,
+}
+
+/// Picks the column type from the non-empty cells; an all-empty column is f64.
+///
+/// - Boolean: every cell is a boolean.
+/// - Date, or datetime when any cell has a time: at least half of the cells
+///   are dates, and no fewer than the numeric ones.
+/// - i64 when every number is an integer cell, f64 otherwise: more than half
+///   of the cells are numbers, so a label column with a few numeric codes
+///   stays text.
+/// - Text otherwise.
+///
+/// In a date or numeric column, cells of another kind are set to null and
+/// reported by `infer_series`.
+fn infer_kind(cells: &[RawCell]) -> Kind {
+    let filled: Vec<&RawCell> = cells.iter().filter(|c| **c != RawCell::Empty).collect();
+    if filled.is_empty() {
+        return Kind::Float;
+    }
+    if filled.iter().all(|c| matches!(c, RawCell::Bool(_))) {
+        return Kind::Boolean;
+    }
+    let n = filled.len();
+    let dates: Vec<NaiveDateTime> = filled.iter().filter_map(|c| c.as_datetime()).collect();
+    if dates.len() * 2 >= n && dates.len() >= filled.iter().filter(|c| c.as_number().is_some()).count() {
+        return if dates.iter().all(|dt| dt.time() == NaiveTime::MIN) { Kind::Date } else { Kind::Datetime };
+    }
+    let numbers: Vec<&&RawCell> = filled.iter().filter(|c| c.as_number().is_some()).collect();
+    if numbers.len() * 2 > n {
+        return if numbers.iter().all(|c| matches!(c, RawCell::Int(_))) { Kind::Int } else { Kind::Float };
+    }
+    Kind::Text
+}
+
+/// Builds one typed Series. `first_row` is the source row number of `cells[0]`.
+pub fn infer_series(name: &str, cells: &[RawCell], first_row: usize) -> (Series, ColumnReport) {
+    let kind = infer_kind(cells);
+    let mut coerced = Vec::new();
+    let mut keep = |i: usize, cell: &RawCell, ok: bool| {
+        if !ok && *cell != RawCell::Empty {
+            coerced.push(CoercedCell { row: first_row + i, value: cell.display() });
+        }
+    };
+
+    let series = match kind {
+        Kind::Boolean => {
+            let values: Vec<Option<bool>> = cells.iter().map(|c| match c {
+                RawCell::Bool(v) => Some(*v),
+                _ => None,
+            }).collect();
+            Series::new(name, values)
+        }
+        Kind::Int => {
+            let values: Vec<Option<i64>> = cells.iter().enumerate().map(|(i, c)| {
+                let v = match c {
+                    RawCell::Int(v) => Some(*v),
+                    _ => None,
+                };
+                keep(i, c, v.is_some());
+                v
+            }).collect();
+            Series::new(name, values)
+        }
+        Kind::Float => {
+            let values: Vec<Option<f64>> = cells.iter().enumerate().map(|(i, c)| {
+                let v = c.as_number();
+                keep(i, c, v.is_some());
+                v
+            }).collect();
+            Series::new(name, values)
+        }
+        Kind::Date | Kind::Datetime => {
+            let values: Vec<Option<NaiveDateTime>> = cells.iter().enumerate().map(|(i, c)| {
+                let v = c.as_datetime();
+                keep(i, c, v.is_some());
+                v
+            }).collect();
+            datetime_series(name, &values, kind == Kind::Date)
+        }
+        Kind::Text => {
+            let values: Vec<Option<String>> = cells.iter().map(|c| match c {
+                RawCell::Empty => None,
+                other => Some(other.display()),
+            }).collect();
+            Series::new(name, values)
+        }
+    };
+
+    let report = ColumnReport { column: name.to_string(), dtype: series.dtype().to_string(), coerced };
+    (series, report)
+}
+
+fn datetime_series(name: &str, values: &[Option<NaiveDateTime>], date_only: bool) -> Series {
+    if date_only {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+        let days: Vec<Option<i32>> = values.iter().map(|v| v.map(|dt| (dt.date() - epoch).num_days() as i32)).collect();
+        Series::new(name, days).cast(&DataType::Date).expect("i32 casts to Date")
+    } else {
+        let millis: Vec<Option<i64>> = values.iter().map(|v| v.map(|dt| dt.and_utc().timestamp_millis())).collect();
+        Series::new(name, millis)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .expect("i64 casts to Datetime")
+    }
+}
+
+/// Builds a DataFrame from columns of raw cells, inferring each column's type.
+pub fn build_dataframe(headers: Vec<String>, columns: Vec<Vec<RawCell>>, first_row: usize) -> Result<(DataFrame, ImportReport)> {
+    let mut report = ImportReport::default();
+    let series: Vec<Series> = headers
+        .iter()
+        .zip(&columns)
+        .map(|(name, cells)| {
+            let (s, col_report) = infer_series(name, cells, first_row);
+            report.columns.push(col_report);
+            s
+        })
+        .collect();
+    let df = DataFrame::new(series).map_err(|e| anyhow!("Failed to create DataFrame: {}", e))?;
+    Ok((df, report))
+}
@@ -0,0 +1,144 @@
+//! Minimal `.xlsx` writer: one worksheet, inline strings, numbers, booleans
+//! and dates (as serial numbers with Excel's built-in date formats).
+
+use anyhow::{anyhow, Result};
+use polars::prelude::{AnyValue, DataFrame, TimeUnit};
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Days between Excel's 1900 epoch (with its leap-year bug) and 1970-01-01.
+const UNIX_EPOCH_SERIAL: f64 = 25569.0;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+// Style 1: built-in date format 14, style 2: built-in date-time format 22
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="22" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></cellXfs></styleSheet>"#;
+
+/// Column letters for a 0-based index (0 → `A`, 26 → `AA`).
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).expect("ASCII letters")
+}
+
+fn escape(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .fold(String::with_capacity(s.len()), |mut out, c| {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                _ => out.push(c),
+            }
+            out
+        })
+}
+
+fn text_cell(cell_ref: &str, s: &str) -> String {
+    format!(r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#, cell_ref, escape(s))
+}
+
+fn number_cell(cell_ref: &str, v: f64, style: u8) -> String {
+    if !v.is_finite() {
+        return String::new();
+    }
+    match style {
+        0 => format!(r#"<c r="{}"><v>{}</v></c>"#, cell_ref, v),
+        _ => format!(r#"<c r="{}" s="{}"><v>{}</v></c>"#, cell_ref, style, v),
+    }
+}
+
+fn value_cell(cell_ref: &str, value: &AnyValue) -> String {
+    match value {
+        AnyValue::Null => String::new(),
+        AnyValue::Boolean(b) => format!(r#"<c r="{}" t="b"><v>{}</v></c>"#, cell_ref, u8::from(*b)),
+        AnyValue::String(s) => text_cell(cell_ref, s),
+        AnyValue::StringOwned(s) => text_cell(cell_ref, s),
+        AnyValue::Date(days) => number_cell(cell_ref, *days as f64 + UNIX_EPOCH_SERIAL, 1),
+        AnyValue::Datetime(v, unit, _) => {
+            let per_day = match unit {
+                TimeUnit::Nanoseconds => 86_400e9,
+                TimeUnit::Microseconds => 86_400e6,
+                TimeUnit::Milliseconds => 86_400e3,
+            };
+            number_cell(cell_ref, *v as f64 / per_day + UNIX_EPOCH_SERIAL, 2)
+        }
+        v if v.is_numeric() => v.extract::<f64>().map_or(String::new(), |x| number_cell(cell_ref, x, 0)),
+        other => text_cell(cell_ref, &other.to_string()),
+    }
+}
+
+fn sheet_xml(df: &DataFrame) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    let letters: Vec<String> = (0..df.width()).map(column_name).collect();
+
+    xml.push_str(r#"<row r="1">"#);
+    for (letter, name) in letters.iter().zip(df.get_column_names()) {
+        xml.push_str(&text_cell(&format!("{}1", letter), name));
+    }
+    xml.push_str("</row>");
+
+    for i in 0..df.height() {
+        let row = i + 2;
+        xml.push_str(&format!(r#"<row r="{}">"#, row));
+        for (letter, series) in letters.iter().zip(df.get_columns()) {
+            if let Ok(value) = series.get(i) {
+                xml.push_str(&value_cell(&format!("{}{}", letter, row), &value));
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// Writes the DataFrame as a single-sheet workbook with the column names in row 1.
+pub fn write_xlsx(df: &DataFrame, sheet_name: &str) -> Result<Vec<u8>> {
+    // Excel limits sheet names to 31 characters and forbids []:*?/\
+    let sheet_name: String = sheet_name.chars().filter(|c| !"[]:*?/\\".contains(*c)).take(31).collect();
+    let sheet_name = if sheet_name.is_empty() { "Sheet1".to_string() } else { sheet_name };
+    let workbook = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        escape(&sheet_name)
+    );
+
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", ROOT_RELS.to_string()),
+        ("xl/workbook.xml", workbook),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.to_string()),
+        ("xl/styles.xml", STYLES.to_string()),
+        ("xl/worksheets/sheet1.xml", sheet_xml(df)),
+    ];
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in parts {
+        zip.start_file(name, options).map_err(|e| anyhow!("XLSX write error: {}", e))?;
+        zip.write_all(content.as_bytes()).map_err(|e| anyhow!("XLSX write error: {}", e))?;
+    }
+    let cursor = zip.finish().map_err(|e| anyhow!("XLSX write error: {}", e))?;
+    Ok(cursor.into_inner())
+}
@@ -0,0 +1,65 @@
+//! The dataset registry behind the sidebar switcher.
+
+use easy_stat_r::datasets::DatasetRegistry;
+use easy_stat_r::utils::recipe::Step;
+use polars::prelude::*;
+
+fn frame(n: i64) -> DataFrame {
+    DataFrame::new(vec![Series::new("x", (0..n).collect::<Vec<_>>())]).unwrap()
+}
+
+#[test]
+fn registry_keeps_every_dataset_and_tracks_the_active_one() {
+    let mut registry = DatasetRegistry::default();
+    assert!(registry.active().is_none());
+
+    assert_eq!(registry.add("pre.csv", frame(3)), "pre.csv");
+    assert_eq!(registry.add("post.csv", frame(4)), "post.csv");
+    // A second file with the same name does not replace the first
+    assert_eq!(registry.add("pre.csv", frame(5)), "pre.csv (2)");
+    assert_eq!(registry.names(), vec!["pre.csv", "post.csv", "pre.csv (2)"]);
+    assert_eq!(registry.active().unwrap().name, "pre.csv (2)");
+
+    // A page can pick another dataset; unknown names fall back to the active one
+    assert_eq!(registry.resolve(Some("pre.csv")).unwrap().df.height(), 3);
+    assert_eq!(registry.resolve(Some("gone.csv")).unwrap().name, "pre.csv (2)");
+
+    registry.set_active("post.csv");
+    assert!(registry.replace("pre.csv", frame(10)));
+    assert_eq!(registry.active().unwrap().name, "post.csv");
+    assert_eq!(registry.get("pre.csv").unwrap().df.height(), 10);
+
+    registry.set("ttest_demo.xlsx", frame(1));
+    registry.set("ttest_demo.xlsx", frame(2));
+    assert_eq!(registry.names().len(), 4);
+    assert_eq!(registry.active().unwrap().df.height(), 2);
+
+    registry.remove("ttest_demo.xlsx");
+    assert_eq!(registry.active().unwrap().name, "pre.csv (2)");
+}
+
+#[test]
+fn cleaning_steps_can_be_undone_and_redone() {
+    let mut registry = DatasetRegistry::default();
+    registry.add("data.csv", frame(5));
+    let step = |expression: &str| Step::Compute { name: "y".into(), expression: expression.into() };
+    let width = |r: &DatasetRegistry| r.get("data.csv").unwrap().df.width();
+
+    assert!(!registry.undo("data.csv"));
+    assert!(registry.apply("data.csv", step("x + 1"), frame(5).hstack(&[Series::new("y", [1i64; 5])]).unwrap()));
+    assert_eq!(width(&registry), 2);
+    assert!(registry.undo("data.csv"));
+    assert_eq!(width(&registry), 1);
+    assert!(registry.get("data.csv").unwrap().history.can_redo());
+    assert!(registry.redo("data.csv"));
+    assert_eq!(width(&registry), 2);
+    assert_eq!(registry.get("data.csv").unwrap().history.steps(), vec![step("x + 1")]);
+
+    // A reshaped copy carries the source's steps; reloading a file clears them
+    let derived = registry.add_derived("data.csv (縦長)", frame(10), "data.csv", step("x"));
+    assert_eq!(registry.get(&derived).unwrap().history.steps().len(), 2);
+    assert!(registry.undo(&derived));
+    assert_eq!(registry.get(&derived).unwrap().df.width(), 2);
+    registry.set("data.csv", frame(3));
+    assert!(!registry.get("data.csv").unwrap().history.can_undo());
+}
@@ -0,0 +1,161 @@
+#!/usr/bin/env python3
+"""Writes the small spreadsheets used by tests/import.rs.
+
+Only the standard library is needed: an .ods file is a zip archive with an
+uncompressed `mimetype` entry followed by content.xml and a manifest, and a
+minimal .xlsx is a handful of SpreadsheetML parts.
+
+    python3 tests/fixtures/generate_import_fixtures.py
+"""
+
+import datetime
+import os
+import zipfile
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+MIMETYPE = "application/vnd.oasis.opendocument.spreadsheet"
+
+MANIFEST = """<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2"><manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"""
+
+
+def entry(name, compress_type=zipfile.ZIP_DEFLATED):
+    """Zip entry with a fixed timestamp so regenerated files are byte-identical."""
+    info = zipfile.ZipInfo(name, date_time=(2024, 1, 1, 0, 0, 0))
+    info.compress_type = compress_type
+    return info
+
+
+def cell(v):
+    if v is None:
+        return "<table:table-cell/>"
+    if isinstance(v, bool):
+        return f'<table:table-cell office:value-type="boolean" office:boolean-value="{str(v).lower()}"><text:p>{v}</text:p></table:table-cell>'
+    if isinstance(v, (datetime.date, datetime.datetime)):
+        return f'<table:table-cell office:value-type="date" office:date-value="{v.isoformat()}"><text:p>{v}</text:p></table:table-cell>'
+    if isinstance(v, (int, float)):
+        return f'<table:table-cell office:value-type="float" office:value="{v}"><text:p>{v}</text:p></table:table-cell>'
+    return f'<table:table-cell office:value-type="string"><text:p>{v}</text:p></table:table-cell>'
+
+
+def table(name, rows):
+    body = "".join("<table:table-row>" + "".join(cell(v) for v in r) + "</table:table-row>" for r in rows)
+    return f'<table:table table:name="{name}">{body}</table:table>'
+
+
+def write_ods(file_name, sheets):
+    tables = "".join(table(name, rows) for name, rows in sheets)
+    content = (
+        '<?xml version="1.0" encoding="UTF-8"?>\n'
+        '<office:document-content'
+        ' xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"'
+        ' xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"'
+        ' xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"'
+        ' office:version="1.2"><office:body><office:spreadsheet>'
+        f"{tables}</office:spreadsheet></office:body></office:document-content>"
+    )
+    with zipfile.ZipFile(os.path.join(HERE, file_name), "w") as z:
+        z.writestr(entry("mimetype", zipfile.ZIP_STORED), MIMETYPE)
+        z.writestr(entry("content.xml"), content)
+        z.writestr(entry("META-INF/manifest.xml"), MANIFEST)
+
+
+XLSX_PARTS = {
+    "[Content_Types].xml": """<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>""",
+    "_rels/.rels": """<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>""",
+    "xl/workbook.xml": """<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>""",
+    "xl/_rels/workbook.xml.rels": """<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>""",
+    # Style 1 = built-in date format 14, style 2 = built-in date-time format 22
+    "xl/styles.xml": """<?xml version="1.0" encoding="UTF-8"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="1"><font/></fonts><fills count="1"><fill/></fills><borders count="1"><border/></borders><cellStyleXfs count="1"><xf/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0"/><xf numFmtId="14" applyNumberFormat="1"/><xf numFmtId="22" applyNumberFormat="1"/></cellXfs></styleSheet>""",
+}
+
+
+def excel_serial(v):
+    base = datetime.datetime(1899, 12, 30)
+    if not isinstance(v, datetime.datetime):
+        v = datetime.datetime(v.year, v.month, v.day)
+    return (v - base).total_seconds() / 86400
+
+
+def xlsx_cell(ref, v):
+    if v is None:
+        return ""
+    if isinstance(v, datetime.datetime):
+        return f'<c r="{ref}" s="2"><v>{excel_serial(v)}</v></c>'
+    if isinstance(v, datetime.date):
+        return f'<c r="{ref}" s="1"><v>{excel_serial(v)}</v></c>'
+    if isinstance(v, (int, float)):
+        return f'<c r="{ref}"><v>{v}</v></c>'
+    return f'<c r="{ref}" t="inlineStr"><is><t>{v}</t></is></c>'
+
+
+def write_xlsx(file_name, rows):
+    body = ""
+    for i, row in enumerate(rows, start=1):
+        cells = "".join(xlsx_cell(f"{chr(ord('A') + j)}{i}", v) for j, v in enumerate(row))
+        body += f'<row r="{i}">{cells}</row>'
+    sheet = (
+        '<?xml version="1.0" encoding="UTF-8"?>\n'
+        '<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">'
+        f"<sheetData>{body}</sheetData></worksheet>"
+    )
+    with zipfile.ZipFile(os.path.join(HERE, file_name), "w") as z:
+        for name, text in XLSX_PARTS.items():
+            z.writestr(entry(name), text)
+        z.writestr(entry("xl/worksheets/sheet1.xml"), sheet)
+
+
+# Plain table on the first sheet
+write_ods("import_demo.ods", [
+    ("Sheet1", [
+        ("組", "英語", "国語"),
+        ("A組", 72, 65.5),
+        ("B組", 58, 70),
+        ("A組", 81, None),
+        ("B組", 64, 62),
+    ]),
+])
+
+# Survey workbook: cover sheet first, two title rows, table starting at B3
+write_ods("survey_demo.ods", [
+    ("表紙", [
+        ("2024年度 生活アンケート",),
+        ("集計用",),
+    ]),
+    ("回答", [
+        ("生活アンケート 回答一覧",),
+        ("※ 無回答は空欄",),
+        (None, "ID", "学年", "満足度", "メモ"),
+        (None, 1, "1年", 4, "特になし"),
+        (None, 2, "2年", 5, None),
+        (None, 3, "1年", 3, "部活が楽しい"),
+        (None, 4, "3年", 2, None),
+    ]),
+])
+
+# Date and date-time columns, as Excel serial numbers and as ODS ISO strings
+DATES = [
+    ("日付", "記録時刻", "値"),
+    (datetime.date(2024, 4, 1), datetime.datetime(2024, 4, 1, 8, 30), 1.5),
+    (datetime.date(2024, 4, 6), datetime.datetime(2024, 4, 6, 12, 0), 2.0),
+    (None, datetime.datetime(2024, 4, 7, 18, 45), 3.25),
+]
+write_xlsx("dates_demo.xlsx", DATES)
+write_ods("dates_demo.ods", [("Sheet1", DATES)])
+
+# Mixed-type columns: text typed into a score column, numeric codes in a label column
+write_ods("mixed_demo.ods", [
+    ("Sheet1", [
+        ("氏名", "英語", "出席番号", "欠席"),
+        ("佐藤", 72, "A-01", False),
+        ("鈴木", "N/A", "A-02", True),
+        ("高橋", "７０", 3, False),
+        ("田中", 58.5, "A-04", None),
+        ("伊藤", "-", "A-05", False),
+    ]),
+])
@@ -0,0 +1,244 @@
+//! Golden-value tests: every analysis on the bundled demo datasets is
+//! checked against the values stored in `tests/fixtures/golden.json`.
+//!
+//! The stored values were computed by a Python reimplementation of R's
+//! conventions, not by R. The fixture says so in its `source` field, and its
+//! `r_version` is null. `Rscript tests/fixtures/generate_golden.R` replaces
+//! them with R's results and records the R and package versions used.
+//! `tests/reference.rs` checks the engine against output printed by R itself.
+
+use easy_stat_r::stats::{anova, factor, pca, regression, ttest};
+use easy_stat_r::utils::excel_to_polars::convert_excel_to_df;
+use polars::prelude::DataFrame;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+struct Tolerance {
+    statistic: f64,
+    p_value: f64,
+    loading: f64,
+}
+
+fn fixtures() -> &'static Value {
+    static FIXTURES: OnceLock<Value> = OnceLock::new();
+    FIXTURES.get_or_init(|| {
+        let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden.json"))
+            .expect("golden.json missing");
+        serde_json::from_str(&text).expect("golden.json is not valid JSON")
+    })
+}
+
+fn tolerance() -> Tolerance {
+    let t = &fixtures()["tolerance"];
+    Tolerance {
+        statistic: t["statistic"].as_f64().unwrap(),
+        p_value: t["p_value"].as_f64().unwrap(),
+        loading: t["loading"].as_f64().unwrap(),
+    }
+}
+
+fn cases(kind: &str) -> &'static Vec<Value> {
+    fixtures()["cases"][kind].as_array().expect("missing fixture kind")
+}
+
+fn load(case: &Value) -> DataFrame {
+    let file = case["file"].as_str().unwrap();
+    let path = format!("{}/datasets/{}", env!("CARGO_MANIFEST_DIR"), file);
+    let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    convert_excel_to_df(&bytes).unwrap_or_else(|e| panic!("{}: {}", file, e))
+}
+
+fn s<'a>(case: &'a Value, key: &str) -> &'a str {
+    case[key].as_str().unwrap_or_else(|| panic!("missing {}", key))
+}
+
+fn strs(case: &Value, key: &str) -> Vec<String> {
+    case[key].as_array().unwrap().iter().map(|v| v.as_str().unwrap().to_string()).collect()
+}
+
+fn expected(case: &Value, key: &str) -> f64 {
+    case["expected"][key].as_f64().unwrap_or_else(|| panic!("missing expected {}", key))
+}
+
+/// Relative comparison for statistics (absolute near zero).
+fn assert_stat(label: &str, actual: f64, expected: f64, tol: f64) {
+    let scale = expected.abs().max(1.0);
+    assert!(
+        (actual - expected).abs() <= tol * scale,
+        "{}: got {}, expected {} (tol {})",
+        label, actual, expected, tol
+    );
+}
+
+/// p-values are compared relatively so tiny p-values are still checked.
+fn assert_p(label: &str, actual: f64, expected: f64, tol: f64) {
+    assert!(
+        (actual - expected).abs() <= tol * expected.max(1e-300) || (actual - expected).abs() < 1e-15,
+        "{}: got p = {:e}, expected {:e}",
+        label, actual, expected
+    );
+}
+
+#[test]
+fn welch_t_test_matches_reference() {
+    let tol = tolerance();
+    for case in cases("welch") {
+        let df = load(case);
+        let label = format!("welch {}", s(case, "value"));
+        let res = ttest::welch_from_df(&df, s(case, "value"), s(case, "group")).unwrap();
+        assert_stat(&label, res.groups[0].mean, expected(case, "mean1"), tol.statistic);
+        assert_stat(&label, res.groups[1].mean, expected(case, "mean2"), tol.statistic);
+        assert_stat(&label, res.t, expected(case, "t"), tol.statistic);
+        assert_stat(&label, res.df, expected(case, "df"), tol.statistic);
+        assert_stat(&label, res.d, expected(case, "d"), tol.statistic);
+        assert_p(&label, res.p, expected(case, "p"), tol.p_value);
+    }
+}
+
+#[test]
+fn paired_t_test_matches_reference() {
+    let tol = tolerance();
+    for case in cases("paired") {
+        let df = load(case);
+        let label = format!("paired {}", s(case, "pre"));
+        let res = ttest::paired_from_df(&df, s(case, "pre"), s(case, "post")).unwrap();
+        assert_stat(&label, res.mean_diff, expected(case, "mean_diff"), tol.statistic);
+        assert_stat(&label, res.sd_diff.unwrap(), expected(case, "sd_diff"), tol.statistic);
+        assert_stat(&label, res.t, expected(case, "t"), tol.statistic);
+        assert_stat(&label, res.df, expected(case, "df"), tol.statistic);
+        assert_stat(&label, res.d, expected(case, "d"), tol.statistic);
+        assert_p(&label, res.p, expected(case, "p"), tol.p_value);
+    }
+}
+
+#[test]
+fn one_way_anova_matches_reference() {
+    let tol = tolerance();
+    for case in cases("one_way") {
+        let df = load(case);
+        let label = format!("one-way {} by {}", s(case, "value"), s(case, "factor"));
+        let res = anova::one_way_from_df(&df, s(case, "value"), s(case, "factor")).unwrap();
+        assert_eq!(res.df_model as f64, expected(case, "df_model"), "{}", label);
+        assert_eq!(res.df_resid as f64, expected(case, "df_resid"), "{}", label);
+        assert_stat(&label, res.table[0].ss, expected(case, "ss_between"), tol.statistic);
+        assert_stat(&label, res.table[1].ss, expected(case, "ss_within"), tol.statistic);
+        assert_stat(&label, res.f, expected(case, "f"), tol.statistic);
+        assert_stat(&label, res.eta_squared, expected(case, "eta_squared"), tol.statistic);
+        assert_p(&label, res.p, expected(case, "p"), tol.p_value);
+    }
+}
+
+#[test]
+fn two_way_anova_matches_reference() {
+    let tol = tolerance();
+    for case in cases("two_way") {
+        let df = load(case);
+        let label = format!("two-way {}", s(case, "value"));
+        let res = anova::two_way_from_df(&df, s(case, "value"), s(case, "factor_a"), s(case, "factor_b")).unwrap();
+        for (row, key) in [(res.effect_a(), "a"), (res.effect_b(), "b"), (res.interaction(), "ab")] {
+            let label = format!("{} [{}]", label, row.source);
+            assert_eq!(row.df, expected(case, &format!("df_{}", key)), "{}", label);
+            assert_stat(&label, row.ss, expected(case, &format!("ss_{}", key)), tol.statistic);
+            assert_stat(&label, row.f.unwrap(), expected(case, &format!("f_{}", key)), tol.statistic);
+            assert_p(&label, row.p.unwrap(), expected(case, &format!("p_{}", key)), tol.p_value);
+        }
+        assert_eq!(res.table[3].df, expected(case, "df_resid"), "{}", label);
+        assert_stat(&label, res.table[3].ss, expected(case, "ss_resid"), tol.statistic);
+    }
+}
+
+#[test]
+fn regression_matches_reference() {
+    let tol = tolerance();
+    for case in cases("regression") {
+        let df = load(case);
+        let expl = strs(case, "explanatory");
+        let expl_refs: Vec<&str> = expl.iter().map(|s| s.as_str()).collect();
+        let label = format!("regression {}", s(case, "target"));
+        let res = regression::ols_from_df(&df, s(case, "target"), &expl_refs).unwrap();
+
+        assert_eq!(res.df_model as f64, expected(case, "df_model"), "{}", label);
+        assert_eq!(res.df_resid as f64, expected(case, "df_resid"), "{}", label);
+        assert_stat(&label, res.r2, expected(case, "r2"), tol.statistic);
+        assert_stat(&label, res.adj_r2, expected(case, "adj_r2"), tol.statistic);
+        assert_stat(&label, res.f, expected(case, "f"), tol.statistic);
+        assert_p(&label, res.p, expected(case, "p"), tol.p_value);
+
+        let coefs = case["expected"]["coefficients"].as_array().unwrap();
+        assert_eq!(res.coefficients.len(), coefs.len());
+        for (actual, exp) in res.coefficients.iter().zip(coefs) {
+            let label = format!("{} [{}]", label, actual.name);
+            assert_eq!(actual.name, exp["name"].as_str().unwrap());
+            assert_stat(&label, actual.estimate, exp["estimate"].as_f64().unwrap(), tol.statistic);
+            assert_stat(&label, actual.se, exp["se"].as_f64().unwrap(), tol.statistic);
+            assert_stat(&label, actual.t, exp["t"].as_f64().unwrap(), tol.statistic);
+            assert_p(&label, actual.p, exp["p"].as_f64().unwrap(), tol.p_value);
+        }
+    }
+}
+
+fn assert_eigenvalues(label: &str, actual: &[f64], case: &Value, tol: f64) {
+    let exp: Vec<f64> = case["expected"]["eigenvalues"].as_array().unwrap().iter().map(|v| v.as_f64().unwrap()).collect();
+    assert_eq!(actual.len(), exp.len(), "{}", label);
+    for (i, (a, e)) in actual.iter().zip(&exp).enumerate() {
+        assert_stat(&format!("{} eigenvalue {}", label, i + 1), *a, *e, tol);
+    }
+}
+
+/// Flips a component so its largest loading is positive; the sign of an
+/// eigenvector is arbitrary.
+fn sign_normalized(v: &[f64]) -> Vec<f64> {
+    let largest = v.iter().copied().max_by(|a, b| a.abs().total_cmp(&b.abs())).unwrap();
+    v.iter().map(|x| x * largest.signum()).collect()
+}
+
+#[test]
+fn pca_matches_reference() {
+    let tol = tolerance();
+    for case in cases("pca") {
+        let df = load(case);
+        let cols = strs(case, "columns");
+        let col_refs: Vec<&str> = cols.iter().map(|s| s.as_str()).collect();
+        let res = pca::pca_from_df(&df, &col_refs).unwrap();
+        let label = format!("pca {}", s(case, "file"));
+        assert_eigenvalues(&label, &res.eigenvalues(), case, tol.statistic);
+        let last = res.components.last().unwrap();
+        assert_stat("cumulative variance", last.cumulative, 1.0, tol.statistic);
+
+        let exp_loadings = case["expected"]["loadings"].as_array().unwrap();
+        for (j, component) in res.components.iter().enumerate() {
+            let exp: Vec<f64> = exp_loadings.iter().map(|row| row[j].as_f64().unwrap()).collect();
+            let actual = sign_normalized(&component.loadings);
+            for (i, (a, e)) in actual.iter().zip(sign_normalized(&exp)).enumerate() {
+                assert_stat(&format!("{} PC{} loading[{}]", label, j + 1, i), *a, e, tol.loading);
+            }
+        }
+    }
+}
+
+#[test]
+fn factor_analysis_matches_reference() {
+    let tol = tolerance();
+    for case in cases("factor") {
+        let df = load(case);
+        let cols = strs(case, "columns");
+        let col_refs: Vec<&str> = cols.iter().map(|s| s.as_str()).collect();
+        let n_factors = case["n_factors"].as_u64().unwrap() as usize;
+        let rotation = match s(case, "rotation") {
+            "varimax" => factor::Rotation::Varimax,
+            _ => factor::Rotation::None,
+        };
+        let label = format!("factor n={} {}", n_factors, s(case, "rotation"));
+        let res = factor::factor_analysis_from_df(&df, &col_refs, n_factors, rotation).unwrap();
+
+        assert_eigenvalues(&label, &res.eigenvalues, case, tol.statistic);
+        let exp_loadings = case["expected"]["loadings"].as_array().unwrap();
+        let exp_comm = case["expected"]["communalities"].as_array().unwrap();
+        for (i, (row, exp_row)) in res.loadings.iter().zip(exp_loadings).enumerate() {
+            for (j, (a, e)) in row.iter().zip(exp_row.as_array().unwrap()).enumerate() {
+                assert_stat(&format!("{} loading[{}][{}]", label, i, j), *a, e.as_f64().unwrap(), tol.loading);
+            }
+            assert_stat(&format!("{} communality[{}]", label, i), res.communalities[i], exp_comm[i].as_f64().unwrap(), tol.loading);
+        }
+    }
+}
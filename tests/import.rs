@@ -0,0 +1,219 @@
+//! File import and export: format detection, the spreadsheet readers, CSV
+//! options, pasted ranges and the download formats.
+//!
+//! The `.ods` fixtures come from `tests/fixtures/generate_import_fixtures.py`.
+
+use easy_stat_r::utils::csv_import::{detect_delimiter, preview_csv, CsvOptions, TextEncoding};
+use easy_stat_r::utils::data_formats::{export_dataframe, ExportFormat};
+use easy_stat_r::utils::dates::{add_date_parts, date_columns};
+use easy_stat_r::utils::excel_to_polars::{
+    parse_cell_range, preview_rows, read_workbook, read_workbook_with_report, sheet_names, SheetOptions, WorkbookFormat,
+};
+use easy_stat_r::utils::file_loader::{read_dataframe, read_dataframe_with, ImportOptions};
+use easy_stat_r::utils::paste_import::{detect_header, read_pasted, split_pasted};
+use easy_stat_r::utils::samples::{self, SAMPLES};
+use polars::prelude::*;
+
+fn read(path: &str) -> Vec<u8> {
+    let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+#[test]
+fn detects_workbook_format_from_content() {
+    let xlsx = read("datasets/ttest_demo.xlsx");
+    let ods = read("tests/fixtures/import_demo.ods");
+    let ole2 = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1, 0, 0];
+
+    assert_eq!(WorkbookFormat::detect("a.xlsx", &xlsx), Some(WorkbookFormat::Xlsx));
+    // Content wins over a wrong extension
+    assert_eq!(WorkbookFormat::detect("a.xls", &xlsx), Some(WorkbookFormat::Xlsx));
+    assert_eq!(WorkbookFormat::detect("upload", &ods), Some(WorkbookFormat::Ods));
+    assert_eq!(WorkbookFormat::detect("old.bin", &ole2), Some(WorkbookFormat::Xls));
+    assert_eq!(WorkbookFormat::detect("data.xlsb", b"?"), Some(WorkbookFormat::Xlsb));
+    assert_eq!(WorkbookFormat::detect("data.csv", b"a,b\n1,2\n"), None);
+}
+
+#[test]
+fn reads_ods_and_mislabelled_xlsx() {
+    let df = read_dataframe("import_demo.ods", &read("tests/fixtures/import_demo.ods")).unwrap();
+    assert_eq!(df.get_column_names(), &["組", "英語", "国語"]);
+    assert_eq!(df.height(), 4);
+    assert_eq!(df.column("国語").unwrap().null_count(), 1);
+
+    let df = read_dataframe("ttest_demo.xls", &read("datasets/ttest_demo.xlsx")).unwrap();
+    assert!(df.get_column_names().contains(&"英語"));
+}
+
+#[test]
+fn picks_sheet_header_row_and_range() {
+    let bytes = read("tests/fixtures/survey_demo.ods");
+    assert_eq!(sheet_names("survey_demo.ods", &bytes).unwrap(), vec!["表紙", "回答"]);
+
+    let sheet = SheetOptions { sheet: Some("回答".to_string()), ..Default::default() };
+    let preview = preview_rows("survey_demo.ods", &bytes, &sheet, 3).unwrap();
+    assert_eq!(preview[2].0, 3);
+    assert_eq!(preview[2].1[1], "ID");
+
+    let options = SheetOptions { header_row: Some(3), ..sheet.clone() };
+    let df = read_workbook("survey_demo.ods", &bytes, &options).unwrap();
+    assert_eq!(df.get_column_names(), &["Column1", "ID", "学年", "満足度", "メモ"]);
+    assert_eq!(df.height(), 4);
+
+    let options = SheetOptions { range: Some("C3:D99".to_string()), ..sheet.clone() };
+    let df = read_workbook("survey_demo.ods", &bytes, &options).unwrap();
+    assert_eq!(df.get_column_names(), &["学年", "満足度"]);
+    assert_eq!(df.height(), 4);
+
+    let options = SheetOptions { header_row: Some(2), range: Some("B3:E7".to_string()), ..sheet };
+    assert!(read_workbook("survey_demo.ods", &bytes, &options).is_err());
+}
+
+#[test]
+fn parses_a1_ranges() {
+    assert_eq!(parse_cell_range("B3:F120").unwrap(), ((2, 1), (119, 5)));
+    assert_eq!(parse_cell_range("aa1:ab2").unwrap(), ((0, 26), (1, 27)));
+    assert!(parse_cell_range("F3:B1").is_err());
+    assert!(parse_cell_range("B0:C2").is_err());
+    assert!(parse_cell_range("B3").is_err());
+}
+
+#[test]
+fn date_cells_become_date_columns() {
+    for file in ["dates_demo.xlsx", "dates_demo.ods"] {
+        let df = read_dataframe(file, &read(&format!("tests/fixtures/{}", file))).unwrap();
+        assert_eq!(df.column("日付").unwrap().dtype(), &DataType::Date, "{}", file);
+        assert_eq!(
+            df.column("記録時刻").unwrap().dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None),
+            "{}",
+            file
+        );
+        assert_eq!(df.column("日付").unwrap().get(0).unwrap().to_string(), "2024-04-01", "{}", file);
+        assert_eq!(df.column("記録時刻").unwrap().get(0).unwrap().to_string(), "2024-04-01 08:30:00", "{}", file);
+        assert_eq!(df.column("日付").unwrap().null_count(), 1, "{}", file);
+        assert_eq!(date_columns(&df), vec!["日付", "記録時刻"]);
+
+        let df = add_date_parts(&df, "日付").unwrap();
+        let year: Vec<Option<i32>> = df.column("日付_年").unwrap().i32().unwrap().into_iter().collect();
+        let weekday: Vec<Option<i32>> = df.column("日付_曜日").unwrap().i32().unwrap().into_iter().collect();
+        assert_eq!(year, vec![Some(2024), Some(2024), None]);
+        // 2024-04-01 was a Monday, 2024-04-06 a Saturday
+        assert_eq!(weekday, vec![Some(1), Some(6), None]);
+        assert!(add_date_parts(&df, "値").is_err());
+        // Derived columns are never overwritten
+        assert!(add_date_parts(&df, "日付").unwrap_err().to_string().contains("'日付_年' already exists"));
+    }
+}
+
+#[test]
+fn mixed_columns_are_coerced_and_reported() {
+    let bytes = read("tests/fixtures/mixed_demo.ods");
+    let (df, report) = read_workbook_with_report("mixed_demo.ods", &bytes, &Default::default()).unwrap();
+
+    let scores: Vec<Option<f64>> = df.column("英語").unwrap().f64().unwrap().into_iter().collect();
+    assert_eq!(scores, vec![Some(72.0), None, Some(70.0), Some(58.5), None]);
+    // A numeric code in a label column is kept as text, not dropped
+    assert_eq!(df.column("出席番号").unwrap().dtype(), &DataType::String);
+    assert_eq!(df.column("出席番号").unwrap().get(2).unwrap().to_string(), "\"3\"");
+    assert_eq!(df.column("欠席").unwrap().dtype(), &DataType::Boolean);
+
+    assert_eq!(report.coerced_total(), 2);
+    let english = report.coerced_columns().next().unwrap();
+    assert_eq!(english.column, "英語");
+    let cells: Vec<(usize, &str)> = english.coerced.iter().map(|c| (c.row, c.value.as_str())).collect();
+    assert_eq!(cells, vec![(3, "N/A"), (6, "-")]);
+    assert_eq!(report.summary_lines(1), vec!["英語 (f64): 2 cells set to null (row 3 \"N/A\", ...)"]);
+}
+
+#[test]
+fn detects_csv_encoding_and_delimiter() {
+    let (sjis, _, _) = encoding_rs::SHIFT_JIS.encode("組;英語;国語\nA;72,5;NA\nB;64;81\n");
+    assert_eq!(TextEncoding::detect(&sjis), TextEncoding::ShiftJis);
+    assert_eq!(detect_delimiter("a\tb,c\t\"x\ty\"\n1\t2,5\t3\n", Some('"')), '\t');
+    assert_eq!(detect_delimiter("a;b;c\n1,5;2;3\n", Some('"')), ';');
+
+    let options = CsvOptions { decimal_comma: true, ..Default::default() };
+    let df = read_dataframe_with("score.csv", &sjis, &ImportOptions { csv: options, ..Default::default() }).unwrap();
+    assert_eq!(df.get_column_names(), &["組", "英語", "国語"]);
+    let english: Vec<Option<f64>> = df.column("英語").unwrap().f64().unwrap().into_iter().collect();
+    assert_eq!(english, vec![Some(72.5), Some(64.0)]);
+    assert_eq!(df.column("国語").unwrap().null_count(), 1);
+
+    // UTF-16 with BOM, as saved by Excel's "Unicode text"
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend("ID\t値\n1\t-\n2\t3.5\n".encode_utf16().flat_map(|u| u.to_le_bytes()));
+    let options = CsvOptions { na_values: vec!["-".to_string()], ..Default::default() };
+    let df = preview_csv(&utf16, &options, 1).unwrap();
+    assert_eq!(df.get_column_names(), &["ID", "値"]);
+    assert_eq!(df.height(), 1);
+    assert_eq!(df.column("値").unwrap().null_count(), 1);
+}
+
+#[test]
+fn bundled_samples_load() {
+    for sample in SAMPLES {
+        let df = sample.load().unwrap();
+        assert!(df.height() > 0, "{}", sample.id);
+    }
+    let ttest = samples::find("ttest_demo").unwrap().load().unwrap();
+    assert!(ttest.get_column_names().contains(&"組"));
+    assert!(samples::find("missing").is_none());
+}
+
+#[test]
+fn pasted_ranges_detect_header_and_types() {
+    // As copied from Excel: CRLF line ends, a quoted cell with a line break, a trailing newline
+    let text = "組\t英語\t出席\r\nA\t72\tTRUE\r\nB\t欠席\tFALSE\r\n\"C\nD\"\t58.5\tTRUE\r\n";
+    let rows = split_pasted(text);
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[3][0], "C\nD");
+    assert!(detect_header(&rows));
+    assert!(!detect_header(&split_pasted("1\t2\n3\t4\n")));
+
+    let (df, report) = read_pasted(text, true).unwrap();
+    assert_eq!(df.get_column_names(), &["組", "英語", "出席"]);
+    assert_eq!(df.column("英語").unwrap().dtype(), &DataType::Float64);
+    assert_eq!(df.column("出席").unwrap().dtype(), &DataType::Boolean);
+    let cells: Vec<(usize, &str)> = report.columns[1].coerced.iter().map(|c| (c.row, c.value.as_str())).collect();
+    assert_eq!(cells, vec![(3, "欠席")]);
+
+    let (df, _) = read_pasted("1\t2\n3\t4\n", false).unwrap();
+    assert_eq!(df.get_column_names(), &["Column1", "Column2"]);
+    assert_eq!(df.column("Column1").unwrap().dtype(), &DataType::Int64);
+}
+
+#[test]
+fn exported_data_reads_back_in_every_format() {
+    let dates = Series::new("日付", [19814i32, 19819]).cast(&DataType::Date).unwrap();
+    let df = DataFrame::new(vec![
+        Series::new("組", ["A", "B & <C>"]),
+        Series::new("英語", [Some(72.5), None]),
+        Series::new("人数", [30i64, 28]),
+        Series::new("欠席", [false, true]),
+        dates,
+    ])
+    .unwrap();
+
+    for format in ExportFormat::ALL {
+        let bytes = export_dataframe(&df, format).unwrap();
+        let name = format.file_name("survey.xlsx");
+        let back = read_dataframe(&name, &bytes).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert_eq!(back.get_column_names(), df.get_column_names(), "{}", name);
+        assert_eq!(back.column("組").unwrap().get(1).unwrap().to_string(), "\"B & <C>\"", "{}", name);
+        assert_eq!(back.column("英語").unwrap().null_count(), 1, "{}", name);
+        let english = back.column("英語").unwrap().cast(&DataType::Float64).unwrap();
+        assert_eq!(english.f64().unwrap().get(0), Some(72.5), "{}", name);
+        if format != ExportFormat::Json {
+            // JSON has no date type; the others keep dates as dates
+            assert_eq!(back.column("日付").unwrap().dtype(), &DataType::Date, "{}", name);
+        }
+    }
+    assert_eq!(ExportFormat::Parquet.file_name("survey.xlsx"), "survey.parquet");
+
+    // Records from a form tool: a JSON array, or JSON Lines saved as .json
+    let lines = read_dataframe("form.json", b"{\"id\": 1, \"q1\": 4}\n{\"id\": 2, \"q1\": null}\n").unwrap();
+    assert_eq!(lines.shape(), (2, 2));
+    let array = read_dataframe("form.json", b"[{\"id\": 1, \"q1\": 4}, {\"id\": 2, \"q1\": 5}]").unwrap();
+    assert_eq!(array.column("q1").unwrap().sum::<i64>().unwrap(), 9);
+}
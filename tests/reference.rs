@@ -0,0 +1,275 @@
+//! The engine on R's built-in example datasets, checked against the output
+//! R prints for them (`t.test`, `summary(lm)`, `summary(aov)`, `prcomp`), to
+//! the number of digits R shows. The varimax solution is checked against
+//! Kaiser's closed form for two factors, which R's iterative
+//! `stats::varimax(normalize = TRUE)` converges to.
+
+use easy_stat_r::stats::{anova, factor, mean, pca, regression, ttest};
+use nalgebra::DMatrix;
+
+/// `expected` as R prints it, with `digits` significant digits.
+fn assert_printed(label: &str, actual: f64, expected: f64, digits: i32) {
+    let unit = 10f64.powi(expected.abs().log10().floor() as i32 - digits + 1);
+    assert!(
+        (actual - expected).abs() <= unit / 2.0 + 1e-12,
+        "{}: got {}, R prints {}",
+        label, actual, expected
+    );
+}
+
+/// `sleep`: extra hours of sleep under two drugs.
+fn sleep() -> (Vec<f64>, Vec<f64>) {
+    (
+        vec![0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0],
+        vec![1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4],
+    )
+}
+
+#[test]
+fn welch_t_test_matches_r_on_sleep() {
+    // t.test(extra ~ group, data = sleep)
+    let (g1, g2) = sleep();
+    let res = ttest::welch("1", &g1, "2", &g2).unwrap();
+    assert_printed("t", res.t, -1.8608, 5);
+    assert_printed("df", res.df, 17.776, 5);
+    assert_printed("p", res.p, 0.07939, 4);
+    assert_printed("mean 1", res.groups[0].mean, 0.75, 2);
+    assert_printed("mean 2", res.groups[1].mean, 2.33, 3);
+}
+
+#[test]
+fn regression_matches_r_on_cars() {
+    // summary(lm(dist ~ speed, data = cars))
+    let speed = [
+        4, 4, 7, 7, 8, 9, 10, 10, 10, 11, 11, 12, 12, 12, 12, 13, 13, 13, 13, 14, 14, 14, 14, 15, 15, 15, 16, 16, 17, 17,
+        17, 18, 18, 18, 18, 19, 19, 19, 20, 20, 20, 20, 20, 22, 23, 24, 24, 24, 24, 25,
+    ];
+    let dist = [
+        2, 10, 4, 22, 16, 10, 18, 26, 34, 17, 28, 14, 20, 24, 28, 26, 34, 34, 46, 26, 36, 60, 80, 20, 26, 54, 32, 40, 32,
+        40, 50, 42, 56, 76, 84, 36, 46, 68, 32, 48, 52, 56, 64, 66, 54, 70, 92, 93, 120, 85,
+    ];
+    let speed: Vec<f64> = speed.iter().map(|x| *x as f64).collect();
+    let dist: Vec<f64> = dist.iter().map(|x| *x as f64).collect();
+    let res = regression::ols("dist", &dist, &["speed"], &[speed]).unwrap();
+
+    let (b0, b1) = (res.intercept(), &res.predictors()[0]);
+    assert_printed("intercept", b0.estimate, -17.5791, 6);
+    assert_printed("intercept se", b0.se, 6.7584, 5);
+    assert_printed("intercept t", b0.t, -2.601, 4);
+    assert_printed("intercept p", b0.p, 0.0123, 3);
+    assert_printed("slope", b1.estimate, 3.9324, 5);
+    assert_printed("slope se", b1.se, 0.4155, 4);
+    assert_printed("slope t", b1.t, 9.464, 4);
+    assert_printed("slope p", b1.p, 1.49e-12, 3);
+    assert_printed("R²", res.r2, 0.6511, 4);
+    assert_printed("adjusted R²", res.adj_r2, 0.6438, 4);
+    assert_printed("F", res.f, 89.57, 4);
+    assert_printed("F p", res.p, 1.49e-12, 3);
+    assert_eq!((res.df_model, res.df_resid), (1, 48));
+    let rss: f64 = res.observed.iter().zip(&res.fitted).map(|(y, f)| (y - f).powi(2)).sum();
+    assert_printed("residual standard error", (rss / 48.0).sqrt(), 15.38, 4);
+}
+
+#[test]
+fn two_way_anova_matches_r_on_tooth_growth() {
+    // summary(aov(len ~ supp * factor(dose), data = ToothGrowth)); the design
+    // is balanced, so these are also car::Anova(type = 2)
+    let cells: [(&str, &str, [f64; 10]); 6] = [
+        ("VC", "0.5", [4.2, 11.5, 7.3, 5.8, 6.4, 10.0, 11.2, 11.2, 5.2, 7.0]),
+        ("VC", "1", [16.5, 16.5, 15.2, 17.3, 22.5, 17.3, 13.6, 14.5, 18.8, 15.5]),
+        ("VC", "2", [23.6, 18.5, 33.9, 25.5, 26.4, 32.5, 26.7, 21.5, 23.3, 29.5]),
+        ("OJ", "0.5", [15.2, 21.5, 17.6, 9.7, 14.5, 10.0, 8.2, 9.4, 16.5, 9.7]),
+        ("OJ", "1", [19.7, 23.3, 23.6, 26.4, 20.0, 25.2, 25.8, 21.2, 14.5, 27.3]),
+        ("OJ", "2", [25.5, 26.4, 22.4, 24.5, 24.8, 30.9, 26.4, 27.3, 29.4, 23.0]),
+    ];
+    let (mut y, mut supp, mut dose) = (Vec::new(), Vec::new(), Vec::new());
+    for (s, d, values) in &cells {
+        y.extend_from_slice(values);
+        supp.extend(std::iter::repeat_n(s.to_string(), values.len()));
+        dose.extend(std::iter::repeat_n(d.to_string(), values.len()));
+    }
+    let res = anova::two_way("supp", "dose", &y, &supp, &dose).unwrap();
+
+    let (a, b, ab, resid) = (res.effect_a(), res.effect_b(), res.interaction(), &res.table[3]);
+    assert_eq!((a.df, b.df, ab.df, resid.df), (1.0, 2.0, 2.0, 54.0));
+    assert_printed("supp SS", a.ss, 205.4, 4);
+    assert_printed("supp F", a.f.unwrap(), 15.572, 5);
+    assert_printed("supp p", a.p.unwrap(), 0.000231, 3);
+    assert_printed("dose SS", b.ss, 2426.4, 5);
+    assert_printed("dose F", b.f.unwrap(), 92.000, 5);
+    // R prints "< 2e-16": the upper tail is tiny but not zero
+    let p = b.p.unwrap();
+    assert!(p > 0.0 && p < 2e-16, "dose p = {:e}", p);
+    assert_printed("supp:dose SS", ab.ss, 108.3, 4);
+    assert_printed("supp:dose F", ab.f.unwrap(), 4.107, 4);
+    assert_printed("supp:dose p", ab.p.unwrap(), 0.02186, 4);
+    assert_printed("residual SS", resid.ss, 712.1, 4);
+}
+
+/// `USArrests`: Murder, Assault, UrbanPop and Rape for the 50 states.
+fn us_arrests() -> Vec<Vec<f64>> {
+    let rows: [[f64; 4]; 50] = [
+        [13.2, 236.0, 58.0, 21.2],
+        [10.0, 263.0, 48.0, 44.5],
+        [8.1, 294.0, 80.0, 31.0],
+        [8.8, 190.0, 50.0, 19.5],
+        [9.0, 276.0, 91.0, 40.6],
+        [7.9, 204.0, 78.0, 38.7],
+        [3.3, 110.0, 77.0, 11.1],
+        [5.9, 238.0, 72.0, 15.8],
+        [15.4, 335.0, 80.0, 31.9],
+        [17.4, 211.0, 60.0, 25.8],
+        [5.3, 46.0, 83.0, 20.2],
+        [2.6, 120.0, 54.0, 14.2],
+        [10.4, 249.0, 83.0, 24.0],
+        [7.2, 113.0, 65.0, 21.0],
+        [2.2, 56.0, 57.0, 11.3],
+        [6.0, 115.0, 66.0, 18.0],
+        [9.7, 109.0, 52.0, 16.3],
+        [15.4, 249.0, 66.0, 22.2],
+        [2.1, 83.0, 51.0, 7.8],
+        [11.3, 300.0, 67.0, 27.8],
+        [4.4, 149.0, 85.0, 16.3],
+        [12.1, 255.0, 74.0, 35.1],
+        [2.7, 72.0, 66.0, 14.9],
+        [16.1, 259.0, 44.0, 17.1],
+        [9.0, 178.0, 70.0, 28.2],
+        [6.0, 109.0, 53.0, 16.4],
+        [4.3, 102.0, 62.0, 16.5],
+        [12.2, 252.0, 81.0, 46.0],
+        [2.1, 57.0, 56.0, 9.5],
+        [7.4, 159.0, 89.0, 18.8],
+        [11.4, 285.0, 70.0, 32.1],
+        [11.1, 254.0, 86.0, 26.1],
+        [13.0, 337.0, 45.0, 16.1],
+        [0.8, 45.0, 44.0, 7.3],
+        [7.3, 120.0, 75.0, 21.4],
+        [6.6, 151.0, 68.0, 20.0],
+        [4.9, 159.0, 67.0, 29.3],
+        [6.3, 106.0, 72.0, 14.9],
+        [3.4, 174.0, 87.0, 8.3],
+        [14.4, 279.0, 48.0, 22.5],
+        [3.8, 86.0, 45.0, 12.8],
+        [13.2, 188.0, 59.0, 26.9],
+        [12.7, 201.0, 80.0, 25.5],
+        [3.2, 120.0, 80.0, 22.9],
+        [2.2, 48.0, 32.0, 11.2],
+        [8.5, 156.0, 63.0, 20.7],
+        [4.0, 145.0, 73.0, 26.2],
+        [5.7, 81.0, 39.0, 9.3],
+        [2.6, 53.0, 66.0, 10.8],
+        [6.8, 161.0, 60.0, 15.6],
+    ];
+    let cols: Vec<Vec<f64>> = (0..4).map(|j| rows.iter().map(|r| r[j]).collect()).collect();
+    // colMeans(USArrests), to catch a mistyped row
+    for (col, m) in cols.iter().zip([7.788, 170.76, 65.54, 21.232]) {
+        assert!((mean(col) - m).abs() < 1e-9, "column mean {} != {}", mean(col), m);
+    }
+    cols
+}
+
+const US_ARRESTS: [&str; 4] = ["Murder", "Assault", "UrbanPop", "Rape"];
+
+/// Flips a vector so its largest element is positive (the sign of a
+/// principal component is arbitrary).
+fn sign_normalized(v: &[f64]) -> Vec<f64> {
+    let largest = v.iter().copied().max_by(|a, b| a.abs().total_cmp(&b.abs())).unwrap();
+    v.iter().map(|x| x * largest.signum()).collect()
+}
+
+#[test]
+fn pca_matches_r_on_us_arrests() {
+    // prcomp(USArrests, scale. = TRUE)
+    let res = pca::pca(&US_ARRESTS, &us_arrests()).unwrap();
+    for (i, (c, sdev)) in res.components.iter().zip([1.5748783, 0.9948694, 0.5971291, 0.4164494]).enumerate() {
+        assert_printed(&format!("PC{} sdev", i + 1), c.eigenvalue.sqrt(), sdev, 7);
+    }
+    let rotation = [
+        [-0.5358995, -0.5831836, -0.2781909, -0.5434321],
+        [0.4181809, 0.1879856, -0.8728062, -0.1673186],
+    ];
+    for (i, expected) in rotation.iter().enumerate() {
+        let actual = sign_normalized(&res.components[i].loadings);
+        for (j, (a, e)) in actual.iter().zip(sign_normalized(expected)).enumerate() {
+            assert_printed(&format!("PC{} {}", i + 1, US_ARRESTS[j]), *a, e, 7);
+        }
+    }
+}
+
+/// Raw varimax criterion of a two-column loading matrix.
+fn varimax_criterion(rows: &[[f64; 2]]) -> f64 {
+    let p = rows.len() as f64;
+    (0..2)
+        .map(|j| {
+            let sq: Vec<f64> = rows.iter().map(|r| r[j] * r[j]).collect();
+            sq.iter().map(|x| x * x).sum::<f64>() - sq.iter().sum::<f64>().powi(2) / p
+        })
+        .sum()
+}
+
+fn rotated(rows: &[[f64; 2]], angle: f64) -> Vec<[f64; 2]> {
+    let (s, c) = angle.sin_cos();
+    rows.iter().map(|[x, y]| [x * c + y * s, -x * s + y * c]).collect()
+}
+
+/// psych's convention: factors by decreasing sum of squares, with positive
+/// column sums.
+fn sorted_and_signed(mut rows: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let ss = |rows: &[[f64; 2]], j: usize| rows.iter().map(|r| r[j] * r[j]).sum::<f64>();
+    if ss(&rows, 1) > ss(&rows, 0) {
+        rows.iter_mut().for_each(|r| r.swap(0, 1));
+    }
+    for j in 0..2 {
+        if rows.iter().map(|r| r[j]).sum::<f64>() < 0.0 {
+            rows.iter_mut().for_each(|r| r[j] = -r[j]);
+        }
+    }
+    rows
+}
+
+#[test]
+fn unrotated_factors_follow_the_psych_sign_convention() {
+    // principal(USArrests, 2, rotate = "none"): prcomp's second component sums
+    // to a negative value, so psych flips it
+    let res = factor::factor_analysis(&US_ARRESTS, &us_arrests(), 2, factor::Rotation::None).unwrap();
+    for j in 0..2 {
+        let sum: f64 = res.loadings.iter().map(|row| row[j]).sum();
+        assert!(sum >= 0.0, "factor {} sums to {}", j + 1, sum);
+    }
+    assert!(res.loadings[2][1] > 0.0, "UrbanPop on factor 2: {}", res.loadings[2][1]);
+}
+
+#[test]
+fn varimax_matches_kaiser_closed_form() {
+    let cols = us_arrests();
+    let unrotated = factor::factor_analysis(&US_ARRESTS, &cols, 2, factor::Rotation::None).unwrap();
+    let varimax = factor::factor_analysis(&US_ARRESTS, &cols, 2, factor::Rotation::Varimax).unwrap();
+
+    // Kaiser normalization: rotate rows scaled to unit length, then scale back
+    let norms: Vec<f64> = unrotated.communalities.iter().map(|h| h.sqrt()).collect();
+    let normalized: Vec<[f64; 2]> = unrotated.loadings.iter().zip(&norms).map(|(r, h)| [r[0] / h, r[1] / h]).collect();
+    let (u, v): (Vec<f64>, Vec<f64>) = normalized.iter().map(|[x, y]| (x * x - y * y, 2.0 * x * y)).unzip();
+    let p = normalized.len() as f64;
+    let (a, b) = (u.iter().sum::<f64>(), v.iter().sum::<f64>());
+    let c: f64 = u.iter().zip(&v).map(|(u, v)| u * u - v * v).sum();
+    let d: f64 = 2.0 * u.iter().zip(&v).map(|(u, v)| u * v).sum::<f64>();
+    let angle = (d - 2.0 * a * b / p).atan2(c - (a * a - b * b) / p) / 4.0;
+    let best = varimax_criterion(&rotated(&normalized, angle));
+    for step in [-0.01, 0.01] {
+        assert!(best > varimax_criterion(&rotated(&normalized, angle + step)), "closed form is not a maximum");
+    }
+    let expected = sorted_and_signed(rotated(&normalized, angle).iter().zip(&norms).map(|(r, h)| [r[0] * h, r[1] * h]).collect());
+
+    // Iterated to convergence, the rotation is the closed-form one
+    let m = DMatrix::from_fn(4, 2, |i, j| unrotated.loadings[i][j]);
+    let converged = sorted_and_signed(factor::varimax(&m, 1000, 1e-14).row_iter().map(|r| [r[0], r[1]]).collect());
+    // The analysis stops at R's default eps = 1e-5, a little short of the optimum
+    for (i, exp) in expected.iter().enumerate() {
+        for j in 0..2 {
+            let label = format!("{} factor {}", US_ARRESTS[i], j + 1);
+            assert!((converged[i][j] - exp[j]).abs() < 1e-6, "{}: got {}, expected {}", label, converged[i][j], exp[j]);
+            assert!((varimax.loadings[i][j] - exp[j]).abs() < 1e-3, "{}: got {}, expected {}", label, varimax.loadings[i][j], exp[j]);
+        }
+        assert!((varimax.communalities[i] - unrotated.communalities[i]).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,59 @@
+//! The typed result model: cells keep their numbers, p-values carry marks,
+//! plot specs are plain Plotly JSON.
+
+use easy_stat_r::plot::{self, Figure};
+use easy_stat_r::report::{Cell, Table};
+use easy_stat_r::stats::ttest;
+
+#[test]
+fn p_value_cells_keep_value_and_mark() {
+    let cell = Cell::PValue(0.003);
+    assert_eq!(cell.as_f64(), Some(0.003));
+    assert_eq!(cell.mark(), Some("**"));
+    assert_eq!(cell.to_string(), "0.0030 **");
+    assert_eq!(Cell::PValue(1e-8).value_text(), "< 0.0001");
+    assert_eq!(Cell::float(1.23456, 2).to_string(), "1.23");
+    assert_eq!(Cell::Int(3).mark(), None);
+}
+
+#[test]
+fn table_text_aligns_wide_characters() {
+    let table = Table::new("t", &["変数", "N"])
+        .row(vec![Cell::text("英語"), Cell::Int(10)])
+        .row(vec![Cell::text("ab"), Cell::Int(5)]);
+    let text = table.to_text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "--- t ---");
+    assert_eq!(lines[1], "変数  N");
+    assert_eq!(lines[3], "英語  10");
+    assert_eq!(lines[4], "ab    5");
+}
+
+#[test]
+fn t_test_report_exposes_numbers() {
+    let a = [5.1, 4.9, 6.2, 5.8, 6.0, 5.5];
+    let b = [4.1, 4.5, 3.9, 5.0, 4.2, 4.4];
+    let res = ttest::welch("A", &a, "B", &b).unwrap();
+    let report = res.report();
+
+    let test = report.tables.iter().find(|t| t.title.as_deref() == Some("検定結果")).unwrap();
+    let p_col = test.header.iter().position(|h| h == "p").unwrap();
+    assert_eq!(test.rows[0][p_col], Cell::PValue(res.p));
+    assert!(report.interpretation.is_some());
+    assert_eq!(report.plots.len(), 1);
+}
+
+#[test]
+fn plot_spec_keeps_labels_verbatim() {
+    let label = r"it's a \ label";
+    let spec = Figure::new(label)
+        .x_title("x")
+        .trace(plot::bar(label, vec![label.to_string()], vec![1.5], Some(vec![0.1])))
+        .build();
+
+    assert_eq!(spec.data[0]["type"], "bar");
+    assert_eq!(spec.data[0]["x"][0], label);
+    assert_eq!(spec.data[0]["error_y"]["array"][0], 0.1);
+    assert_eq!(spec.layout["title"]["text"], label);
+    assert_eq!(spec.layout["xaxis"]["title"]["text"], "x");
+}
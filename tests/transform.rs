@@ -0,0 +1,451 @@
+//! Data-cleaning transforms on small hand-made frames.
+
+use easy_stat_r::stats::{anova, ttest};
+use easy_stat_r::utils::binning::{bin_column, BinMethod, BinSpec};
+use easy_stat_r::utils::duplicates::{deduplicate, duplicate_groups, KeepRule};
+use easy_stat_r::utils::expression::{add_computed_column, parse};
+use easy_stat_r::utils::filter::{Condition, FilterOp, RowFilter};
+use easy_stat_r::utils::merge::{check_keys, merge, JoinKind, MergeOptions};
+use easy_stat_r::utils::missing::{apply_missing, missing_patterns, null_summary, ImputeMethod, MissingAction};
+use easy_stat_r::utils::outliers::{change_log, detect_outliers, flagged_rows, treat_outliers, OutlierMethod, OutlierTreatment};
+use easy_stat_r::utils::recipe::{recipe_from_json, recipe_to_json, replay, Step};
+use easy_stat_r::utils::recode::{cast_column, level_order, merge_rare, recode, set_level_order, value_counts, CastTarget};
+use easy_stat_r::utils::reshape::{melt, pivot, MeltOptions, PivotOptions};
+use easy_stat_r::utils::scale::{score_scale, ScaleSpec, ScoreMethod};
+use polars::prelude::*;
+
+fn roster() -> DataFrame {
+    df!(
+        "学籍番号" => [1i64, 2, 3, 4],
+        "氏名" => ["相川", "井上", "上田", "遠藤"],
+    )
+    .unwrap()
+}
+
+fn scores() -> DataFrame {
+    df!(
+        "id" => [2i64, 3, 3, 5],
+        "得点" => [70i64, 55, 60, 80],
+    )
+    .unwrap()
+}
+
+fn options(kind: JoinKind) -> MergeOptions {
+    MergeOptions { left_on: vec!["学籍番号".into()], right_on: vec!["id".into()], kind }
+}
+
+#[test]
+fn merge_supports_each_join_kind_and_reports_key_problems() {
+    let (left, right) = (roster(), scores());
+
+    // Student 3 has two score rows, so inner and left joins repeat that student
+    assert_eq!(merge(&left, &right, &options(JoinKind::Inner)).unwrap().height(), 3);
+    let joined = merge(&left, &right, &options(JoinKind::Left)).unwrap();
+    assert_eq!(joined.height(), 5);
+    assert_eq!(joined.column("得点").unwrap().null_count(), 2);
+    let outer = merge(&left, &right, &options(JoinKind::Outer)).unwrap();
+    assert_eq!(outer.height(), 6);
+    assert_eq!(outer.column("学籍番号").unwrap().null_count(), 0);
+    let anti = merge(&left, &right, &options(JoinKind::Anti)).unwrap();
+    assert_eq!(anti.column("学籍番号").unwrap().i64().unwrap().into_no_null_iter().collect::<Vec<_>>(), vec![1, 4]);
+
+    let check = check_keys(&left, &right, &options(JoinKind::Left)).unwrap();
+    assert_eq!(check.left_unmatched.height(), 2);
+    assert_eq!(check.right_unmatched.column("id").unwrap().i64().unwrap().get(0), Some(5));
+    assert!(check.left_duplicates.is_empty());
+    assert_eq!(check.right_duplicates.column("rows").unwrap().cast(&DataType::Int64).unwrap().i64().unwrap().get(0), Some(2));
+
+    // Text keys do not silently match numeric ones
+    let text_ids = df!("id" => ["2", "3"]).unwrap();
+    assert!(merge(&left, &text_ids, &options(JoinKind::Inner)).is_err());
+}
+
+fn f64_values(df: &DataFrame, name: &str) -> Vec<Option<f64>> {
+    df.column(name).unwrap().cast(&DataType::Float64).unwrap().f64().unwrap().into_iter().collect()
+}
+
+#[test]
+fn missing_values_are_counted_dropped_and_imputed() {
+    let df = df!(
+        "score" => [Some(1i64), None, Some(3), Some(3), None],
+        "group" => [Some("a"), Some("b"), None, Some("b"), None],
+    )
+    .unwrap();
+
+    let summary = null_summary(&df);
+    assert_eq!(f64_values(&summary, "missing"), vec![Some(2.0), Some(2.0)]);
+    let patterns = missing_patterns(&df);
+    assert_eq!(patterns.column("missing columns").unwrap().str().unwrap().get(0), Some(""));
+    assert_eq!(f64_values(&patterns, "rows"), vec![Some(2.0), Some(1.0), Some(1.0), Some(1.0)]);
+
+    let listwise = apply_missing(&df, &MissingAction::DropRows(vec!["score".into(), "group".into()])).unwrap();
+    assert_eq!(listwise.height(), 2);
+    let dropped = apply_missing(&df, &MissingAction::DropColumns(vec!["group".into()])).unwrap();
+    assert_eq!(dropped.get_column_names(), vec!["score"]);
+
+    let impute = |method| apply_missing(&df, &MissingAction::Impute(vec!["score".into()], method)).unwrap();
+    let mean = 7.0 / 3.0;
+    assert_eq!(f64_values(&impute(ImputeMethod::Mean), "score"), vec![Some(1.0), Some(mean), Some(3.0), Some(3.0), Some(mean)]);
+    assert_eq!(f64_values(&impute(ImputeMethod::Median), "score")[1], Some(3.0));
+    assert_eq!(f64_values(&impute(ImputeMethod::Mode), "score")[4], Some(3.0));
+    assert_eq!(f64_values(&impute(ImputeMethod::Constant("0".into())), "score")[1], Some(0.0));
+    assert_eq!(f64_values(&impute(ImputeMethod::Locf), "score"), vec![Some(1.0), Some(1.0), Some(3.0), Some(3.0), Some(3.0)]);
+
+    let groups = apply_missing(&df, &MissingAction::Impute(vec!["group".into()], ImputeMethod::Mode)).unwrap();
+    assert_eq!(groups.column("group").unwrap().null_count(), 0);
+    assert!(apply_missing(&df, &MissingAction::Impute(vec!["group".into()], ImputeMethod::Mean)).is_err());
+    assert!(apply_missing(&df, &MissingAction::Impute(vec!["score".into()], ImputeMethod::Constant("x".into()))).is_err());
+}
+
+#[test]
+fn columns_are_cast_with_a_report_and_recoded() {
+    let df = df!(
+        "score" => ["72", " 65", "欠席", "80", "欠席"],
+        "gender" => [1i64, 2, 2, 1, 3],
+        "ratio" => [1.0f64, 2.5, 3.0, 4.0, 5.0],
+    )
+    .unwrap();
+
+    let (cast, report) = cast_column(&df, "score", CastTarget::Float).unwrap();
+    assert_eq!(f64_values(&cast, "score"), vec![Some(72.0), Some(65.0), None, Some(80.0), None]);
+    assert_eq!((report.failed, report.examples), (2, vec!["欠席".to_string()]));
+    // Fractions are reported rather than truncated
+    let (_, report) = cast_column(&df, "ratio", CastTarget::Integer).unwrap();
+    assert_eq!(report.examples, vec!["2.5"]);
+    let (dates, report) = cast_column(&df!("day" => ["2024/04/01", "x"]).unwrap(), "day", CastTarget::Date).unwrap();
+    assert_eq!((dates.column("day").unwrap().dtype(), report.failed), (&DataType::Date, 1));
+
+    let mapping = vec![("1".to_string(), "男性".to_string()), ("2".to_string(), "女性".to_string())];
+    let recoded = recode(&df, "gender", &mapping, "性別").unwrap();
+    let labels: Vec<Option<&str>> = recoded.column("性別").unwrap().str().unwrap().into_iter().collect();
+    assert_eq!(labels, vec![Some("男性"), Some("女性"), Some("女性"), Some("男性"), Some("3")]);
+    assert_eq!(recoded.column("gender").unwrap().dtype(), &DataType::Int64);
+
+    let (merged, rare) = merge_rare(&recoded, "性別", 2, "その他").unwrap();
+    assert_eq!(rare, vec!["3"]);
+    assert_eq!(value_counts(&merged, "性別").unwrap().last().unwrap(), &("その他".to_string(), 1));
+    assert!(set_level_order(&recoded, "性別", &["女性".to_string(), "男性".to_string()]).is_err());
+}
+
+#[test]
+fn recoding_keeps_the_level_order() {
+    let df = df!("評価" => ["高", "中", "低", "中", "最低", "中"]).unwrap();
+    let levels: Vec<String> = ["最低", "低", "中", "高"].map(String::from).to_vec();
+    let ordered = set_level_order(&df, "評価", &levels).unwrap();
+
+    let mapping = vec![("高".to_string(), "上".to_string()), ("最低".to_string(), "低".to_string())];
+    let recoded = recode(&ordered, "評価", &mapping, "評価2").unwrap();
+    assert_eq!(level_order(recoded.column("評価2").unwrap()).unwrap(), ["低", "中", "上"]);
+    assert_eq!(value_counts(&recoded, "評価2").unwrap()[0], ("低".to_string(), 2));
+
+    let (merged, rare) = merge_rare(&ordered, "評価", 2, "その他").unwrap();
+    assert_eq!(rare, ["最低", "低", "高"]);
+    assert_eq!(level_order(merged.column("評価").unwrap()).unwrap(), ["中", "その他"]);
+
+    let duplicated: Vec<String> = ["最低", "低", "中", "低", "高"].map(String::from).to_vec();
+    let err = set_level_order(&df, "評価", &duplicated).unwrap_err().to_string();
+    assert!(err.contains("'低'"), "{}", err);
+    let empty: Vec<String> = ["最低", "低", " ", "中", "高"].map(String::from).to_vec();
+    assert!(set_level_order(&df, "評価", &empty).unwrap_err().to_string().contains("empty level"));
+}
+
+#[test]
+fn level_order_decides_group_order_in_analyses() {
+    let df = df!(
+        "group" => ["pre", "pre", "pre", "post", "post", "post"],
+        "class" => ["A", "B", "A", "B", "A", "B"],
+        "y" => [1.0f64, 2.0, 3.0, 5.0, 6.0, 8.0],
+    )
+    .unwrap();
+    assert_eq!(ttest::welch_from_df(&df, "y", "group").unwrap().groups[0].name, "post");
+
+    let ordered = set_level_order(&df, "group", &["pre".to_string(), "post".to_string()]).unwrap();
+    assert_eq!(value_counts(&ordered, "group").unwrap()[0].0, "pre");
+    let result = ttest::welch_from_df(&ordered, "y", "group").unwrap();
+    assert_eq!(result.groups[0].name, "pre");
+    let names: Vec<String> = anova::one_way_from_df(&ordered, "y", "group").unwrap().groups.into_iter().map(|g| g.name).collect();
+    assert_eq!(names, vec!["pre", "post"]);
+    // Series of the grouped bar chart follow the level order of factor B
+    let report = anova::two_way_from_df(&ordered, "y", "class", "group").unwrap().report();
+    let series: Vec<&str> = report.plots[0].data.as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(series, ["pre", "post"]);
+}
+
+#[test]
+fn computed_columns_follow_the_expression_language() {
+    let df = df!(
+        "q1" => [Some(1i64), Some(2), Some(3), None],
+        "q2" => [3i64, 4, 5, 6],
+        "q3" => [2i64, 3, 4, 5],
+        "年収 (万円)" => [100.0f64, 1000.0, 10.0, 1.0],
+        "score" => [59i64, 60, 80, 40],
+    )
+    .unwrap();
+    let eval = |source: &str| add_computed_column(&df, "new", source).unwrap();
+
+    // Integer columns divide to fractions; a missing item makes the row missing
+    assert_eq!(f64_values(&eval("(q1 + q2 + q3) / 3"), "new"), vec![Some(2.0), Some(3.0), Some(4.0), None]);
+    assert_eq!(f64_values(&eval("mean(q1, q2, q3)"), "new")[..3], [Some(2.0), Some(3.0), Some(4.0)]);
+    assert_eq!(f64_values(&eval("max(q1, q2) - min(q2, q3)"), "new")[0], Some(1.0));
+    let logs = f64_values(&eval("log10(`年収 (万円)`)"), "new");
+    assert!(logs.iter().zip([2.0, 3.0, 1.0, 0.0]).all(|(l, e)| (l.unwrap() - e).abs() < 1e-12));
+    assert_eq!(f64_values(&eval("-2^2 + q2 % 2"), "new")[0], Some(-3.0));
+    assert_eq!(f64_values(&eval("q2 - mean(q2)"), "new"), vec![Some(-1.5), Some(-0.5), Some(0.5), Some(1.5)]);
+    let z = f64_values(&eval("zscore(q2)"), "new");
+    assert!((z[3].unwrap() - 1.5 / 1.2909944487358056).abs() < 1e-12);
+
+    let passed = eval(r#"if(score >= 60 and not is_missing(q1), "pass", "fail")"#);
+    let labels: Vec<Option<&str>> = passed.column("new").unwrap().str().unwrap().into_iter().collect();
+    assert_eq!(labels, vec![Some("fail"), Some("pass"), Some("pass"), Some("fail")]);
+
+    let error = |source: &str| add_computed_column(&df, "new", source).unwrap_err().to_string();
+    assert!(error("q1 + q4").contains("Unknown column(s): q4"));
+    assert!(error("(q1 + q2").contains("expected ')'"));
+    assert!(error("q1 + * q2").contains("position 6"));
+    assert!(error("median(q1)").contains("Unknown function 'median'"));
+    assert!(error("if(q1 > 1, 2)").contains("if() takes 3 argument(s)"));
+    assert!(add_computed_column(&df, "q1", "q2").is_err());
+    assert_eq!(parse("英語 * 2").unwrap().columns(), vec!["英語"]);
+}
+
+#[test]
+fn scale_scores_reverse_items_and_limit_skipped_answers() {
+    let df = df!(
+        "q1" => [Some(5i64), Some(4), None, None],
+        "q2" => [Some(1i64), Some(2), Some(3), None],
+        "q3" => [Some(4i64), None, Some(5), Some(2)],
+    )
+    .unwrap();
+    let mut spec = ScaleSpec {
+        name: "自尊感情".into(),
+        items: vec!["q1".into(), "q2".into(), "q3".into()],
+        reversed: vec!["q2".into()],
+        min: 1.0,
+        max: 5.0,
+        method: ScoreMethod::Sum,
+        max_missing: 1,
+    };
+
+    // q2 reversed: 5, 4, 3; the second row is prorated from two answers
+    let (scored, report) = score_scale(&df, &spec).unwrap();
+    assert_eq!(f64_values(&scored, "自尊感情"), vec![Some(14.0), Some(12.0), Some(12.0), None]);
+    assert_eq!((report.scored, report.too_many_missing), (3, 1));
+
+    spec.method = ScoreMethod::Mean;
+    spec.max_missing = 0;
+    let (scored, _) = score_scale(&df, &spec).unwrap();
+    assert_eq!(f64_values(&scored, "自尊感情")[..2], [Some(14.0 / 3.0), None]);
+
+    spec.max = 4.0;
+    assert!(score_scale(&df, &spec).unwrap_err().to_string().contains("outside the scale range"));
+}
+
+#[test]
+fn row_filter_combines_conditions_without_changing_the_data() {
+    let df = df!(
+        "学年" => [1i64, 2, 2, 3],
+        "組" => [Some("A"), Some("B"), None, Some("AB")],
+        "日付" => ["2024/04/01", "2024/05/01", "2024/06/01", "2024/07/01"],
+    )
+    .unwrap();
+    let (df, _) = cast_column(&df, "日付", CastTarget::Date).unwrap();
+    let condition = |column: &str, op, value: &str| Condition { column: column.into(), op, value: value.into() };
+
+    let mut filter = RowFilter { conditions: vec![condition("学年", FilterOp::Eq, "2"), condition("組", FilterOp::NotMissing, "")], all: true };
+    assert_eq!(filter.apply(&df).unwrap().height(), 1);
+    filter.all = false;
+    filter.conditions[1] = condition("組", FilterOp::Eq, "A");
+    assert_eq!(filter.apply(&df).unwrap().height(), 3);
+    assert_eq!(filter.describe(), "学年 = 2 OR 組 = A");
+
+    let contains = RowFilter { conditions: vec![condition("組", FilterOp::Contains, "A")], all: true };
+    assert_eq!(contains.apply(&df).unwrap().height(), 2);
+    let dates = RowFilter { conditions: vec![condition("日付", FilterOp::GtEq, "2024-06-01")], all: true };
+    assert_eq!(dates.apply(&df).unwrap().height(), 2);
+    assert_eq!(df.height(), 4);
+
+    assert!(RowFilter { conditions: vec![condition("学年", FilterOp::Gt, "二")], all: true }.apply(&df).is_err());
+    assert!(RowFilter { conditions: vec![condition("性別", FilterOp::Eq, "1")], all: true }.apply(&df).is_err());
+}
+
+#[test]
+fn outliers_are_flagged_and_treated_with_a_change_log() {
+    let df = df!(
+        "身長" => [160i64, 162, 158, 161, 159, 163, 157, 190],
+        "体重" => [Some(55.0), Some(57.0), Some(53.0), Some(56.0), None, Some(58.0), Some(52.0), Some(60.0)],
+    )
+    .unwrap();
+    let height = ["身長".to_string()];
+
+    let iqr = detect_outliers(&df, &height, OutlierMethod::Iqr(1.5)).unwrap();
+    assert_eq!(iqr.rows(), vec![7]);
+    assert_eq!((iqr.bounds[0].lower, iqr.bounds[0].upper), (153.5, 167.5));
+    assert_eq!(detect_outliers(&df, &height, OutlierMethod::ModifiedZ(3.5)).unwrap().rows(), vec![7]);
+    // One extreme value inflates the SD, so z = 3 misses it in a small sample
+    assert!(detect_outliers(&df, &height, OutlierMethod::ZScore(3.0)).unwrap().flags.is_empty());
+    assert_eq!(detect_outliers(&df, &height, OutlierMethod::ZScore(2.0)).unwrap().rows(), vec![7]);
+
+    let shown = flagged_rows(&df, &iqr).unwrap();
+    assert_eq!(shown.get_column_names(), ["row", "flagged", "身長", "体重"]);
+    assert_eq!(shown.column("row").unwrap().u32().unwrap().get(0), Some(8));
+
+    let (removed, changes) = treat_outliers(&df, &iqr, OutlierTreatment::Remove).unwrap();
+    assert_eq!((removed.height(), changes.len()), (7, 1));
+    let (winsorized, changes) = treat_outliers(&df, &iqr, OutlierTreatment::Winsorize).unwrap();
+    assert_eq!(f64_values(&winsorized, "身長")[7], Some(167.5));
+    assert_eq!((changes[0].row, changes[0].before, changes[0].after), (8, 190.0, Some(167.5)));
+    let (blanked, changes) = treat_outliers(&df, &iqr, OutlierTreatment::SetNull).unwrap();
+    assert_eq!(blanked.column("身長").unwrap().dtype(), &DataType::Int64);
+    assert_eq!(blanked.column("身長").unwrap().null_count(), 1);
+    let log = change_log(&changes);
+    assert_eq!(log.get_column_names(), ["row", "column", "before", "after"]);
+    assert_eq!(log.column("after").unwrap().null_count(), 1);
+
+    // The last pupil is neither short nor light, only light for their height
+    let body = df!(
+        "身長" => [150.0, 152.0, 154.0, 156.0, 158.0, 160.0, 162.0, 164.0, 166.0, 168.0, 170.0, 166.0],
+        "体重" => [45.0, 48.0, 49.0, 51.0, 52.0, 55.0, 57.0, 58.0, 61.0, 62.0, 65.0, 47.0],
+    )
+    .unwrap();
+    let both = ["身長".to_string(), "体重".to_string()];
+    assert!(detect_outliers(&body, &both, OutlierMethod::Iqr(1.5)).unwrap().flags.is_empty());
+    let multivariate = detect_outliers(&body, &both, OutlierMethod::Mahalanobis(0.01)).unwrap();
+    assert_eq!(multivariate.rows(), vec![11]);
+    assert_eq!(multivariate.flags.len(), 2);
+    assert!(treat_outliers(&body, &multivariate, OutlierTreatment::Winsorize).is_err());
+    assert!(detect_outliers(&df, &height, OutlierMethod::Mahalanobis(0.05)).is_err());
+}
+
+#[test]
+fn melt_and_pivot_convert_between_wide_and_long() {
+    let wide = df!(
+        "id" => [1i64, 2, 3],
+        "前" => [10.0, 12.0, 9.0],
+        "後" => [14.0, 13.0, 11.0],
+    )
+    .unwrap();
+    let melted = melt(&wide, &MeltOptions {
+        id_columns: vec!["id".into()],
+        value_columns: vec!["前".into(), "後".into()],
+        variable_name: "時期".into(),
+        value_name: "得点".into(),
+    })
+    .unwrap();
+    assert_eq!(melted.get_column_names(), ["id", "時期", "得点"]);
+    assert_eq!(f64_values(&melted, "id"), vec![Some(1.0), Some(1.0), Some(2.0), Some(2.0), Some(3.0), Some(3.0)]);
+    assert_eq!(f64_values(&melted, "得点")[..2], [Some(10.0), Some(14.0)]);
+    assert_eq!(level_order(melted.column("時期").unwrap()), Some(vec!["前".to_string(), "後".to_string()]));
+
+    // Back to wide, with a missing time point for id 3
+    let long = melted.slice(0, 5);
+    let options = PivotOptions { id_columns: vec!["id".into()], names_from: "時期".into(), values_from: "得点".into() };
+    let back = pivot(&long, &options).unwrap();
+    assert_eq!(back.get_column_names(), ["id", "前", "後"]);
+    assert_eq!(f64_values(&back, "後"), vec![Some(14.0), Some(13.0), None]);
+    assert!(back.slice(0, 2).equals(&wide.slice(0, 2)));
+
+    let repeated = df!("id" => [1i64, 1], "時期" => ["前", "前"], "得点" => [1.0, 2.0]).unwrap();
+    assert!(pivot(&repeated, &options).unwrap_err().to_string().contains("more than one row"));
+    let clash = MeltOptions { id_columns: vec!["id".into()], value_columns: vec!["前".into()], variable_name: "id".into(), value_name: "得点".into() };
+    assert!(melt(&wide, &clash).is_err());
+}
+
+#[test]
+fn recipes_round_trip_through_json_and_replay_on_new_data() {
+    let steps = vec![
+        Step::Filter { filter: RowFilter { conditions: vec![Condition { column: "学年".into(), op: FilterOp::Gt, value: "1".into() }], all: true } },
+        Step::Missing { action: MissingAction::Impute(vec!["得点".into()], ImputeMethod::Median) },
+        Step::Recode { column: "組".into(), mapping: vec![("A".into(), "一組".into())], into: "組名".into() },
+        Step::Compute { name: "倍".into(), expression: "得点 * 2".into() },
+        Step::Outliers { columns: vec!["得点".into()], method: OutlierMethod::Iqr(1.5), treatment: OutlierTreatment::SetNull },
+    ];
+    let json = recipe_to_json(&steps).unwrap();
+    assert!(json.contains("\"step\": \"missing\""));
+    assert_eq!(recipe_from_json(&json).unwrap(), steps);
+
+    // Next year's upload has other rows but the same columns
+    let next_year = df!(
+        "学年" => [1i64, 2, 2, 3, 3],
+        "組" => ["A", "A", "B", "B", "A"],
+        "得点" => [Some(50.0), None, Some(60.0), Some(70.0), Some(64.0)],
+    )
+    .unwrap();
+    let frames = replay(&next_year, &steps).unwrap();
+    assert_eq!(frames.len(), steps.len());
+    let done = frames.last().unwrap();
+    assert_eq!(done.height(), 4);
+    assert_eq!(f64_values(done, "倍"), vec![Some(128.0), Some(120.0), Some(140.0), Some(128.0)]);
+    assert_eq!(done.column("組名").unwrap().str().unwrap().get(0), Some("一組"));
+
+    let error = replay(&next_year.drop("組").unwrap(), &steps).unwrap_err().to_string();
+    assert!(error.starts_with("Step 3 ("), "{}", error);
+    assert!(recipe_from_json(r#"{"version": 99, "steps": []}"#).is_err());
+    assert!(recipe_from_json("not json").is_err());
+}
+
+#[test]
+fn duplicates_are_grouped_and_one_row_per_group_is_kept() {
+    let df = df!(
+        "学籍番号" => [Some(1i64), Some(2), Some(1), Some(3), Some(1), None, None],
+        "回答" => [Some("a"), Some("b"), None, Some("c"), Some("a"), Some("d"), Some("d")],
+        "点" => [Some(1.0), Some(2.0), Some(3.0), Some(4.0), Some(1.0), None, None],
+    )
+    .unwrap();
+    let id = ["学籍番号".to_string()];
+
+    let groups = duplicate_groups(&df, &id).unwrap();
+    assert_eq!(groups.get_column_names()[..2], ["group", "row"]);
+    assert_eq!(groups.column("row").unwrap().u32().unwrap().into_no_null_iter().collect::<Vec<_>>(), vec![1, 3, 5]);
+
+    let (first, report) = deduplicate(&df, &id, KeepRule::First).unwrap();
+    assert_eq!((report.groups, report.dropped, first.height()), (1, 2, 5));
+    let (last, _) = deduplicate(&df, &id, KeepRule::Last).unwrap();
+    assert_eq!(f64_values(&last, "点"), vec![Some(2.0), Some(4.0), Some(1.0), None, None]);
+    // Rows 1 and 5 are complete; the first of them stays
+    let (complete, _) = deduplicate(&df, &id, KeepRule::MostComplete).unwrap();
+    assert_eq!(f64_values(&complete, "点")[0], Some(1.0));
+
+    // Whole rows: rows 1 and 5 repeat, and so do the two rows without an id
+    let (whole, report) = deduplicate(&df, &[], KeepRule::First).unwrap();
+    assert_eq!((report.groups, report.dropped, whole.height()), (2, 2, 5));
+    assert!(duplicate_groups(&df, &["学年".to_string()]).is_err());
+}
+
+#[test]
+fn binning_makes_an_ordered_categorical_column() {
+    let df = df!("得点" => [Some(0.0), Some(10.0), Some(20.0), Some(30.0), Some(40.0), Some(50.0), Some(60.0), Some(100.0), None]).unwrap();
+    let spec = |method| BinSpec { column: "得点".into(), method, into: "群".into(), labels: Vec::new(), descending: false };
+    let text = |df: &DataFrame| df.column("群").unwrap().cast(&DataType::String).unwrap().str().unwrap().into_iter().map(|v| v.map(str::to_string)).collect::<Vec<_>>();
+
+    let (binned, report) = bin_column(&df, &spec(BinMethod::EqualWidth(2))).unwrap();
+    assert_eq!(report.labels, ["[0, 50)", "[50, 100]"]);
+    assert_eq!(report.counts, [5, 3]);
+    assert_eq!(level_order(binned.column("群").unwrap()).unwrap(), report.labels);
+    assert_eq!(text(&binned)[8], None);
+
+    let (_, report) = bin_column(&df, &spec(BinMethod::Quantiles(4))).unwrap();
+    assert_eq!(report.counts, [2, 2, 2, 2]);
+
+    let mut custom = spec(BinMethod::Breaks(vec![60.0, 0.0, 40.0]));
+    custom.labels = vec!["低".into(), "高".into()];
+    custom.descending = true;
+    let (binned, report) = bin_column(&df, &custom).unwrap();
+    assert_eq!((report.counts.clone(), report.outside), (vec![4, 3], 1));
+    assert_eq!(level_order(binned.column("群").unwrap()).unwrap(), ["高", "低"]);
+    assert_eq!(text(&binned)[7], None);
+
+    // The median (35) and below form the low group
+    let (binned, _) = bin_column(&df, &spec(BinMethod::MedianSplit)).unwrap();
+    assert_eq!(text(&binned)[3..5], [Some("低群".to_string()), Some("高群".to_string())]);
+
+    custom.labels.push("特".into());
+    assert!(bin_column(&df, &custom).unwrap_err().to_string().contains("3 labels given for 2 bins"));
+    assert!(bin_column(&df, &spec(BinMethod::EqualWidth(1))).is_err());
+
+    // Neither the binned column nor another existing column is overwritten
+    let mut onto_source = spec(BinMethod::MedianSplit);
+    onto_source.into = "得点".into();
+    assert!(bin_column(&df, &onto_source).unwrap_err().to_string().contains("Column '得点' already exists"));
+    let with_group = df.hstack(&[Series::new("群", [1i64; 9])]).unwrap();
+    assert!(bin_column(&with_group, &spec(BinMethod::MedianSplit)).unwrap_err().to_string().contains("Column '群' already exists"));
+}